@@ -1,4 +1,10 @@
-use std::{convert::Infallible, marker::PhantomData};
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use tokio::sync::mpsc::{
     self,
@@ -68,3 +74,125 @@ where
         }
     }
 }
+
+/// Keeps track of a receiver handed out to a task, so the values still queued
+/// in it can be recovered once the task exits.
+pub struct Reclaimer<T> {
+    slot: Arc<Mutex<Option<mpsc::UnboundedReceiver<T>>>>,
+}
+
+impl<T> Reclaimer<T> {
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            slot: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn wrap(
+        &self,
+        receiver: mpsc::UnboundedReceiver<T>,
+    ) -> ReclaimableReceiver<T> {
+        ReclaimableReceiver {
+            receiver,
+            slot: self.slot.clone(),
+        }
+    }
+
+    /// Moves the values left in the last reclaimed receiver, if any, into
+    /// the given sender, preserving their order.
+    ///
+    /// Returns the number of values handed off.
+    pub fn drain_into(&self, sender: &mpsc::UnboundedSender<T>) -> usize {
+        let Some(mut receiver) = self
+            .slot
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        else {
+            return 0;
+        };
+
+        let mut count = 0;
+
+        while let Ok(value) = receiver.try_recv() {
+            if sender.send(value).is_err() {
+                break;
+            }
+
+            count += 1;
+        }
+
+        count
+    }
+}
+
+impl<T> Default for Reclaimer<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Receiver which hands itself back to its [`Reclaimer`] when dropped.
+pub struct ReclaimableReceiver<T> {
+    receiver: mpsc::UnboundedReceiver<T>,
+    slot: Arc<Mutex<Option<mpsc::UnboundedReceiver<T>>>>,
+}
+
+impl<T> Deref for ReclaimableReceiver<T> {
+    type Target = mpsc::UnboundedReceiver<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl<T> DerefMut for ReclaimableReceiver<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.receiver
+    }
+}
+
+impl<T> Drop for ReclaimableReceiver<T> {
+    fn drop(&mut self) {
+        let (_, closed) = mpsc::unbounded_channel();
+
+        *self.slot.lock().unwrap_or_else(PoisonError::into_inner) =
+            Some(mem::replace(&mut self.receiver, closed));
+    }
+}
+
+#[tokio::test]
+async fn test_queued_values_survive_receiver_restart() {
+    let reclaimer = Reclaimer::new();
+
+    let (old_sender, receiver) = mpsc::unbounded_channel();
+
+    let mut receiver = reclaimer.wrap(receiver);
+
+    for value in 1..=5 {
+        old_sender.send(value).unwrap();
+    }
+
+    assert_eq!(receiver.recv().await, Some(1));
+
+    drop(receiver);
+
+    let (new_sender, mut receiver) = mpsc::unbounded_channel();
+
+    assert_eq!(reclaimer.drain_into(&new_sender), 4);
+
+    assert_eq!(reclaimer.drain_into(&new_sender), 0);
+
+    new_sender.send(6).unwrap();
+
+    for expected in 2..=6 {
+        assert_eq!(receiver.recv().await, Some(expected));
+    }
+}
@@ -0,0 +1,152 @@
+use std::{future::Future, time::Duration};
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::node;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "clock-skew",
+            $($body)+
+        )
+    };
+}
+
+pub trait LatestBlockTime: Send {
+    fn latest_block_time(
+        &mut self,
+    ) -> impl Future<Output = Result<DateTime<Utc>>> + Send + '_;
+}
+
+impl LatestBlockTime for node::QueryTendermint {
+    #[inline]
+    fn latest_block_time(
+        &mut self,
+    ) -> impl Future<Output = Result<DateTime<Utc>>> + Send + '_ {
+        self.get_latest_block_time()
+    }
+}
+
+/// Compares the local system time against the chain's latest block time,
+/// warning when the difference exceeds the tolerance, as signing
+/// time-sensitive transactions with a skewed clock leads to premature
+/// expirations.
+///
+/// Returns the measured difference, or `None` when the latest block's time
+/// couldn't be fetched, in which case a warning is logged instead, as the
+/// check is not worth aborting the startup over.
+pub async fn check<C>(
+    client: &mut C,
+    local_time: DateTime<Utc>,
+    tolerance: Duration,
+) -> Option<Duration>
+where
+    C: LatestBlockTime + ?Sized,
+{
+    let block_time = match client.latest_block_time().await {
+        Ok(block_time) => block_time,
+        Err(error) => {
+            log!(warn!(
+                ?error,
+                "Failed to fetch latest block's time! Skipping clock skew \
+                check.",
+            ));
+
+            return None;
+        },
+    };
+
+    let skew = (local_time - block_time)
+        .abs()
+        .to_std()
+        .unwrap_or(Duration::MAX);
+
+    let local_time = local_time.to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    let block_time = block_time.to_rfc3339_opts(SecondsFormat::Millis, true);
+
+    if skew > tolerance {
+        log!(warn!(
+            %local_time,
+            %block_time,
+            ?skew,
+            ?tolerance,
+            "Local clock differs from the chain's latest block time more \
+            than tolerated!",
+        ));
+    } else {
+        log!(info!(
+            %local_time,
+            %block_time,
+            ?skew,
+            "Local clock is in line with the chain's latest block time.",
+        ));
+    }
+
+    Some(skew)
+}
+
+#[tokio::test]
+async fn test_skew_warning_threshold() {
+    use tracing::{subscriber, Level};
+
+    use crate::testing::LogCapture;
+
+    struct Stub(Option<DateTime<Utc>>);
+
+    impl LatestBlockTime for Stub {
+        async fn latest_block_time(&mut self) -> Result<DateTime<Utc>> {
+            self.0.ok_or_else(|| anyhow::anyhow!("Node unavailable!"))
+        }
+    }
+
+    let capture = LogCapture::leak();
+
+    let _guard = subscriber::set_default(capture);
+
+    let local_time = DateTime::from_timestamp(1_700_000_045, 0).unwrap();
+
+    let mut stub = Stub(DateTime::from_timestamp(1_700_000_000, 0));
+
+    let skew = check(&mut stub, local_time, Duration::from_secs(60))
+        .await
+        .unwrap();
+
+    assert_eq!(skew, Duration::from_secs(45));
+
+    assert_eq!(capture.count(Level::WARN), 0);
+
+    _ = check(&mut stub, local_time, Duration::from_secs(45))
+        .await
+        .unwrap();
+
+    assert_eq!(capture.count(Level::WARN), 0);
+
+    _ = check(&mut stub, local_time, Duration::from_secs(44))
+        .await
+        .unwrap();
+
+    assert_eq!(capture.count(Level::WARN), 1);
+
+    stub.0 = DateTime::from_timestamp(1_700_000_090, 0);
+
+    assert_eq!(
+        check(&mut stub, local_time, Duration::from_secs(30))
+            .await
+            .unwrap(),
+        Duration::from_secs(45),
+    );
+
+    assert_eq!(capture.count(Level::WARN), 2);
+
+    stub.0 = None;
+
+    assert_eq!(
+        check(&mut stub, local_time, Duration::from_secs(30)).await,
+        None,
+    );
+
+    assert_eq!(capture.count(Level::WARN), 3);
+}
@@ -20,6 +20,12 @@ impl Admin {
         }
     }
 
+    #[inline]
+    #[must_use]
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
     pub async fn platform(&mut self) -> Result<Platform> {
         const QUERY_MSG: &[u8; 15] = br#"{"platform":{}}"#;
 
@@ -87,7 +93,7 @@ pub struct Protocol {
     pub contracts: ProtocolContracts,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
 #[serde(
     rename_all = "PascalCase",
     rename_all_fields = "snake_case",
@@ -0,0 +1,30 @@
+use anyhow::{Context as _, Result};
+
+use crate::env::ReadFromVar;
+
+/// How a failed startup check is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enforcement {
+    /// Fails the startup, or the task's creation, which ran the check.
+    Strict,
+    /// Only logs a warning, e.g. while the account is still being set up.
+    Relaxed,
+}
+
+impl Enforcement {
+    /// Reads whether the check is relaxed from a boolean variable, being
+    /// strict when the variable is not set.
+    pub fn read_relaxed_from_var(variable: &str) -> Result<Self> {
+        Option::<bool>::read_from_var(variable)
+            .map(|relaxed| {
+                if relaxed.unwrap_or(false) {
+                    Self::Relaxed
+                } else {
+                    Self::Strict
+                }
+            })
+            .with_context(|| {
+                format!(r#"Failed to read whether "{variable}" is set!"#)
+            })
+    }
+}
@@ -3,7 +3,8 @@ use std::{
     env,
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8,
-        NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8,
+        NonZeroIsize, NonZeroU128, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8, NonZeroUsize,
     },
 };
 
@@ -28,6 +29,33 @@ impl ReadFromVar for String {
     }
 }
 
+impl ReadFromVar for bool {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        String::read_from_var(variable).map(|value| {
+            const { ["1", "Y", "y", "yes", "true"] }.contains(&value.as_str())
+        })
+    }
+}
+
+impl<T> ReadFromVar for Option<T>
+where
+    T: ReadFromVar,
+{
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        if env::var_os(variable.borrow()).is_some() {
+            T::read_from_var(variable).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 macro_rules! impl_for_parseable {
     ($($type: ty),+ $(,)?) => {
         $(
@@ -77,4 +105,8 @@ impl_for_parseable![
     NonZeroI128,
     u128,
     NonZeroU128,
+    isize,
+    NonZeroIsize,
+    usize,
+    NonZeroUsize,
 ];
@@ -0,0 +1,161 @@
+use std::future::Future;
+
+use anyhow::{bail, Context as _, Result};
+
+use crate::{enforcement::Enforcement, node};
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "fee-balance",
+            $($body)+
+        )
+    };
+}
+
+pub trait Balance: Send {
+    fn balance(
+        &mut self,
+        address: String,
+        denom: String,
+    ) -> impl Future<Output = Result<u128>> + Send + '_;
+}
+
+impl Balance for node::QueryBank {
+    #[inline]
+    fn balance(
+        &mut self,
+        address: String,
+        denom: String,
+    ) -> impl Future<Output = Result<u128>> + Send + '_ {
+        node::QueryBank::balance(self, address, denom)
+    }
+}
+
+/// Checks that the signer's account holds some of the fee token, so an
+/// unfunded account is reported upfront instead of through failing
+/// broadcasts.
+///
+/// When relaxed, neither an unfunded account nor a failed balance query
+/// fail the check, only being logged instead.
+///
+/// Returns the queried balance, or `None` when the query failed.
+pub async fn check<C>(
+    client: &mut C,
+    address: &str,
+    fee_token: &str,
+    enforcement: Enforcement,
+) -> Result<Option<u128>>
+where
+    C: Balance + ?Sized,
+{
+    let balance = match client
+        .balance(address.into(), fee_token.into())
+        .await
+        .context("Failed to query signer's fee token balance!")
+    {
+        Ok(balance) => balance,
+        Err(error) => {
+            return match enforcement {
+                Enforcement::Strict => Err(error),
+                Enforcement::Relaxed => {
+                    log!(warn!(
+                        ?error,
+                        "Skipping fee token balance check, as it's relaxed, \
+                        because the balance couldn't be queried!",
+                    ));
+
+                    Ok(None)
+                },
+            };
+        },
+    };
+
+    if balance != 0 {
+        log!(info!(
+            %address,
+            %fee_token,
+            balance,
+            "Signer holds fee token.",
+        ));
+    } else {
+        match enforcement {
+            Enforcement::Strict => bail!(
+                "Signer doesn't hold any of the fee token! Address={address}; \
+                Denom={fee_token}; Balance={balance}",
+            ),
+            Enforcement::Relaxed => log!(warn!(
+                %address,
+                %fee_token,
+                balance,
+                "Signer doesn't hold any of the fee token! Transactions will \
+                fail to broadcast until the account is funded.",
+            )),
+        }
+    }
+
+    Ok(Some(balance))
+}
+
+#[tokio::test]
+async fn test_zero_and_non_zero_fee_token_balance() {
+    const ADDRESS: &str = "nolus1signer";
+
+    const FEE_TOKEN: &str = "unls";
+
+    struct Stub(Option<u128>);
+
+    impl Balance for Stub {
+        async fn balance(
+            &mut self,
+            address: String,
+            denom: String,
+        ) -> Result<u128> {
+            assert_eq!(address, ADDRESS);
+
+            assert_eq!(denom, FEE_TOKEN);
+
+            self.0.context("Node unavailable!")
+        }
+    }
+
+    let mut stub = Stub(Some(2_500_000));
+
+    assert_eq!(
+        check(&mut stub, ADDRESS, FEE_TOKEN, Enforcement::Strict)
+            .await
+            .unwrap(),
+        Some(2_500_000),
+    );
+
+    stub.0 = Some(0);
+
+    let error = check(&mut stub, ADDRESS, FEE_TOKEN, Enforcement::Strict)
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.contains(ADDRESS));
+
+    assert!(error.contains(FEE_TOKEN));
+
+    assert_eq!(
+        check(&mut stub, ADDRESS, FEE_TOKEN, Enforcement::Relaxed)
+            .await
+            .unwrap(),
+        Some(0),
+    );
+
+    stub.0 = None;
+
+    assert!(check(&mut stub, ADDRESS, FEE_TOKEN, Enforcement::Strict)
+        .await
+        .is_err());
+
+    assert_eq!(
+        check(&mut stub, ADDRESS, FEE_TOKEN, Enforcement::Relaxed)
+            .await
+            .unwrap(),
+        None,
+    );
+}
@@ -0,0 +1,64 @@
+use std::any::type_name;
+
+use anyhow::{Context as _, Result};
+use serde::de::DeserializeOwned;
+
+/// Maximum number of bytes of the offending payload included in the context
+/// of deserialization errors.
+const PAYLOAD_SNIPPET_LENGTH: usize = 256;
+
+/// Deserializes JSON data, attaching the target type's name and a truncated
+/// snippet of the payload to the error on failure.
+pub fn from_slice<T>(data: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json_wasm::from_slice(data)
+        .with_context(|| {
+            let snippet = String::from_utf8_lossy(
+                &data[..data.len().min(PAYLOAD_SNIPPET_LENGTH)],
+            );
+
+            if data.len() > PAYLOAD_SNIPPET_LENGTH {
+                format!(
+                    "Payload ({} bytes, truncated): {snippet}...",
+                    data.len(),
+                )
+            } else {
+                format!("Payload: {snippet}")
+            }
+        })
+        .with_context(|| {
+            format!(
+                r#"Failed to deserialize JSON into "{}"!"#,
+                type_name::<T>()
+            )
+        })
+}
+
+#[test]
+fn test_error_context() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Currency {
+        #[serde(rename = "ticker")]
+        _ticker: String,
+    }
+
+    let error = from_slice::<Currency>(br#"{"ticker":42}"#).unwrap_err();
+
+    let message = format!("{error:#}");
+
+    assert!(message.contains("Currency"), "{message}");
+
+    assert!(message.contains(r#"{"ticker":42}"#), "{message}");
+
+    let oversized = format!(r#"{{"ticker":"{}"#, "A".repeat(1024));
+
+    let error = from_slice::<Currency>(oversized.as_bytes()).unwrap_err();
+
+    let message = format!("{error:#}");
+
+    assert!(message.contains("truncated"), "{message}");
+
+    assert!(!message.contains(&oversized), "{message}");
+}
@@ -1,5 +1,12 @@
+use std::fs;
+
 use anyhow::{Context as _, Result};
+#[cfg(feature = "keyring")]
+use anyhow::bail;
 use bip32::{Language, Mnemonic};
+use zeroize::Zeroizing;
+
+use crate::env::ReadFromVar;
 
 pub type Signing = cosmrs::crypto::secp256k1::SigningKey;
 
@@ -24,3 +31,132 @@ where
                 })
         })
 }
+
+/// Reads a mnemonic from the file whose path is stored in `{variable}_FILE`
+/// when set, falling back to reading it directly from `variable` otherwise.
+///
+/// When the `keyring` feature is enabled, the mnemonic is first looked up in
+/// the system's keyring under the service and account names stored in
+/// `{variable}_KEYRING_SERVICE` and `{variable}_KEYRING_ACCOUNT`, when set.
+pub fn read_mnemonic(variable: &str) -> Result<Zeroizing<String>> {
+    #[cfg(feature = "keyring")]
+    if let Some(mnemonic) = read_mnemonic_from_keyring(variable)? {
+        return Ok(mnemonic);
+    }
+
+    let file_variable = format!("{variable}_FILE");
+
+    let Some(path) = Option::<String>::read_from_var(file_variable.as_str())
+        .context("Failed to read mnemonic file's path!")?
+    else {
+        return String::read_from_var(variable).map(Zeroizing::new);
+    };
+
+    let mut mnemonic = fs::read_to_string(&path)
+        .map(Zeroizing::new)
+        .with_context(|| {
+            format!(r#"Failed to read mnemonic file "{path}"!"#)
+        })?;
+
+    let trimmed_length = mnemonic.trim_end().len();
+
+    mnemonic.truncate(trimmed_length);
+
+    Ok(mnemonic)
+}
+
+#[cfg(feature = "keyring")]
+fn read_mnemonic_from_keyring(
+    variable: &str,
+) -> Result<Option<Zeroizing<String>>> {
+    let service =
+        Option::<String>::read_from_var(format!("{variable}_KEYRING_SERVICE"))
+            .context("Failed to read keyring entry's service name!")?;
+
+    let Some(service) = service else {
+        return Ok(None);
+    };
+
+    let account = String::read_from_var(format!("{variable}_KEYRING_ACCOUNT"))
+        .context("Failed to read keyring entry's account name!")?;
+
+    keyring::Entry::new(&service, &account)
+        .context("Failed to construct keyring entry!")
+        .and_then(|entry| read_keyring_entry(&entry))
+        .with_context(|| {
+            format!(
+                "Failed to read mnemonic from keyring entry with service \
+                \"{service}\" and account \"{account}\"!"
+            )
+        })
+        .map(Some)
+}
+
+#[cfg(feature = "keyring")]
+fn read_keyring_entry(entry: &keyring::Entry) -> Result<Zeroizing<String>> {
+    match entry.get_password() {
+        Ok(secret) => Ok(Zeroizing::new(secret)),
+        Err(keyring::Error::NoEntry) => {
+            bail!("Keyring doesn't contain an entry for the mnemonic!")
+        },
+        Err(error) => {
+            Err(error).context("Failed to retrieve secret from keyring entry!")
+        },
+    }
+}
+
+#[cfg(test)]
+const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon \
+    abandon abandon abandon abandon abandon about";
+
+#[test]
+fn test_mnemonic_from_env() {
+    std::env::set_var("TEST_MNEMONIC_FROM_ENV", TEST_MNEMONIC);
+
+    let mnemonic = read_mnemonic("TEST_MNEMONIC_FROM_ENV").unwrap();
+
+    assert_eq!(*mnemonic, TEST_MNEMONIC);
+
+    derive_from_mnemonic(&mnemonic, "").unwrap();
+}
+
+#[test]
+fn test_mnemonic_from_file() {
+    let path = std::env::temp_dir().join(format!(
+        "oracle-price-feeder-test-mnemonic-{}",
+        std::process::id(),
+    ));
+
+    fs::write(&path, format!("{TEST_MNEMONIC}\n")).unwrap();
+
+    std::env::set_var("TEST_MNEMONIC_FROM_FILE", "not a mnemonic");
+
+    std::env::set_var("TEST_MNEMONIC_FROM_FILE_FILE", &path);
+
+    let result = read_mnemonic("TEST_MNEMONIC_FROM_FILE");
+
+    fs::remove_file(&path).unwrap();
+
+    let mnemonic = result.unwrap();
+
+    assert_eq!(*mnemonic, TEST_MNEMONIC);
+
+    derive_from_mnemonic(&mnemonic, "").unwrap();
+}
+
+#[cfg(feature = "keyring")]
+#[test]
+fn test_mnemonic_from_mock_keyring() {
+    keyring::set_default_credential_builder(
+        keyring::mock::default_credential_builder(),
+    );
+
+    let entry =
+        keyring::Entry::new("oracle-price-feeder", "test-mnemonic").unwrap();
+
+    assert!(read_keyring_entry(&entry).is_err());
+
+    entry.set_password(TEST_MNEMONIC).unwrap();
+
+    assert_eq!(*read_keyring_entry(&entry).unwrap(), TEST_MNEMONIC);
+}
@@ -3,9 +3,13 @@
 #![allow(clippy::missing_errors_doc)]
 
 pub mod channel;
+pub mod clock_skew;
 pub mod contract;
 pub mod defer;
+pub mod enforcement;
 pub mod env;
+pub mod fee_balance;
+pub mod json;
 pub mod key;
 pub mod log;
 mod macros;
@@ -16,4 +20,6 @@ pub mod signer;
 pub mod supervisor;
 pub mod task;
 pub mod task_set;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod tx;
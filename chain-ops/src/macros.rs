@@ -20,3 +20,150 @@ macro_rules! run_app {
         }
     };
 }
+
+/// Logs a transaction's response with the same set of structured fields
+/// across all services, so log pipelines can parse them uniformly.
+///
+/// The raw log and the outcome's category are only included when the
+/// transaction failed, in which case the response is logged with the given
+/// level. Callers which report the failure themselves, e.g. with a more
+/// specific message, should pick a low level to avoid logging it twice.
+#[macro_export]
+macro_rules! log_tx_response {
+    (
+        target: $target:literal,
+        stage: $stage:literal,
+        source: $source:expr,
+        failure: $failure:ident,
+        $response:expr $(,)?
+    ) => {{
+        let response = &$response;
+
+        if response.code == 0 {
+            ::tracing::info!(
+                target: $target,
+                source = %$source,
+                stage = $stage,
+                hash = %response.txhash,
+                code = response.code,
+                gas_wanted = response.gas_wanted,
+                gas_used = response.gas_used,
+                "Transaction succeeded.",
+            );
+        } else {
+            ::tracing::$failure!(
+                target: $target,
+                source = %$source,
+                stage = $stage,
+                hash = %response.txhash,
+                code = response.code,
+                codespace = %response.codespace,
+                category = %$crate::tx::OutcomeCategory::of(response),
+                gas_wanted = response.gas_wanted,
+                gas_used = response.gas_used,
+                raw_log = %response.raw_log,
+                "Transaction failed!",
+            );
+        }
+    }};
+}
+
+#[test]
+fn test_log_tx_response_fields() {
+    use cosmrs::proto::cosmos::base::abci::v1beta1::TxResponse;
+    use tracing::{subscriber, Level};
+
+    use crate::testing::LogCapture;
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        log_tx_response!(
+            target: "test",
+            stage: "delivered",
+            source: "Time Alarms",
+            failure: error,
+            TxResponse {
+                txhash: "AB12".into(),
+                gas_wanted: 200_000,
+                gas_used: 150_000,
+                raw_log: "[]".into(),
+                ..TxResponse::default()
+            },
+        );
+
+        log_tx_response!(
+            target: "test",
+            stage: "broadcast",
+            source: "Time Alarms",
+            failure: error,
+            TxResponse {
+                txhash: "CD34".into(),
+                codespace: "sdk".into(),
+                code: 13,
+                gas_wanted: 200_000,
+                raw_log: "insufficient fees".into(),
+                ..TxResponse::default()
+            },
+        );
+    });
+
+    let events = capture.events();
+
+    let (level, fields) = (events[0].level, &events[0].fields);
+
+    assert_eq!(level, Level::INFO);
+
+    assert_eq!(fields["hash"], "AB12");
+
+    assert_eq!(fields["code"], "0");
+
+    assert_eq!(fields["gas_wanted"], "200000");
+
+    assert_eq!(fields["gas_used"], "150000");
+
+    assert_eq!(fields["stage"], "delivered");
+
+    assert!(!fields.contains_key("raw_log"));
+
+    let (level, fields) = (events[1].level, &events[1].fields);
+
+    assert_eq!(level, Level::ERROR);
+
+    assert_eq!(fields["hash"], "CD34");
+
+    assert_eq!(fields["code"], "13");
+
+    assert_eq!(fields["codespace"], "sdk");
+
+    assert_eq!(fields["raw_log"], "insufficient fees");
+
+    assert_eq!(fields["category"], "insufficient_fee");
+
+    assert_eq!(events.len(), 2);
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        log_tx_response!(
+            target: "test",
+            stage: "delivered",
+            source: "Time Alarms",
+            failure: info,
+            TxResponse {
+                txhash: "EF56".into(),
+                code: 11,
+                raw_log: "out of gas".into(),
+                ..TxResponse::default()
+            },
+        );
+    });
+
+    let events = capture.events();
+
+    assert_eq!(events.len(), 1);
+
+    assert_eq!(events[0].level, Level::INFO);
+
+    assert_eq!(events[0].fields["raw_log"], "out of gas");
+}
@@ -1,15 +1,67 @@
-use anyhow::{anyhow, Context as _, Result};
+use std::borrow::Borrow;
+
+use anyhow::{anyhow, bail, Context as _, Result};
 use cosmrs::{
     proto::cosmos::{
         base::abci::v1beta1::TxResponse,
-        tx::v1beta1::{BroadcastMode, BroadcastTxRequest, SimulateRequest},
+        tx::v1beta1::{
+            BroadcastMode as ProtoBroadcastMode, BroadcastTxRequest,
+            SimulateRequest,
+        },
     },
     tx::Raw as RawTx,
     Gas,
 };
 
+use crate::env::ReadFromVar;
+
 use super::{set_reconnect_if_required, BroadcastTx};
 
+/// Selects how long a node waits before responding to a broadcast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BroadcastMode {
+    /// Responds with the result of checking the transaction, before it gets
+    /// included in a block.
+    Sync,
+    /// Responds immediately, without checking the transaction. Failures are
+    /// only observed when polling for the delivered transaction.
+    Async,
+    /// Responds once the transaction is included in a block.
+    ///
+    /// Not supported by nodes running Cosmos SDK v0.47 and later.
+    Block,
+}
+
+impl BroadcastMode {
+    const fn proto(self) -> ProtoBroadcastMode {
+        match self {
+            Self::Sync => ProtoBroadcastMode::Sync,
+            Self::Async => ProtoBroadcastMode::Async,
+            #[allow(deprecated)]
+            Self::Block => ProtoBroadcastMode::Block,
+        }
+    }
+}
+
+impl ReadFromVar for BroadcastMode {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        match &*String::read_from_var(variable)
+            .context("Failed to read broadcast mode!")?
+        {
+            "sync" => Ok(Self::Sync),
+            "async" => Ok(Self::Async),
+            "block" => Ok(Self::Block),
+            mode => bail!(
+                "Unknown broadcast mode \"{mode}\"! Expected either \
+                \"sync\", \"async\" or \"block\"."
+            ),
+        }
+    }
+}
+
 impl BroadcastTx {
     const ENCODE_TRANSACTION_ERROR: &'static str =
         "Failed to encode signed transaction in binary Protobuf format!";
@@ -21,6 +73,8 @@ impl BroadcastTx {
         const MISSING_GAS_INFO_ERROR: &str =
             "Node didn't respond with gas information about simulation!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         self.inner
             .tx_service_client()
             .await?
@@ -46,24 +100,28 @@ impl BroadcastTx {
             })
     }
 
+    #[inline]
     pub async fn sync(&mut self, tx: RawTx) -> Result<TxResponse> {
+        self.broadcast(tx, BroadcastMode::Sync).await
+    }
+
+    pub async fn broadcast(
+        &mut self,
+        tx: RawTx,
+        mode: BroadcastMode,
+    ) -> Result<TxResponse> {
         const BROADCAST_TRANSACTION_ERROR: &str =
             "Failed to broadcast transaction!";
 
         const MISSING_TRANSACTION_RESPONSE_ERROR: &str =
             "Node didn't respond with transaction response!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         self.inner
             .tx_service_client()
             .await?
-            .broadcast_tx(BroadcastTxRequest {
-                tx_bytes: {
-                    tx.to_bytes()
-                        .map_err(|error| anyhow!(error))
-                        .context(Self::ENCODE_TRANSACTION_ERROR)?
-                },
-                mode: BroadcastMode::Sync.into(),
-            })
+            .broadcast_tx(Self::broadcast_request(&tx, mode)?)
             .await
             .inspect_err(|status| {
                 set_reconnect_if_required(&self.inner, status.code());
@@ -76,4 +134,41 @@ impl BroadcastTx {
                     .context(MISSING_TRANSACTION_RESPONSE_ERROR)
             })
     }
+
+    fn broadcast_request(
+        tx: &RawTx,
+        mode: BroadcastMode,
+    ) -> Result<BroadcastTxRequest> {
+        tx.to_bytes()
+            .map_err(|error| anyhow!(error))
+            .context(Self::ENCODE_TRANSACTION_ERROR)
+            .map(|tx_bytes| BroadcastTxRequest {
+                tx_bytes,
+                mode: mode.proto().into(),
+            })
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_broadcast_request_mode() {
+    use cosmrs::proto::cosmos::tx::v1beta1::TxRaw;
+
+    let tx = RawTx::from(TxRaw {
+        body_bytes: vec![1],
+        auth_info_bytes: vec![2],
+        signatures: vec![vec![3]],
+    });
+
+    for (mode, expected) in [
+        (BroadcastMode::Sync, ProtoBroadcastMode::Sync),
+        (BroadcastMode::Async, ProtoBroadcastMode::Async),
+        (BroadcastMode::Block, ProtoBroadcastMode::Block),
+    ] {
+        let request = BroadcastTx::broadcast_request(&tx, mode).unwrap();
+
+        assert_eq!(request.mode(), expected);
+
+        assert_eq!(request.tx_bytes, tx.to_bytes().unwrap());
+    }
 }
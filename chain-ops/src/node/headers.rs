@@ -0,0 +1,171 @@
+use std::{
+    env,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result};
+use tonic::{
+    metadata::{AsciiMetadataKey, AsciiMetadataValue},
+    service::Interceptor,
+    Request, Status,
+};
+
+/// Static headers attached to every request, e.g. API keys required by
+/// gateways in front of a node.
+///
+/// Values are treated as secrets and are never printed.
+#[derive(Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct RequestHeaders(Arc<[(AsciiMetadataKey, AsciiMetadataValue)]>);
+
+impl RequestHeaders {
+    pub fn new<I, K, V>(headers: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        headers
+            .into_iter()
+            .map(|(name, value)| {
+                let name = name.as_ref();
+
+                let key = AsciiMetadataKey::from_bytes(
+                    name.to_ascii_lowercase().as_bytes(),
+                )
+                .with_context(|| {
+                    format!(r#"Invalid request header name, "{name}"!"#)
+                })?;
+
+                let mut value = AsciiMetadataValue::try_from(value.as_ref())
+                    .with_context(|| {
+                        format!(r#"Invalid value for request header "{name}"!"#)
+                    })?;
+
+                value.set_sensitive(true);
+
+                Ok((key, value))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// Reads the headers from all environment variables starting with the
+    /// given prefix.
+    ///
+    /// The rest of the variable's name is used as the header's name, with
+    /// underscores replaced by hyphens, e.g. `<PREFIX>X_API_KEY` results in
+    /// the `x-api-key` header.
+    pub fn read_from_env_prefix(prefix: &str) -> Result<Self> {
+        Self::new(env::vars().filter_map(|(variable, value)| {
+            variable
+                .strip_prefix(prefix)
+                .filter(|name| !name.is_empty())
+                .map(|name| (name.replace('_', "-"), value))
+        }))
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Debug for RequestHeaders {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.0.iter().map(|(key, _)| (key, "<redacted>")))
+            .finish()
+    }
+}
+
+impl Interceptor for RequestHeaders {
+    fn call(
+        &mut self,
+        mut request: Request<()>,
+    ) -> Result<Request<()>, Status> {
+        let metadata = request.metadata_mut();
+
+        for (key, value) in self.0.iter() {
+            _ = metadata.insert(key.clone(), value.clone());
+        }
+
+        Ok(request)
+    }
+}
+
+#[test]
+fn test_headers_attached_and_redacted() {
+    let mut headers = RequestHeaders::new([
+        ("X-Api-Key", "secret-key"),
+        ("x-tenant", "nolus"),
+    ])
+    .unwrap();
+
+    let request = headers.call(Request::new(())).unwrap();
+
+    assert_eq!(request.metadata().get("x-api-key").unwrap(), "secret-key");
+
+    assert_eq!(request.metadata().get("x-tenant").unwrap(), "nolus");
+
+    let debug = format!("{headers:?}");
+
+    assert!(debug.contains("x-api-key"));
+
+    assert!(!debug.contains("secret-key"));
+
+    assert!(!format!("{:?}", request.metadata()).contains("secret-key"));
+
+    assert!(RequestHeaders::new([("x api key", "secret-key")]).is_err());
+
+    std::env::set_var("TEST_HEADERS__X_API_KEY", "secret-key");
+
+    let headers =
+        RequestHeaders::read_from_env_prefix("TEST_HEADERS__").unwrap();
+
+    let debug = format!("{headers:?}");
+
+    assert!(debug.contains("x-api-key") && !debug.contains("secret-key"));
+}
+
+#[tokio::test]
+async fn test_headers_sent_to_node() {
+    use tokio::net::TcpListener;
+
+    use super::{Client, ConnectOptions};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+    let address = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await.unwrap();
+
+        let mut connection = h2::server::handshake(socket).await.unwrap();
+
+        let (request, _) = connection.accept().await.unwrap().unwrap();
+
+        request.headers().clone()
+    });
+
+    let client = Client::connect_pool_with_options(
+        &[format!("http://{address}")],
+        ConnectOptions {
+            headers: RequestHeaders::new([("X-Api-Key", "secret-key")])
+                .unwrap(),
+            ..ConnectOptions::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let (headers, _) =
+        tokio::join!(server, client.query_tendermint().syncing());
+
+    let headers = headers.unwrap();
+
+    assert_eq!(headers["x-api-key"], "secret-key");
+
+    assert_eq!(headers["content-type"], "application/grpc");
+}
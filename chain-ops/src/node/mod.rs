@@ -1,12 +1,15 @@
 use std::{
+    borrow::Borrow,
     future::Future,
+    num::{NonZeroU32, NonZeroUsize},
+    str::FromStr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
 };
 
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, bail, Context as _, Error, Result};
 use cosmrs::proto::{
     cosmos::{
         auth::v1beta1::query_client::QueryClient as AuthQueryClient,
@@ -19,14 +22,30 @@ use cosmrs::proto::{
     },
     cosmwasm::wasm::v1::query_client::QueryClient as WasmQueryClient,
 };
+use thiserror::Error as ThisError;
 use tokio::sync::RwLock;
 use tonic::{
     client::Grpc as GrpcClient,
+    service::interceptor::InterceptedService,
     transport::{Channel as GrpcChannel, ClientTlsConfig, Endpoint, Uri},
     Code as TonicCode,
 };
 
+use crate::env::ReadFromVar;
+
+use self::{
+    pool::{Pool, Route, Selected},
+    scheduler::Scheduler,
+};
+
+pub use self::{
+    broadcast_tx::BroadcastMode, headers::RequestHeaders,
+    query_wasm::is_contract_not_found, scheduler::Priority,
+};
+
 mod broadcast_tx;
+mod headers;
+mod pool;
 mod query_auth;
 mod query_bank;
 mod query_raw;
@@ -34,6 +53,7 @@ mod query_reflection;
 mod query_tendermint;
 mod query_tx;
 mod query_wasm;
+mod scheduler;
 
 pub trait Reconnect {
     fn reconnect(&self) -> impl Future<Output = Result<()>> + Send + '_;
@@ -52,46 +72,164 @@ impl Client
 where
     Self: Reconnect,
 {
+    #[inline]
     pub async fn connect(uri: &str) -> Result<Self> {
+        Self::connect_pool(&[uri.into()]).await
+    }
+
+    /// Connects to the first healthy endpoint out of a set of redundant
+    /// endpoints of the same node, failing over to the rest when the
+    /// connection is deemed broken.
+    #[inline]
+    pub async fn connect_pool(uris: &[String]) -> Result<Self> {
+        Self::connect_pool_with_options(uris, ConnectOptions::default()).await
+    }
+
+    /// Same as [`Self::connect_pool`], applying the provided connection
+    /// options.
+    pub async fn connect_pool_with_options(
+        uris: &[String],
+        ConnectOptions {
+            reconnect_codes,
+            query_weights,
+            max_wasm_response_size,
+            max_message_size,
+            headers,
+        }: ConnectOptions,
+    ) -> Result<Self> {
         const CONNECT_TO_GRPC_ERROR: &str =
             "Failed to connect to node's gRPC endpoint!";
 
+        let query_weights = query_weights.as_deref();
+
+        if let Some(query_weights) = query_weights {
+            if query_weights.len() != uris.len() {
+                bail!(
+                    "Query weights count ({}) doesn't match the endpoints \
+                    count ({})!",
+                    query_weights.len(),
+                    uris.len(),
+                );
+            }
+        }
+
+        let pool = uris
+            .iter()
+            .enumerate()
+            .map(|(index, uri)| {
+                Self::endpoint(uri).map(|(uri, endpoint)| {
+                    (
+                        uri,
+                        endpoint,
+                        query_weights
+                            .map_or(NonZeroU32::MIN, |query_weights| {
+                                query_weights[index]
+                            }),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+            .and_then(|endpoints| {
+                Pool::new(endpoints, query_weights.is_some())
+            })?;
+
+        let connection = pool
+            .connect(false, connect_endpoint)
+            .await
+            .map(|(member, channel)| Connection {
+                uri: member.uri.clone(),
+                channel,
+            })
+            .context(CONNECT_TO_GRPC_ERROR)?;
+
+        let query_channels = if query_weights.is_some() {
+            pool.members()
+                .iter()
+                .map(|member| member.endpoint.clone().connect_lazy())
+                .collect()
+        } else {
+            Box::default()
+        };
+
+        Ok(Self {
+            inner: Arc::new(ClientInner {
+                should_reconnect: const { AtomicBool::new(false) },
+                pool,
+                grpc: RwLock::new(connection),
+                query_channels,
+                scheduler: Scheduler::new(),
+                reconnect_codes,
+                max_wasm_response_size,
+                max_message_size,
+                headers,
+            }),
+        })
+    }
+
+    /// Creates a client which only connects once a request is issued
+    /// through it, so dependants of a client can be constructed in tests
+    /// without a reachable node.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn connect_lazy(uri: &'static str) -> Self {
+        let Ok(pool) = Pool::new(
+            [(
+                Uri::from_static(uri),
+                Endpoint::from_static(uri),
+                NonZeroU32::MIN,
+            )],
+            false,
+        ) else {
+            unreachable!("Pool with a single endpoint is always valid!");
+        };
+
+        Self {
+            inner: Arc::new(ClientInner {
+                should_reconnect: const { AtomicBool::new(false) },
+                pool,
+                grpc: RwLock::new(Connection {
+                    uri: Uri::from_static(uri),
+                    channel: Endpoint::from_static(uri).connect_lazy(),
+                }),
+                query_channels: Box::default(),
+                scheduler: Scheduler::new(),
+                reconnect_codes: ReconnectCodes::DEFAULT,
+                max_wasm_response_size:
+                    ConnectOptions::DEFAULT_MAX_WASM_RESPONSE_SIZE,
+                max_message_size: ConnectOptions::DEFAULT_MAX_MESSAGE_SIZE,
+                headers: RequestHeaders::default(),
+            }),
+        }
+    }
+
+    fn endpoint(uri: &str) -> Result<(Uri, Endpoint)> {
         let uri: Uri = uri.parse().with_context(|| {
             format!(r#"Failed to parse gRPC URI, "{uri}"!"#)
         })?;
 
-        let endpoint = {
-            let endpoint = Endpoint::from(uri.clone())
-                .origin(uri.clone())
-                .keep_alive_while_idle(true);
-
-            if matches!(uri.scheme_str(), Some("http" | "ws")) {
-                endpoint
-            } else {
-                endpoint
-                    .tls_config(
-                        ClientTlsConfig::new()
-                            .assume_http2(true)
-                            .with_webpki_roots(),
-                    )
-                    .context(
-                        "Failed to configure TLS for node's gRPC endpoint!",
-                    )?
-            }
+        let endpoint = Endpoint::from(uri.clone())
+            .origin(uri.clone())
+            .keep_alive_while_idle(true);
+
+        let endpoint = if matches!(uri.scheme_str(), Some("http" | "ws")) {
+            endpoint
+        } else {
+            endpoint
+                .tls_config(
+                    ClientTlsConfig::new()
+                        .assume_http2(true)
+                        .with_webpki_roots(),
+                )
+                .context("Failed to configure TLS for node's gRPC endpoint!")?
         };
 
-        endpoint
-            .connect()
-            .await
-            .map(|grpc| Self {
-                inner: Arc::new(ClientInner {
-                    should_reconnect: const { AtomicBool::new(false) },
-                    uri,
-                    endpoint,
-                    grpc: RwLock::new(grpc),
-                }),
-            })
-            .context(CONNECT_TO_GRPC_ERROR)
+        Ok((uri, endpoint))
+    }
+
+    /// Returns whether a request issued through this client has failed in a
+    /// way which requires the connection to be re-established.
+    #[must_use]
+    pub fn connection_failed(&self) -> bool {
+        self.inner.should_reconnect.load(Ordering::Acquire)
     }
 }
 
@@ -111,6 +249,7 @@ macro_rules! define_interface {
                 Self: Reconnect,
             {
                 inner: Arc<ClientInner>,
+                priority: Priority,
             }
 
             impl $interface
@@ -119,7 +258,17 @@ macro_rules! define_interface {
             {
                 #[inline]
                 const fn new(inner: Arc<ClientInner>) -> Self {
-                    Self { inner }
+                    Self {
+                        inner,
+                        priority: Priority::High,
+                    }
+                }
+
+                /// Sets the priority with which requests are issued through
+                /// this interface.
+                #[inline]
+                pub fn with_priority(self, priority: Priority) -> Self {
+                    Self { priority, ..self }
                 }
             }
 
@@ -158,9 +307,23 @@ define_interface![
 
 struct ClientInner {
     should_reconnect: AtomicBool,
+    pool: Pool,
+    grpc: RwLock<Connection>,
+    /// Lazily connected channels to each of the pool's endpoints, used to
+    /// load-balance queries. Empty when query balancing is disabled.
+    query_channels: Box<[GrpcChannel]>,
+    scheduler: Scheduler,
+    reconnect_codes: ReconnectCodes,
+    max_wasm_response_size: NonZeroUsize,
+    max_message_size: NonZeroUsize,
+    headers: RequestHeaders,
+}
+
+type GrpcService = InterceptedService<GrpcChannel, RequestHeaders>;
+
+struct Connection {
     uri: Uri,
-    endpoint: Endpoint,
-    grpc: RwLock<GrpcChannel>,
+    channel: GrpcChannel,
 }
 
 impl ClientInner {
@@ -176,76 +339,141 @@ impl ClientInner {
         }
     }
 
-    async fn auth_query_client(
-        self: &Arc<Self>,
-    ) -> Result<AuthQueryClient<GrpcChannel>> {
+    /// Returns the channel through which the next request on the given route
+    /// is issued, along with the endpoint it belongs to, so the request's
+    /// failure gets recorded against the same endpoint.
+    async fn connection(
+        &self,
+        route: Route,
+    ) -> Result<(GrpcService, Uri, Selected)> {
         self.reconnect_if_required().await?;
 
-        Ok(AuthQueryClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
+        let selected = self.pool.select(route);
+
+        if let Selected::Member(index) = selected {
+            if let Some(channel) = self.query_channels.get(index) {
+                return Ok((
+                    self.intercepted(channel.clone()),
+                    self.pool.members()[index].uri.clone(),
+                    selected,
+                ));
+            }
+        }
+
+        let connection = self.grpc.read().await;
+
+        Ok((
+            self.intercepted(connection.channel.clone()),
+            connection.uri.clone(),
+            Selected::Current,
         ))
     }
 
-    async fn bank_query_client(
+    fn intercepted(&self, channel: GrpcChannel) -> GrpcService {
+        InterceptedService::new(channel, self.headers.clone())
+    }
+
+    async fn auth_query_client(
         self: &Arc<Self>,
-    ) -> Result<BankQueryClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
+    ) -> Result<AuthQueryClient<GrpcService>> {
+        let limit = self.max_message_size.get();
 
-        Ok(BankQueryClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
-        ))
+        self.connection(Route::Primary)
+            .await
+            .map(|(channel, uri, _)| {
+                AuthQueryClient::with_origin(channel, uri)
+                    .max_decoding_message_size(limit)
+                    .max_encoding_message_size(limit)
+            })
     }
 
-    async fn tendermint_service_client(
+    async fn bank_query_client(
         self: &Arc<Self>,
-    ) -> Result<TendermintServiceClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
+    ) -> Result<(BankQueryClient<GrpcService>, Selected)> {
+        let limit = self.max_message_size.get();
+
+        self.connection(Route::Balanced).await.map(
+            |(channel, uri, selected)| {
+                (
+                    BankQueryClient::with_origin(channel, uri)
+                        .max_decoding_message_size(limit)
+                        .max_encoding_message_size(limit),
+                    selected,
+                )
+            },
+        )
+    }
 
-        Ok(TendermintServiceClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
-        ))
+    async fn tendermint_service_client(
+        self: &Arc<Self>,
+    ) -> Result<(TendermintServiceClient<GrpcService>, Selected)> {
+        let limit = self.max_message_size.get();
+
+        self.connection(Route::Balanced).await.map(
+            |(channel, uri, selected)| {
+                (
+                    TendermintServiceClient::with_origin(channel, uri)
+                        .max_decoding_message_size(limit)
+                        .max_encoding_message_size(limit),
+                    selected,
+                )
+            },
+        )
     }
 
     async fn tx_service_client(
         self: &Arc<Self>,
-    ) -> Result<TxServiceClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
+    ) -> Result<TxServiceClient<GrpcService>> {
+        let limit = self.max_message_size.get();
 
-        Ok(TxServiceClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
-        ))
+        self.connection(Route::Primary)
+            .await
+            .map(|(channel, uri, _)| {
+                TxServiceClient::with_origin(channel, uri)
+                    .max_decoding_message_size(limit)
+                    .max_encoding_message_size(limit)
+            })
     }
 
-    async fn raw_client(self: &Arc<Self>) -> Result<GrpcClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
-
-        Ok(GrpcClient::new(self.grpc.read().await.clone()))
+    async fn raw_client(self: &Arc<Self>) -> Result<GrpcClient<GrpcService>> {
+        self.connection(Route::Primary)
+            .await
+            .map(|(channel, _, _)| {
+                limited_raw_client(channel, self.max_message_size)
+            })
     }
 
     async fn reflection_service_client(
         self: &Arc<Self>,
-    ) -> Result<ReflectionServiceClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
+    ) -> Result<ReflectionServiceClient<GrpcService>> {
+        let limit = self.max_message_size.get();
 
-        Ok(ReflectionServiceClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
-        ))
+        self.connection(Route::Primary)
+            .await
+            .map(|(channel, uri, _)| {
+                ReflectionServiceClient::with_origin(channel, uri)
+                    .max_decoding_message_size(limit)
+                    .max_encoding_message_size(limit)
+            })
     }
 
     async fn wasm_query_client(
         self: &Arc<Self>,
-    ) -> Result<WasmQueryClient<GrpcChannel>> {
-        self.reconnect_if_required().await?;
-
-        Ok(WasmQueryClient::with_origin(
-            self.grpc.read().await.clone(),
-            self.uri.clone(),
-        ))
+    ) -> Result<(WasmQueryClient<GrpcService>, Selected)> {
+        self.connection(Route::Balanced).await.map(
+            |(channel, uri, selected)| {
+                (
+                    WasmQueryClient::with_origin(channel, uri)
+                        .max_decoding_message_size(
+                            self.max_wasm_response_size
+                                .get()
+                                .saturating_add(WASM_RESPONSE_ENVELOPE_SIZE),
+                        )
+                        .max_encoding_message_size(self.max_message_size.get()),
+                    selected,
+                )
+            },
+        )
     }
 }
 
@@ -257,10 +485,17 @@ impl Reconnect for ClientInner {
         let mut lock = self.grpc.write().await;
 
         if self.should_reconnect.load(Ordering::Acquire) {
-            let new_channel =
-                self.endpoint.connect().await.context(RECONNECT_ERROR)?;
-
-            *lock = new_channel;
+            self.pool.mark_current_failed();
+
+            *lock = self
+                .pool
+                .connect(true, connect_endpoint)
+                .await
+                .map(|(member, channel)| Connection {
+                    uri: member.uri.clone(),
+                    channel,
+                })
+                .context(RECONNECT_ERROR)?;
 
             self.should_reconnect.store(false, Ordering::Release);
         }
@@ -269,11 +504,403 @@ impl Reconnect for ClientInner {
     }
 }
 
+fn limited_raw_client<T>(
+    service: T,
+    max_message_size: NonZeroUsize,
+) -> GrpcClient<T> {
+    GrpcClient::new(service)
+        .max_decoding_message_size(max_message_size.get())
+        .max_encoding_message_size(max_message_size.get())
+}
+
+async fn connect_endpoint(
+    endpoint: Endpoint,
+) -> Result<GrpcChannel, tonic::transport::Error> {
+    endpoint.connect().await
+}
+
+/// Allowance for the encoding overhead of a wasm query response on top of
+/// its data, so oversized data is reported through [`ResponseTooLarge`]
+/// whenever the encoded message itself doesn't exceed the limit as well.
+const WASM_RESPONSE_ENVELOPE_SIZE: usize = 1 << 10;
+
+/// Options applied when connecting through
+/// [`Client::connect_pool_with_options`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct ConnectOptions {
+    pub reconnect_codes: ReconnectCodes,
+    /// Weights of the endpoints, one per endpoint, used to load-balance
+    /// bank, wasm and tendermint queries across all healthy endpoints.
+    ///
+    /// Broadcasts and the remaining requests always go through the primary
+    /// endpoint to keep sequence handling consistent.
+    pub query_weights: Option<Vec<NonZeroU32>>,
+    /// Maximum size of wasm query responses' data, enforced before
+    /// deserializing it.
+    pub max_wasm_response_size: NonZeroUsize,
+    /// Maximum size of the remaining requests and responses, in place of
+    /// the gRPC client's default of 4 MiB.
+    pub max_message_size: NonZeroUsize,
+    /// Headers attached to every request, e.g. API keys required by
+    /// gateways in front of the node.
+    pub headers: RequestHeaders,
+}
+
+impl ConnectOptions {
+    pub const DEFAULT_MAX_WASM_RESPONSE_SIZE: NonZeroUsize =
+        if let Some(size) = NonZeroUsize::new(16 << 20) {
+            size
+        } else {
+            unreachable!()
+        };
+
+    pub const DEFAULT_MAX_MESSAGE_SIZE: NonZeroUsize =
+        if let Some(size) = NonZeroUsize::new(16 << 20) {
+            size
+        } else {
+            unreachable!()
+        };
+}
+
+impl Default for ConnectOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            reconnect_codes: ReconnectCodes::DEFAULT,
+            query_weights: None,
+            max_wasm_response_size: Self::DEFAULT_MAX_WASM_RESPONSE_SIZE,
+            max_message_size: Self::DEFAULT_MAX_MESSAGE_SIZE,
+            headers: RequestHeaders::default(),
+        }
+    }
+}
+
+/// Returned when a query's response exceeds the configured maximum size.
+#[derive(Debug, ThisError)]
+#[error("Response of {size} bytes exceeds the maximum of {limit} bytes!")]
+pub struct ResponseTooLarge {
+    pub size: usize,
+    pub limit: usize,
+}
+
 fn set_reconnect_if_required(
     client_inner: &ClientInner,
     error_code: TonicCode,
 ) {
-    if matches!(error_code, TonicCode::Ok | TonicCode::NotFound) {
+    if client_inner.reconnect_codes.contains(error_code) {
         client_inner.set_should_reconnect();
     }
 }
+
+/// Records a failed request against the endpoint it was issued through, so
+/// a failing load-balanced endpoint stops receiving queries instead of the
+/// primary one being reconnected.
+fn record_failure(
+    client_inner: &ClientInner,
+    selected: Selected,
+    error_code: TonicCode,
+) {
+    match selected {
+        Selected::Current => {
+            set_reconnect_if_required(client_inner, error_code);
+        },
+        Selected::Member(index) => {
+            if client_inner.reconnect_codes.contains(error_code) {
+                client_inner.pool.mark_failed(index);
+            }
+        },
+    }
+}
+
+/// Set of gRPC status codes which indicate a broken connection and trigger
+/// reconnecting to the node upon the next request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct ReconnectCodes {
+    mask: u32,
+}
+
+impl ReconnectCodes {
+    pub const DEFAULT: Self = Self::new(&[
+        TonicCode::Unknown,
+        TonicCode::DeadlineExceeded,
+        TonicCode::Unavailable,
+    ]);
+
+    pub const fn new(codes: &[TonicCode]) -> Self {
+        let mut mask = 0;
+
+        let mut index = 0;
+
+        while index < codes.len() {
+            mask |= Self::code_bit(codes[index]);
+
+            index += 1;
+        }
+
+        Self { mask }
+    }
+
+    #[must_use]
+    pub const fn contains(self, code: TonicCode) -> bool {
+        self.mask & Self::code_bit(code) != 0
+    }
+
+    #[allow(clippy::cast_sign_loss)]
+    const fn code_bit(code: TonicCode) -> u32 {
+        1 << (code as i32 as u32)
+    }
+}
+
+impl Default for ReconnectCodes {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl FromStr for ReconnectCodes {
+    type Err = Error;
+
+    fn from_str(codes: &str) -> Result<Self> {
+        codes
+            .split(',')
+            .map(str::trim)
+            .filter(|code| !code.is_empty())
+            .map(|name| {
+                (0..=16)
+                    .map(TonicCode::from)
+                    .find(|code| format!("{code:?}").eq_ignore_ascii_case(name))
+                    .ok_or_else(|| {
+                        anyhow!(r#"Unknown gRPC status code "{name}"!"#)
+                    })
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(|codes| Self::new(&codes))
+    }
+}
+
+impl ReadFromVar for ReconnectCodes {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        String::read_from_var(variable).and_then(|codes| {
+            codes
+                .parse()
+                .context("Failed to parse reconnect status codes!")
+        })
+    }
+}
+
+#[test]
+fn test_reconnect_codes() {
+    for (code, expected) in [
+        (TonicCode::Ok, false),
+        (TonicCode::Unknown, true),
+        (TonicCode::InvalidArgument, false),
+        (TonicCode::DeadlineExceeded, true),
+        (TonicCode::NotFound, false),
+        (TonicCode::PermissionDenied, false),
+        (TonicCode::Unavailable, true),
+        (TonicCode::Unauthenticated, false),
+    ] {
+        assert_eq!(
+            ReconnectCodes::DEFAULT.contains(code),
+            expected,
+            "{code:?}"
+        );
+    }
+
+    let configured: ReconnectCodes =
+        "unavailable, Internal,ResourceExhausted".parse().unwrap();
+
+    assert!(configured.contains(TonicCode::Unavailable));
+
+    assert!(configured.contains(TonicCode::Internal));
+
+    assert!(configured.contains(TonicCode::ResourceExhausted));
+
+    assert!(!configured.contains(TonicCode::Unknown));
+
+    assert!("Unavailable,Disconnected"
+        .parse::<ReconnectCodes>()
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_response_above_default_message_size_decoded() {
+    use std::{
+        convert::Infallible,
+        future::{ready, Ready},
+        task::{Context, Poll},
+    };
+
+    use prost::Message;
+    use tonic::{
+        body::{boxed, BoxBody},
+        codec::ProstCodec,
+        codegen::{
+            http::{uri::PathAndQuery, Request, Response},
+            Service,
+        },
+        IntoRequest as _,
+    };
+
+    /// Size of the message, above tonic's default limit of 4 MiB, chosen so
+    /// all bytes of the frame's length prefix are ASCII.
+    const MESSAGE_SIZE: usize = 0x0050_0000;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Chunks {
+        #[prost(string, repeated, tag = "1")]
+        chunks: Vec<String>,
+    }
+
+    #[derive(Clone)]
+    struct Stub(String);
+
+    impl Service<Request<BoxBody>> for Stub {
+        type Response = Response<BoxBody>;
+
+        type Error = Infallible;
+
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(
+            &mut self,
+            _: &mut Context<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Request<BoxBody>) -> Self::Future {
+            ready(Ok(Response::builder()
+                .header("content-type", "application/grpc")
+                .header("grpc-status", "0")
+                .body(boxed(self.0.clone()))
+                .unwrap()))
+        }
+    }
+
+    // Each chunk is encoded with a tag and a single byte length prefix.
+    const ENCODED_CHUNK_SIZE: usize = 129;
+
+    let mut chunks = vec![
+        "a".repeat(ENCODED_CHUNK_SIZE - 2);
+        MESSAGE_SIZE / ENCODED_CHUNK_SIZE
+    ];
+
+    chunks.push("a".repeat(MESSAGE_SIZE % ENCODED_CHUNK_SIZE - 2));
+
+    let message = Chunks { chunks };
+
+    let mut frame = vec![0];
+
+    frame
+        .extend_from_slice(&u32::try_from(MESSAGE_SIZE).unwrap().to_be_bytes());
+
+    message.encode(&mut frame).unwrap();
+
+    assert_eq!(frame.len(), MESSAGE_SIZE + 5);
+
+    let stub = Stub(String::from_utf8(frame).unwrap());
+
+    let path_and_query = PathAndQuery::from_static("/stub.Query/Chunks");
+
+    let query = |mut client: GrpcClient<Stub>| {
+        let path_and_query = path_and_query.clone();
+
+        async move {
+            client
+                .unary::<_, Chunks, _>(
+                    Chunks::default().into_request(),
+                    path_and_query,
+                    ProstCodec::default(),
+                )
+                .await
+                .map(tonic::Response::into_inner)
+        }
+    };
+
+    assert!(query(GrpcClient::new(stub.clone())).await.is_err());
+
+    assert_eq!(
+        query(limited_raw_client(
+            stub,
+            ConnectOptions::DEFAULT_MAX_MESSAGE_SIZE,
+        ))
+        .await
+        .unwrap(),
+        message,
+    );
+}
+
+#[tokio::test]
+async fn test_balanced_query_failure_recorded_against_selected_endpoint() {
+    const ENDPOINTS: [&str; 2] =
+        ["http://first.invalid:9090", "http://second.invalid:9090"];
+
+    let pool = Pool::new(
+        ENDPOINTS.map(|uri| {
+            (
+                Uri::from_static(uri),
+                Endpoint::from_static(uri),
+                NonZeroU32::MIN,
+            )
+        }),
+        true,
+    )
+    .unwrap();
+
+    let query_channels = pool
+        .members()
+        .iter()
+        .map(|member| member.endpoint.clone().connect_lazy())
+        .collect();
+
+    let client_inner = ClientInner {
+        should_reconnect: const { AtomicBool::new(false) },
+        pool,
+        grpc: RwLock::new(Connection {
+            uri: Uri::from_static(ENDPOINTS[0]),
+            channel: Endpoint::from_static(ENDPOINTS[0]).connect_lazy(),
+        }),
+        query_channels,
+        scheduler: Scheduler::new(),
+        reconnect_codes: ReconnectCodes::DEFAULT,
+        max_wasm_response_size: ConnectOptions::DEFAULT_MAX_WASM_RESPONSE_SIZE,
+        max_message_size: ConnectOptions::DEFAULT_MAX_MESSAGE_SIZE,
+        headers: RequestHeaders::default(),
+    };
+
+    let (_, _, selected) =
+        client_inner.connection(Route::Balanced).await.unwrap();
+
+    assert_eq!(selected, Selected::Current);
+
+    let (_, uri, selected) =
+        client_inner.connection(Route::Balanced).await.unwrap();
+
+    assert_eq!(selected, Selected::Member(1));
+
+    assert_eq!(uri.host(), Some("second.invalid"));
+
+    record_failure(&client_inner, selected, TonicCode::Unavailable);
+
+    assert!(!client_inner.should_reconnect.load(Ordering::Acquire));
+
+    for _ in 0..4 {
+        let (_, uri, selected) =
+            client_inner.connection(Route::Balanced).await.unwrap();
+
+        assert_eq!(selected, Selected::Current);
+
+        assert_eq!(uri.host(), Some("first.invalid"));
+    }
+
+    record_failure(&client_inner, Selected::Current, TonicCode::Unavailable);
+
+    assert!(client_inner.should_reconnect.load(Ordering::Acquire));
+}
@@ -0,0 +1,319 @@
+use std::{
+    error::Error as StdError,
+    future::Future,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicU32, AtomicUsize, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use anyhow::{bail, Context as _, Error, Result};
+use tonic::transport::{Endpoint, Uri};
+
+/// Set of redundant endpoints of the same node, tracking each endpoint's
+/// health so reconnecting fails over to the best available one.
+///
+/// When query balancing is enabled, read-only queries are additionally
+/// distributed across all healthy endpoints via smooth weighted round-robin.
+pub(super) struct Pool {
+    members: Box<[Member]>,
+    current: AtomicUsize,
+    query_balancing: Option<Mutex<Box<[i64]>>>,
+}
+
+impl Pool {
+    pub fn new<I>(endpoints: I, balance_queries: bool) -> Result<Self>
+    where
+        I: IntoIterator<Item = (Uri, Endpoint, NonZeroU32)>,
+    {
+        let members: Box<[Member]> = endpoints
+            .into_iter()
+            .map(|(uri, endpoint, weight)| Member {
+                uri,
+                endpoint,
+                weight,
+                consecutive_failures: const { AtomicU32::new(0) },
+            })
+            .collect();
+
+        if members.is_empty() {
+            bail!("Endpoints pool has to contain at least one endpoint!");
+        }
+
+        let query_balancing = balance_queries
+            .then(|| Mutex::new(vec![0; members.len()].into_boxed_slice()));
+
+        Ok(Self {
+            members,
+            current: const { AtomicUsize::new(0) },
+            query_balancing,
+        })
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    pub fn current(&self) -> &Member {
+        &self.members[self.current.load(Ordering::Acquire)]
+    }
+
+    /// Selects the endpoint through which the next request on the given
+    /// route is issued.
+    ///
+    /// Returns [`Selected::Current`] when the request has to go through the
+    /// current, primary endpoint, which is always the case for
+    /// [`Route::Primary`].
+    pub fn select(&self, route: Route) -> Selected {
+        let (Route::Balanced, Some(query_balancing)) =
+            (route, &self.query_balancing)
+        else {
+            return Selected::Current;
+        };
+
+        let mut current_weights = query_balancing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        let mut total_weight = 0;
+
+        let mut selected: Option<usize> = None;
+
+        for (index, member) in self.members.iter().enumerate() {
+            if member.consecutive_failures.load(Ordering::Acquire) != 0 {
+                continue;
+            }
+
+            let weight = i64::from(member.weight.get());
+
+            total_weight += weight;
+
+            current_weights[index] += weight;
+
+            if selected.map_or(true, |selected| {
+                current_weights[index] > current_weights[selected]
+            }) {
+                selected = Some(index);
+            }
+        }
+
+        let Some(selected) = selected else {
+            return Selected::Current;
+        };
+
+        current_weights[selected] -= total_weight;
+
+        if selected == self.current.load(Ordering::Acquire) {
+            Selected::Current
+        } else {
+            Selected::Member(selected)
+        }
+    }
+
+    /// Records a failed request issued through the endpoint at the given
+    /// index, excluding it from load-balanced queries until a reconnect
+    /// succeeds through it.
+    pub fn mark_failed(&self, index: usize) {
+        _ = self.members[index]
+            .consecutive_failures
+            .fetch_add(1, Ordering::AcqRel);
+    }
+
+    pub fn mark_current_failed(&self) {
+        self.mark_failed(self.current.load(Ordering::Acquire));
+    }
+
+    /// Connects through the healthiest endpoint, trying the rest in order
+    /// of health on failure.
+    ///
+    /// Endpoints with the same amount of consecutive failures are tried in
+    /// rotation, starting with the one after the current endpoint when
+    /// `skip_current` is set.
+    pub async fn connect<F, Fut, T, E>(
+        &self,
+        skip_current: bool,
+        mut connect: F,
+    ) -> Result<(&Member, T)>
+    where
+        F: FnMut(Endpoint) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: StdError + Send + Sync + 'static,
+    {
+        let mut last_error = None;
+
+        for index in self.failover_order(skip_current) {
+            let member = &self.members[index];
+
+            match connect(member.endpoint.clone()).await {
+                Ok(connection) => {
+                    member.consecutive_failures.store(0, Ordering::Release);
+
+                    self.current.store(index, Ordering::Release);
+
+                    return Ok((member, connection));
+                },
+                Err(error) => {
+                    _ = member
+                        .consecutive_failures
+                        .fetch_add(1, Ordering::AcqRel);
+
+                    last_error = Some(Error::new(error).context(format!(
+                        r#"Failed to connect to endpoint "{}"!"#,
+                        member.uri,
+                    )));
+                },
+            }
+        }
+
+        if let Some(error) = last_error {
+            Err(error
+                .context("Failed to connect to any of the pool's endpoints!"))
+        } else {
+            unreachable!(
+                "Endpoints pool cannot be empty as it's checked upon \
+                construction!",
+            )
+        }
+    }
+
+    fn failover_order(&self, skip_current: bool) -> Vec<usize> {
+        let current = self.current.load(Ordering::Acquire);
+
+        let mut order: Vec<usize> = (0..self.members.len())
+            .map(|offset| {
+                (current + offset + usize::from(skip_current))
+                    % self.members.len()
+            })
+            .collect();
+
+        order.sort_by_key(|&index| {
+            self.members[index]
+                .consecutive_failures
+                .load(Ordering::Acquire)
+        });
+
+        order
+    }
+}
+
+pub(super) struct Member {
+    pub uri: Uri,
+    pub endpoint: Endpoint,
+    weight: NonZeroU32,
+    consecutive_failures: AtomicU32,
+}
+
+/// Endpoint selected for a request, against which the request's failure is
+/// recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Selected {
+    /// The current, primary endpoint, whose failure triggers reconnecting.
+    Current,
+    /// Another healthy endpoint of the pool, at the given index, chosen to
+    /// load-balance a query.
+    Member(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Route {
+    /// Always goes through the current endpoint, e.g. for broadcasts and
+    /// account queries, so sequence handling stays consistent.
+    Primary,
+    /// Load-balanced across all healthy endpoints when enabled.
+    Balanced,
+}
+
+#[tokio::test]
+async fn test_failover_to_second_endpoint() {
+    use std::io;
+
+    let pool = Pool::new(
+        ["http://first.invalid:9090", "http://second.invalid:9090"]
+            .into_iter()
+            .map(|uri| {
+                (
+                    Uri::from_static(uri),
+                    Endpoint::from_static(uri),
+                    NonZeroU32::MIN,
+                )
+            }),
+        false,
+    )
+    .unwrap();
+
+    let connect = |endpoint: Endpoint| async move {
+        if endpoint.uri().host() == Some("first.invalid") {
+            Err(io::Error::from(io::ErrorKind::ConnectionRefused))
+        } else {
+            Ok(endpoint.uri().clone())
+        }
+    };
+
+    let (member, connected) = pool.connect(false, connect).await.unwrap();
+
+    assert_eq!(member.uri.host(), Some("second.invalid"));
+
+    assert_eq!(connected.host(), Some("second.invalid"));
+
+    assert_eq!(pool.current().uri.host(), Some("second.invalid"));
+
+    pool.mark_current_failed();
+
+    let (member, _) = pool.connect(true, connect).await.unwrap();
+
+    assert_eq!(member.uri.host(), Some("second.invalid"));
+
+    let failing = |_: Endpoint| async {
+        Err::<(), _>(io::Error::from(io::ErrorKind::ConnectionRefused))
+    };
+
+    assert!(pool.connect(true, failing).await.is_err());
+}
+
+#[test]
+fn test_weighted_query_distribution() {
+    let pool = Pool::new(
+        [
+            ("http://first.invalid:9090", 3),
+            ("http://second.invalid:9090", 1),
+            ("http://third.invalid:9090", 2),
+        ]
+        .into_iter()
+        .map(|(uri, weight)| {
+            (
+                Uri::from_static(uri),
+                Endpoint::from_static(uri),
+                NonZeroU32::new(weight).unwrap(),
+            )
+        }),
+        true,
+    )
+    .unwrap();
+
+    let distribution = |pool: &Pool, route| {
+        let mut counts = [0; 3];
+
+        for _ in 0..60 {
+            counts[match pool.select(route) {
+                Selected::Current => 0,
+                Selected::Member(index) => index,
+            }] += 1;
+        }
+
+        counts
+    };
+
+    assert_eq!(distribution(&pool, Route::Balanced), [30, 10, 20]);
+
+    assert_eq!(distribution(&pool, Route::Primary), [60, 0, 0]);
+
+    pool.mark_failed(1);
+
+    assert_eq!(distribution(&pool, Route::Balanced), [36, 0, 24]);
+
+    assert_eq!(
+        pool.current().consecutive_failures.load(Ordering::Acquire),
+        0,
+    );
+}
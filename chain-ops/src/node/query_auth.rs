@@ -24,6 +24,8 @@ impl QueryAuth {
             "Failed to convert account data query's response into it's \
             structured form!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         self.inner
             .auth_query_client()
             .await?
@@ -1,7 +1,7 @@
 use anyhow::{Context as _, Result};
 use cosmrs::proto::cosmos::bank::v1beta1::QueryBalanceRequest;
 
-use super::{set_reconnect_if_required, QueryBank};
+use super::{record_failure, QueryBank};
 
 impl QueryBank {
     pub async fn balance(
@@ -17,13 +17,15 @@ impl QueryBank {
 
         const PARSE_BALANCE_ERROR: &str = "Failed to parse balance amount!";
 
-        self.inner
-            .bank_query_client()
-            .await?
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let (mut client, selected) = self.inner.bank_query_client().await?;
+
+        client
             .balance(QueryBalanceRequest { address, denom })
             .await
             .inspect_err(|status| {
-                set_reconnect_if_required(&self.inner, status.code());
+                record_failure(&self.inner, selected, status.code());
             })
             .context(QUERY_BALANCE_ERROR)
             .and_then(|response| {
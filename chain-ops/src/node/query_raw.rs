@@ -19,6 +19,8 @@ impl QueryRaw {
 
         const RUN_QUERY_ERROR: &str = "Failed to run raw query!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         let mut raw_client = self.inner.raw_client().await?;
 
         raw_client
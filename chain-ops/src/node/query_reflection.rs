@@ -12,6 +12,8 @@ impl QueryReflection {
             "Query response doesn't contain account address prefix \
             configuration!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         self.inner
             .reflection_service_client()
             .await?
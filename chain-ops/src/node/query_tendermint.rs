@@ -1,4 +1,5 @@
 use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
 use cosmrs::{
     proto::cosmos::base::tendermint::v1beta1::{
         GetLatestBlockRequest, GetNodeInfoRequest, GetSyncingRequest,
@@ -6,7 +7,7 @@ use cosmrs::{
     tendermint::chain::Id as ChainId,
 };
 
-use super::{set_reconnect_if_required, QueryTendermint};
+use super::{record_failure, QueryTendermint};
 
 impl QueryTendermint {
     pub async fn chain_id(&mut self) -> Result<ChainId> {
@@ -18,13 +19,16 @@ impl QueryTendermint {
 
         const PARSE_CHAIN_ID_ERROR: &str = "Failed to parse chain's ID!";
 
-        self.inner
-            .tendermint_service_client()
-            .await?
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let (mut client, selected) =
+            self.inner.tendermint_service_client().await?;
+
+        client
             .get_node_info(GetNodeInfoRequest {})
             .await
             .inspect_err(|status| {
-                set_reconnect_if_required(&self.inner, status.code());
+                record_failure(&self.inner, selected, status.code());
             })
             .context(QUERY_NODE_INFO_ERROR)
             .and_then(|response| {
@@ -42,13 +46,16 @@ impl QueryTendermint {
         const QUERY_SYNCING_STATUS_ERROR: &str =
             "Failed to query syncing status of node!";
 
-        self.inner
-            .tendermint_service_client()
-            .await?
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let (mut client, selected) =
+            self.inner.tendermint_service_client().await?;
+
+        client
             .get_syncing(GetSyncingRequest {})
             .await
             .inspect_err(|status| {
-                set_reconnect_if_required(&self.inner, status.code());
+                record_failure(&self.inner, selected, status.code());
             })
             .context(QUERY_SYNCING_STATUS_ERROR)
             .map(|response| response.into_inner().syncing)
@@ -64,13 +71,16 @@ impl QueryTendermint {
         const MISSING_BLOCK_HEADER_INFO_ERROR: &str =
             "Query response doesn't contain block's header information!";
 
-        self.inner
-            .tendermint_service_client()
-            .await?
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let (mut client, selected) =
+            self.inner.tendermint_service_client().await?;
+
+        client
             .get_latest_block(GetLatestBlockRequest {})
             .await
             .inspect_err(|status| {
-                set_reconnect_if_required(&self.inner, status.code());
+                record_failure(&self.inner, selected, status.code());
             })
             .context(QUERY_NODE_INFO_ERROR)
             .and_then(|response| {
@@ -86,4 +96,51 @@ impl QueryTendermint {
                     })
             })
     }
+
+    pub async fn get_latest_block_time(&mut self) -> Result<DateTime<Utc>> {
+        const QUERY_LATEST_BLOCK_ERROR: &str =
+            "Failed to query node's latest block!";
+
+        const MISSING_BLOCK_INFO_ERROR: &str =
+            "Query response doesn't contain block information!";
+
+        const MISSING_BLOCK_TIME_INFO_ERROR: &str =
+            "Query response doesn't contain block's time information!";
+
+        const INVALID_BLOCK_TIME_ERROR: &str =
+            "Block's time is outside of the representable range!";
+
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let (mut client, selected) =
+            self.inner.tendermint_service_client().await?;
+
+        client
+            .get_latest_block(GetLatestBlockRequest {})
+            .await
+            .inspect_err(|status| {
+                record_failure(&self.inner, selected, status.code());
+            })
+            .context(QUERY_LATEST_BLOCK_ERROR)
+            .and_then(|response| {
+                response
+                    .into_inner()
+                    .sdk_block
+                    .context(MISSING_BLOCK_INFO_ERROR)
+                    .and_then(|block| {
+                        block
+                            .header
+                            .and_then(|header| header.time)
+                            .context(MISSING_BLOCK_TIME_INFO_ERROR)
+                    })
+                    .and_then(|time| {
+                        u32::try_from(time.nanos)
+                            .ok()
+                            .and_then(|nanos| {
+                                DateTime::from_timestamp(time.seconds, nanos)
+                            })
+                            .context(INVALID_BLOCK_TIME_ERROR)
+                    })
+            })
+    }
 }
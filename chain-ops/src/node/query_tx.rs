@@ -10,6 +10,8 @@ impl QueryTx {
         const MISSING_RESPONSE_ERROR: &str =
             "Query response doesn't contain transaction result!";
 
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
         let result = self
             .inner
             .tx_service_client()
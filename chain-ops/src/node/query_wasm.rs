@@ -1,10 +1,11 @@
-use std::any::type_name;
-
-use anyhow::{Context as _, Result};
+use anyhow::{Context as _, Error, Result};
 use cosmrs::proto::cosmwasm::wasm::v1::QuerySmartContractStateRequest;
 use serde::de::DeserializeOwned;
+use tonic::{Code, Status};
+
+use crate::json;
 
-use super::{set_reconnect_if_required, QueryWasm};
+use super::{record_failure, QueryWasm, ResponseTooLarge};
 
 impl QueryWasm {
     pub async fn smart<T>(
@@ -18,9 +19,13 @@ impl QueryWasm {
         const QUERY_CONTRACT_ERROR: &str =
             "Failed to run query against contract!";
 
-        self.inner
-            .wasm_query_client()
-            .await?
+        let _permit = self.inner.scheduler.acquire(self.priority).await;
+
+        let limit = self.inner.max_wasm_response_size.get();
+
+        let (mut client, selected) = self.inner.wasm_query_client().await?;
+
+        client
             .smart_contract_state(QuerySmartContractStateRequest {
                 address,
                 query_data,
@@ -28,23 +33,54 @@ impl QueryWasm {
             .await
             .map(|response| response.into_inner().data)
             .inspect_err(|status| {
-                set_reconnect_if_required(&self.inner, status.code());
+                record_failure(&self.inner, selected, status.code());
             })
             .context(QUERY_CONTRACT_ERROR)
-            .and_then(|data| {
-                serde_json_wasm::from_slice(&data)
-                    .with_context(|| {
-                        format!(
-                            "Response data: {}",
-                            String::from_utf8_lossy(&data),
-                        )
-                    })
-                    .with_context(|| {
-                        format!(
-                            r#"Failed to deserialize response into "{}"!"#,
-                            type_name::<T>()
-                        )
-                    })
-            })
+            .and_then(|data| decode_response(&data, limit))
     }
 }
+
+/// Checks whether the query failed because the contract doesn't exist, e.g.
+/// when it isn't instantiated yet.
+#[must_use]
+pub fn is_contract_not_found(error: &Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<Status>().is_some_and(|status| {
+            status.code() == Code::NotFound
+                || status.message().contains("no such contract")
+        })
+    })
+}
+
+fn decode_response<T>(data: &[u8], limit: usize) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    if data.len() > limit {
+        return Err(ResponseTooLarge {
+            size: data.len(),
+            limit,
+        }
+        .into());
+    }
+
+    json::from_slice(data)
+}
+
+#[test]
+fn test_response_size_limit() {
+    let payload = format!(r#""{}""#, "a".repeat(64));
+
+    let error = decode_response::<String>(payload.as_bytes(), 32).unwrap_err();
+
+    let error = error.downcast::<ResponseTooLarge>().unwrap();
+
+    assert_eq!(error.size, 66);
+
+    assert_eq!(error.limit, 32);
+
+    assert_eq!(
+        decode_response::<String>(payload.as_bytes(), 66).unwrap(),
+        "a".repeat(64),
+    );
+}
@@ -0,0 +1,141 @@
+use std::{
+    pin::pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tokio::sync::Notify;
+
+/// Priority with which requests are issued through a node client's
+/// interface.
+///
+/// Low priority requests are held back while there are high priority ones
+/// in flight on the same client.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    #[default]
+    High,
+    Low,
+}
+
+pub(super) struct Scheduler {
+    high_priority_in_flight: AtomicUsize,
+    high_priority_drained: Notify,
+}
+
+impl Scheduler {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            high_priority_in_flight: const { AtomicUsize::new(0) },
+            high_priority_drained: Notify::new(),
+        }
+    }
+
+    pub async fn acquire(&self, priority: Priority) -> Permit<'_> {
+        match priority {
+            Priority::High => {
+                _ = self.high_priority_in_flight.fetch_add(1, Ordering::AcqRel);
+
+                Permit {
+                    high_priority: Some(self),
+                }
+            },
+            Priority::Low => {
+                loop {
+                    let mut drained =
+                        pin!(self.high_priority_drained.notified());
+
+                    drained.as_mut().enable();
+
+                    if self.high_priority_in_flight.load(Ordering::Acquire) == 0
+                    {
+                        break;
+                    }
+
+                    drained.await;
+                }
+
+                Permit {
+                    high_priority: None,
+                }
+            },
+        }
+    }
+}
+
+#[must_use]
+pub(super) struct Permit<'r> {
+    high_priority: Option<&'r Scheduler>,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if let Some(scheduler) = self.high_priority {
+            if scheduler
+                .high_priority_in_flight
+                .fetch_sub(1, Ordering::AcqRel)
+                == 1
+            {
+                scheduler.high_priority_drained.notify_waiters();
+            }
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_high_priority_issued_first() {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::{sync::Mutex, task::JoinSet, time::sleep};
+
+    let scheduler = Arc::new(Scheduler::new());
+
+    let issued = Arc::new(Mutex::new(vec![]));
+
+    let mut requests = JoinSet::new();
+
+    [
+        (Priority::Low, 1),
+        (Priority::High, 2),
+        (Priority::Low, 3),
+        (Priority::High, 4),
+        (Priority::High, 5),
+        (Priority::Low, 6),
+    ]
+    .into_iter()
+    .enumerate()
+    .for_each(|(index, (priority, request))| {
+        let scheduler = scheduler.clone();
+
+        let issued = issued.clone();
+
+        requests.spawn(async move {
+            // Stagger the requests so high priority ones arrive while low
+            // priority ones are already pending.
+            sleep(Duration::from_millis(10) * u32::try_from(index).unwrap())
+                .await;
+
+            let _permit = scheduler.acquire(priority).await;
+
+            issued.lock().await.push(request);
+
+            sleep(Duration::from_millis(100)).await;
+        });
+    });
+
+    while let Some(result) = requests.join_next().await {
+        result.unwrap();
+    }
+
+    let issued = issued.lock().await;
+
+    assert_eq!(issued[0], 1);
+
+    assert_eq!(issued[1..4], [2, 4, 5]);
+
+    let mut low_priority = issued[4..].to_vec();
+
+    low_priority.sort_unstable();
+
+    assert_eq!(low_priority, [3, 6]);
+}
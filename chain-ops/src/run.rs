@@ -10,8 +10,8 @@ use crate::{
         configuration::{self, Configuration},
     },
     task::{
-        application_defined, balance_reporter::BalanceReporter,
-        broadcast::Broadcast, protocol_watcher::ProtocolWatcher,
+        self, application_defined, balance_reporter::BalanceReporter,
+        broadcast::Broadcast, protocol_watcher::ProtocolWatcher, StartDelays,
     },
 };
 
@@ -36,39 +36,57 @@ where
     StartupTasksFunctor: FnOnce() -> StartupTasksIter,
     StartupTasksIter: IntoIterator + Send + 'static,
     StartupTasksIter::IntoIter: Send,
-    StartupTasksIter::Item: application_defined::Id<ServiceConfiguration=configuration::Service> + Unpin,
+    StartupTasksIter::Item: application_defined::Id<ServiceConfiguration=configuration::Service> + Unpin + Send + 'static,
 {
     log::init(logs_directory).context("Failed to initialize logging!")?;
 
+    let startup_tasks: Vec<_> = startup_tasks().into_iter().collect();
+
+    let enabled_tasks: Vec<_> = [
+        task::Id::BalanceReporter,
+        task::Id::Broadcast,
+        task::Id::ProtocolWatcher,
+    ]
+    .iter()
+    .map(task::Id::<StartupTasksIter::Item>::name)
+    .chain(startup_tasks.iter().map(application_defined::Id::name))
+    .collect();
+
     let service_configuration =
-        configuration::Service::read_from_env()
+        configuration::Service::read_from_env(&enabled_tasks)
             .await
             .context("Failed to read service configuration!")?;
 
     let task_creation_context = task_creation_context()
         .context("Failed to construct task creation context!")?;
 
-    service::run({
-        let startup_tasks = startup_tasks();
+    let panic_restart_policy =
+        configuration::PanicRestartPolicy::read_from_env()?;
 
-        move |task_spawner, task_result_rx| async move {
-            Supervisor::<StartupTasksIter::Item>::new(
-                Configuration::new(
-                    service_configuration,
-                    task_creation_context,
-                ),
-                task_spawner,
-                task_result_rx,
-                application_name,
-                application_version,
-                startup_tasks,
-            )
-            .await
-            .context("Failed to create tasks supervisor!")?
-            .run()
-            .await
-            .context("Supervisor exited with an error!")
-        }
+    let start_delays = StartDelays::read_from_env()?;
+
+    let restart_cooldown = configuration::read_restart_cooldown()?;
+
+    service::run(move |task_spawner, task_result_rx| async move {
+        Supervisor::<StartupTasksIter::Item>::new(
+            Configuration::new(
+                service_configuration,
+                task_creation_context,
+                panic_restart_policy,
+                start_delays,
+                restart_cooldown,
+            ),
+            task_spawner,
+            task_result_rx,
+            application_name,
+            application_version,
+            startup_tasks,
+        )
+        .await
+        .context("Failed to create tasks supervisor!")?
+        .run()
+        .await
+        .context("Supervisor exited with an error!")
     })
     .await
     .context("Running service failed!")
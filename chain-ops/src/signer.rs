@@ -1,11 +1,13 @@
 use std::{
     borrow::Borrow,
+    future::Future,
     num::NonZeroU32,
     ops::{Div, Mul},
     sync::Arc,
+    time::Duration,
 };
 
-use anyhow::{anyhow, Context as _, Result};
+use anyhow::{anyhow, bail, Context as _, Result};
 use cosmrs::{
     auth::BaseAccount,
     tendermint::chain::Id as ChainId,
@@ -15,6 +17,8 @@ use cosmrs::{
     },
     AccountId, Amount, Coin, Gas,
 };
+use tokio::time::sleep;
+use tonic::{Code, Status};
 
 use crate::{
     env::ReadFromVar,
@@ -36,6 +40,7 @@ macro_rules! log {
 pub struct Signer {
     query_auth: node::QueryAuth,
     sequence_number: SequenceNumber,
+    fee_multiplier_percent: u32,
     immutable: Arc<Immutable>,
 }
 
@@ -45,6 +50,8 @@ impl Signer {
         signing_key: SigningKey,
         fee_token: String,
         gas_and_fee_configuration: GasAndFeeConfiguration,
+        fee_escalation: Option<FeeEscalation>,
+        expected_chain_id: Option<&str>,
     ) -> Result<Self> {
         let chain_id = node_client
             .clone()
@@ -53,6 +60,8 @@ impl Signer {
             .await
             .context("Failed to fetch network's chain ID!")?;
 
+        Self::verify_chain_id(&chain_id, expected_chain_id)?;
+
         let public_key = signing_key.public_key();
 
         let account_id = public_key
@@ -67,20 +76,27 @@ impl Signer {
             .map_err(|error| anyhow!(error))
             .context("Failed to derive account ID!")?;
 
-        let mut query_auth = node_client.query_auth();
+        let query_auth = node_client.query_auth();
+
+        let address = account_id.to_string();
 
         let BaseAccount {
             account_number,
             sequence: sequence_number,
             ..
-        } = query_auth
-            .account(account_id.to_string())
-            .await
-            .context("Failed to query account information!")?;
+        } = Self::query_account_with_retry(&address, || {
+            let mut query_auth = query_auth.clone();
+
+            let address = address.clone();
+
+            async move { query_auth.account(address).await }
+        })
+        .await?;
 
         Ok(Self {
             query_auth,
             sequence_number,
+            fee_multiplier_percent: FeeEscalation::BASELINE_PERCENT,
             immutable: Arc::new(Immutable {
                 signing_key,
                 public_key,
@@ -88,11 +104,101 @@ impl Signer {
                 account_number,
                 fee_token,
                 gas_and_fee_configuration,
+                fee_escalation,
                 chain_id,
             }),
         })
     }
 
+    /// Queries the account's information, retrying with a backoff on
+    /// transient failures, e.g. a node hiccup during startup.
+    ///
+    /// A missing account is definitive, thus it fails right away.
+    async fn query_account_with_retry<F, Fut, T>(
+        address: &str,
+        mut query: F,
+    ) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        const MAX_ATTEMPTS: u8 = 5;
+
+        let mut backoff = const { Duration::from_secs(1) };
+
+        let mut attempt = 1;
+
+        loop {
+            match query().await {
+                Ok(account) => break Ok(account),
+                Err(error) if Self::is_account_not_found(&error) => {
+                    break Err(error.context(format!(
+                        "Signer's account, \"{address}\", doesn't exist on \
+                        chain! It has to receive funds before it can sign \
+                        transactions."
+                    )));
+                },
+                Err(error) if attempt >= MAX_ATTEMPTS => {
+                    break Err(
+                        error.context("Failed to query account information!")
+                    );
+                },
+                Err(error) => {
+                    log!(warn!(
+                        ?error,
+                        attempt,
+                        ?backoff,
+                        "Failed to query account information! Retrying.",
+                    ));
+
+                    sleep(backoff).await;
+
+                    backoff *= 2;
+
+                    attempt += 1;
+                },
+            }
+        }
+    }
+
+    fn is_account_not_found(error: &anyhow::Error) -> bool {
+        error.chain().any(|cause| {
+            cause
+                .downcast_ref::<Status>()
+                .is_some_and(|status| status.code() == Code::NotFound)
+        })
+    }
+
+    fn verify_chain_id(
+        chain_id: &ChainId,
+        expected_chain_id: Option<&str>,
+    ) -> Result<()> {
+        let Some(expected_chain_id) = expected_chain_id else {
+            return Ok(());
+        };
+
+        if chain_id.as_str() == expected_chain_id {
+            log!(info!(
+                %chain_id,
+                expected = %expected_chain_id,
+                "Network's chain ID matches the expected one.",
+            ));
+
+            Ok(())
+        } else {
+            log!(error!(
+                %chain_id,
+                expected = %expected_chain_id,
+                "Network's chain ID doesn't match the expected one!",
+            ));
+
+            bail!(
+                "Network's chain ID, \"{chain_id}\", doesn't match the \
+                expected one, \"{expected_chain_id}\"!"
+            )
+        }
+    }
+
     #[must_use]
     #[inline]
     pub fn address(&self) -> &str {
@@ -116,7 +222,9 @@ impl Signer {
                 Coin::new(
                     self.immutable
                         .gas_and_fee_configuration
-                        .calculate_fee(gas_limit),
+                        .calculate_fee(gas_limit)
+                        * Amount::from(self.fee_multiplier_percent)
+                        / Amount::from(FeeEscalation::BASELINE_PERCENT),
                     &self.immutable.fee_token,
                 )
                 .map_err(|error| anyhow!(error))
@@ -175,12 +283,91 @@ impl Signer {
             .context("Failed to fetch sequence number!")
     }
 
+    /// Fetches the on-chain sequence number, only ever advancing the local
+    /// one, so transactions still pending in the mempool aren't reused.
+    ///
+    /// Returns whether the local sequence number was advanced.
+    pub async fn refresh_sequence_number(&mut self) -> Result<bool> {
+        self.query_auth
+            .account(self.immutable.account_id.to_string())
+            .await
+            .map(|BaseAccount { sequence, .. }| {
+                let advanced = sequence > self.sequence_number;
+
+                if advanced {
+                    self.sequence_number = sequence;
+                }
+
+                advanced
+            })
+            .context("Failed to refresh sequence number!")
+    }
+
+    /// Fetches the on-chain sequence number and compares it against the
+    /// local one, without modifying the latter.
+    pub async fn query_sequence_drift(&mut self) -> Result<SequenceDrift> {
+        self.query_auth
+            .account(self.immutable.account_id.to_string())
+            .await
+            .map(|BaseAccount { sequence, .. }| SequenceDrift {
+                local: self.sequence_number,
+                on_chain: sequence,
+            })
+            .context("Failed to query sequence number drift!")
+    }
+
+    /// Escalates the fee multiplier after a transaction got rejected because
+    /// of an insufficient fee.
+    ///
+    /// Returns the new multiplier, in percent, or `None` when escalation is
+    /// disabled or the ceiling was already reached.
+    pub fn escalate_fee(&mut self) -> Option<u32> {
+        self.immutable
+            .fee_escalation
+            .and_then(|fee_escalation| {
+                fee_escalation.next(self.fee_multiplier_percent)
+            })
+            .inspect(|&multiplier| self.fee_multiplier_percent = multiplier)
+    }
+
+    /// Decays the fee multiplier one step back towards its baseline after a
+    /// transaction was accepted, so an escalated fee is kept while it's
+    /// still needed, instead of being rediscovered through rejections.
+    ///
+    /// Returns the new multiplier, in percent, or `None` when it was already
+    /// at its baseline.
+    pub fn decay_fee(&mut self) -> Option<u32> {
+        self.immutable
+            .fee_escalation
+            .and_then(|fee_escalation| {
+                fee_escalation.decayed(self.fee_multiplier_percent)
+            })
+            .inspect(|&multiplier| self.fee_multiplier_percent = multiplier)
+    }
+
     #[inline]
     pub fn increment_sequence_number(&mut self) {
         self.sequence_number += 1;
     }
 }
 
+/// Local sequence number compared against the on-chain one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct SequenceDrift {
+    pub local: SequenceNumber,
+    pub on_chain: SequenceNumber,
+}
+
+impl SequenceDrift {
+    /// Returns by how much the on-chain sequence number is ahead of the local
+    /// one, being negative when the local one is ahead instead.
+    #[must_use]
+    pub fn drift(self) -> i128 {
+        i128::from(self.on_chain) - i128::from(self.local)
+    }
+}
+
 #[must_use]
 pub struct GasAndFeeConfiguration {
     pub gas_adjustment_numerator: u32,
@@ -291,6 +478,69 @@ impl ReadFromVar for GasAndFeeConfiguration {
     }
 }
 
+/// Escalation of transactions' fees, applied when they get rejected because
+/// of an insufficient fee, e.g. after the network's minimum gas price rose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct FeeEscalation {
+    factor_percent: u32,
+    max_multiplier_percent: u32,
+}
+
+impl FeeEscalation {
+    pub(crate) const BASELINE_PERCENT: u32 = 100;
+
+    pub fn new(
+        factor_percent: u32,
+        max_multiplier_percent: u32,
+    ) -> Result<Self> {
+        if factor_percent <= Self::BASELINE_PERCENT {
+            bail!(
+                "Fee escalation factor has to be greater than {}%!",
+                Self::BASELINE_PERCENT,
+            );
+        }
+
+        if max_multiplier_percent < Self::BASELINE_PERCENT {
+            bail!(
+                "Fee escalation ceiling can't be less than {}%!",
+                Self::BASELINE_PERCENT,
+            );
+        }
+
+        Ok(Self {
+            factor_percent,
+            max_multiplier_percent,
+        })
+    }
+
+    pub(crate) fn next(self, multiplier_percent: u32) -> Option<u32> {
+        (multiplier_percent < self.max_multiplier_percent).then(|| {
+            let escalated = u64::from(multiplier_percent)
+                * u64::from(self.factor_percent)
+                / u64::from(Self::BASELINE_PERCENT);
+
+            u32::try_from(escalated)
+                .unwrap_or(u32::MAX)
+                .clamp(multiplier_percent + 1, self.max_multiplier_percent)
+        })
+    }
+
+    /// Divides the multiplier by the escalation factor, without going below
+    /// the baseline.
+    pub(crate) fn decayed(self, multiplier_percent: u32) -> Option<u32> {
+        (multiplier_percent > Self::BASELINE_PERCENT).then(|| {
+            let decayed = u64::from(multiplier_percent)
+                * u64::from(Self::BASELINE_PERCENT)
+                / u64::from(self.factor_percent);
+
+            u32::try_from(decayed)
+                .unwrap_or(u32::MAX)
+                .max(Self::BASELINE_PERCENT)
+        })
+    }
+}
+
 struct Immutable {
     signing_key: SigningKey,
     public_key: PublicKey,
@@ -298,5 +548,124 @@ struct Immutable {
     account_number: AccountNumber,
     fee_token: String,
     gas_and_fee_configuration: GasAndFeeConfiguration,
+    fee_escalation: Option<FeeEscalation>,
     chain_id: ChainId,
 }
+
+#[test]
+fn test_chain_id_verification() {
+    let chain_id: ChainId = "pirin-1".parse().unwrap();
+
+    Signer::verify_chain_id(&chain_id, None).unwrap();
+
+    Signer::verify_chain_id(&chain_id, Some("pirin-1")).unwrap();
+
+    Signer::verify_chain_id(&chain_id, Some("rila-1")).unwrap_err();
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_account_query_retries() {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    use tokio::time::Instant;
+
+    fn failing(code: Code) -> Result<u64> {
+        Err(Status::new(code, "Query failed!"))
+            .context("Failed to query account information!")
+    }
+
+    let attempts = AtomicU8::new(0);
+
+    let started_at = Instant::now();
+
+    let account = Signer::query_account_with_retry("nolus1signer", || {
+        let attempt = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async move {
+            if attempt < 2 {
+                failing(Code::Unavailable)
+            } else {
+                Ok(7)
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(account, 7);
+
+    assert_eq!(attempts.load(Ordering::Acquire), 3);
+
+    assert_eq!(started_at.elapsed(), Duration::from_secs(3));
+
+    attempts.store(0, Ordering::Release);
+
+    let error = Signer::query_account_with_retry("nolus1signer", || {
+        _ = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async { failing(Code::NotFound) }
+    })
+    .await
+    .unwrap_err();
+
+    assert_eq!(attempts.load(Ordering::Acquire), 1);
+
+    assert!(error.to_string().contains("doesn't exist on chain"));
+
+    attempts.store(0, Ordering::Release);
+
+    assert!(Signer::query_account_with_retry("nolus1signer", || {
+        _ = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async { failing(Code::Unavailable) }
+    })
+    .await
+    .is_err());
+
+    assert_eq!(attempts.load(Ordering::Acquire), 5);
+}
+
+#[test]
+fn test_fee_escalation_ceiling() {
+    let fee_escalation = FeeEscalation::new(150, 400).unwrap();
+
+    let mut multiplier = FeeEscalation::BASELINE_PERCENT;
+
+    let mut escalations = vec![];
+
+    while let Some(escalated) = fee_escalation.next(multiplier) {
+        escalations.push(escalated);
+
+        multiplier = escalated;
+    }
+
+    assert_eq!(escalations, [150, 225, 337, 400]);
+
+    assert!(FeeEscalation::new(100, 400).is_err());
+
+    assert!(FeeEscalation::new(150, 99).is_err());
+
+    assert_eq!(
+        FeeEscalation::new(101, 100)
+            .unwrap()
+            .next(FeeEscalation::BASELINE_PERCENT),
+        None,
+    );
+}
+
+#[test]
+fn test_fee_escalation_decay() {
+    let fee_escalation = FeeEscalation::new(150, 400).unwrap();
+
+    let mut multiplier = 400;
+
+    let mut decays = vec![];
+
+    while let Some(decayed) = fee_escalation.decayed(multiplier) {
+        decays.push(decayed);
+
+        multiplier = decayed;
+    }
+
+    assert_eq!(decays, [266, 177, 118, 100]);
+}
@@ -0,0 +1,17 @@
+use std::{borrow::Cow, collections::BTreeMap, sync::Arc};
+
+use super::{QueuedRestart, RecentErrors};
+
+/// Snapshot of the supervisor's state, republished whenever it changes, so
+/// it can be inspected while the supervisor runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Introspection {
+    /// Tasks scheduled for a deferred restart, in the order they are due.
+    pub restart_queue: Vec<QueuedRestart>,
+    /// Names of the active application-defined tasks of each protocol.
+    pub protocol_tasks: BTreeMap<Arc<str>, Vec<Cow<'static, str>>>,
+    /// Recent errors of each task which has failed at least once, kept
+    /// across restarts.
+    pub recent_errors: BTreeMap<Cow<'static, str>, RecentErrors>,
+}
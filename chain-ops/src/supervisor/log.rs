@@ -5,6 +5,8 @@ use tokio::task::JoinError;
 
 use crate::task::application_defined::Id;
 
+use super::RecentErrors;
+
 #[inline]
 pub fn balance_reporter_result(result: Result<Result<()>, JoinError>) {
     () = log_task_result("Balance Reporter", result);
@@ -30,6 +32,14 @@ pub fn application_defined_result<T>(
     () = log_task_result(id.name(), result);
 }
 
+pub fn recent_errors(task_id: &str, recent_errors: &RecentErrors) {
+    log!(warn!(
+        task = %task_id,
+        recent_errors = %recent_errors.to_json(),
+        "Recent errors, from the oldest to the most recent one.",
+    ));
+}
+
 fn log_task_result<TaskId>(
     task_id: TaskId,
     result: Result<Result<()>, JoinError>,
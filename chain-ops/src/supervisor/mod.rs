@@ -1,15 +1,18 @@
 use std::{
-    collections::{btree_map::Entry as BTreeMapEntry, BTreeMap, VecDeque},
+    borrow::Cow,
+    collections::{btree_map::Entry as BTreeMapEntry, BTreeMap},
     convert::identity,
-    future::pending,
     marker::PhantomData,
+    sync::Arc,
     time::Duration,
 };
 
 use anyhow::{Context as _, Result};
 use tokio::{
     select,
-    time::{sleep_until, Instant},
+    sync::watch,
+    task::JoinError,
+    time::Instant,
 };
 
 use crate::{
@@ -18,15 +21,25 @@ use crate::{
     task::{
         self,
         application_defined::{self, Id as _},
-        protocol_watcher::Command as ProtocolWatcherCommand,
+        protocol_watcher::{AuditStage, Command as ProtocolWatcherCommand},
         BalanceReporter, Broadcast, ProtocolWatcher, State as TaskState, Task,
         TxPackage,
     },
 };
 
-use self::configuration::Configuration;
+pub use self::{
+    introspection::Introspection,
+    recent_errors::{RecentErrors, RecordedError},
+    restart_queue::QueuedRestart,
+};
+use self::{
+    configuration::{Configuration, PanicRestartPolicy},
+    restart_queue::RestartQueue,
+};
 
 pub mod configuration;
+mod introspection;
+mod recent_errors;
 
 macro_rules! log {
     ($macro:ident!($($body:tt)+)) => {
@@ -38,6 +51,7 @@ macro_rules! log {
 }
 
 pub mod log;
+mod restart_queue;
 
 #[must_use]
 pub struct Supervisor<
@@ -65,10 +79,16 @@ pub struct Supervisor<
     task_result_rx:
         TaskResultsReceiver<task::Id<ApplicationDefined::Id>, Result<()>>,
     task_states: BTreeMap<task::Id<ApplicationDefined::Id>, TaskState>,
-    restart_queue: VecDeque<(Instant, task::Id<ApplicationDefined::Id>)>,
+    not_ready_deferrals: BTreeMap<task::Id<ApplicationDefined::Id>, u32>,
+    recent_errors: BTreeMap<task::Id<ApplicationDefined::Id>, RecentErrors>,
+    restart_queue: RestartQueue<ApplicationDefined::Id>,
     transaction_tx:
         channel::unbounded::Sender<TxPackage<ApplicationDefined::TxExpiration>>,
+    transaction_reclaimer: channel::unbounded::Reclaimer<
+        TxPackage<ApplicationDefined::TxExpiration>,
+    >,
     protocol_watcher_rx: channel::bounded::Receiver<ProtocolWatcherCommand>,
+    introspection_tx: watch::Sender<Introspection>,
     _balance_reporter: PhantomData<BalanceReporter>,
     _broadcast: PhantomData<Broadcast>,
     _protocol_watcher: PhantomData<ProtocolWatcher>,
@@ -122,9 +142,13 @@ where
             task_spawner,
             task_result_rx,
             task_states: BTreeMap::new(),
-            restart_queue: VecDeque::new(),
+            not_ready_deferrals: BTreeMap::new(),
+            recent_errors: BTreeMap::new(),
+            restart_queue: RestartQueue::new(),
             transaction_tx,
+            transaction_reclaimer: channel::unbounded::Reclaimer::new(),
             protocol_watcher_rx,
+            introspection_tx: watch::Sender::new(Introspection::default()),
             _balance_reporter: PhantomData,
             _broadcast: PhantomData,
             _protocol_watcher: PhantomData,
@@ -136,10 +160,21 @@ where
             .start_tasks(transaction_rx, protocol_watcher_tx, tasks)
             .await
             .inspect(|()| log!(info!("Worker tasks started.")))
-            .map(|()| supervisor)
+            .map(|()| {
+                supervisor.publish_introspection();
+
+                supervisor
+            })
             .context("Failed to start initial tasks!")
     }
 
+    /// Returns a handle through which the supervisor's state can be
+    /// inspected while it runs.
+    #[must_use]
+    pub fn introspection(&self) -> watch::Receiver<Introspection> {
+        self.introspection_tx.subscribe()
+    }
+
     #[inline]
     pub async fn run(mut self) -> Result<()> {
         const TASK_RESULTS_CHANNEL_CLOSED_ERROR: &str =
@@ -161,18 +196,57 @@ where
                         .await
                         .context("Failed to handle protocol command!")
                 },
-                task_id = Self::next_restart_task_future(
-                    &mut self.restart_queue,
-                ), if !self.restart_queue.is_empty() => {
+                task_id = self.restart_queue.next(),
+                if !self.restart_queue.is_empty() => {
                     self.run_task(task_id).await
                 },
             )
             .inspect_err(|error| {
                 log!(error!(?error, "Fatal error occurred!"));
             })?;
+
+            self.publish_introspection();
         }
     }
 
+    fn publish_introspection(&self) {
+        let introspection = Introspection {
+            restart_queue: self.restart_queue.snapshot(),
+            protocol_tasks: self.protocol_task_names(),
+            recent_errors: self
+                .recent_errors
+                .iter()
+                .map(|(task_id, recent_errors)| {
+                    (task_id.name(), recent_errors.clone())
+                })
+                .collect(),
+        };
+
+        _ = self.introspection_tx.send_if_modified(|published| {
+            if *published == introspection {
+                false
+            } else {
+                *published = introspection;
+
+                true
+            }
+        });
+    }
+
+    fn protocol_task_names(
+        &self,
+    ) -> BTreeMap<Arc<str>, Vec<Cow<'static, str>>> {
+        protocol_tasks(self.task_states.keys())
+            .into_iter()
+            .map(|(protocol, ids)| {
+                (
+                    protocol,
+                    ids.iter().map(application_defined::Id::name).collect(),
+                )
+            })
+            .collect()
+    }
+
     async fn start_tasks<U>(
         &mut self,
         transaction_rx: channel::unbounded::Receiver<
@@ -190,7 +264,11 @@ where
             ProtocolWatcher,
             ApplicationDefined,
         >::BalanceReporter(self.create_balance_reporter_task())
-            .run(&self.task_spawner, &mut self.task_states)
+            .run(
+                &self.task_spawner,
+                &mut self.task_states,
+                &self.configuration.start_delays,
+            )
             .await
             .context("Failed to start balance reporter task!")?;
 
@@ -200,7 +278,11 @@ where
             ProtocolWatcher,
             ApplicationDefined,
         >::Broadcast(self.create_broadcast_task_with(transaction_rx))
-            .run(&self.task_spawner, &mut self.task_states)
+            .run(
+                &self.task_spawner,
+                &mut self.task_states,
+                &self.configuration.start_delays,
+            )
             .await
             .context("Failed to start broadcaster task!")?;
 
@@ -212,7 +294,11 @@ where
         >::ProtocolWatcher(
             self.create_protocol_watcher_task_with(protocol_watcher_tx),
         )
-            .run(&self.task_spawner, &mut self.task_states)
+            .run(
+                &self.task_spawner,
+                &mut self.task_states,
+                &self.configuration.start_delays,
+            )
             .await
             .context("Failed to start protocol watcher task!")?;
 
@@ -229,10 +315,22 @@ where
         &mut self,
         task_result: TaskResult<task::Id<ApplicationDefined::Id>, Result<()>>,
     ) -> Result<()> {
-        const MAX_CONSEQUENT_RETRIES: u8 = 2;
-
         let task_id = task_result.identifier.clone();
 
+        let panicked =
+            task_result.result.as_ref().is_err_and(JoinError::is_panic);
+
+        if !matches!(task_result.result, Ok(Ok(()))) {
+            let recent_errors = self
+                .recent_errors
+                .entry(task_id.clone())
+                .or_insert_with(RecentErrors::new);
+
+            () = recent_errors.record(&task_result.result);
+
+            log::recent_errors(&task_id.name(), recent_errors);
+        }
+
         () = self
             .handle_task_result(task_result)
             .await
@@ -241,7 +339,11 @@ where
         if let BTreeMapEntry::Occupied(mut entry) =
             self.task_states.entry(task_id)
         {
-            if entry.get_mut().retry() >= MAX_CONSEQUENT_RETRIES {
+            if defer_restart(
+                entry.get().retry(),
+                panicked,
+                self.configuration.panic_restart_policy,
+            ) {
                 let task_id = entry.remove_entry().0;
 
                 self.place_on_restart_queue(task_id)
@@ -283,10 +385,36 @@ where
         };
 
         match result {
-            Ok(task) => task
-                .run(&self.task_spawner, &mut self.task_states)
+            Ok(task) => {
+                _ = self.not_ready_deferrals.remove(&task_id);
+
+                task.run(
+                    &self.task_spawner,
+                    &mut self.task_states,
+                    &self.configuration.start_delays,
+                )
                 .await
-                .map_err(Into::into),
+                .map_err(Into::into)
+            },
+            Err(error) if error.is::<application_defined::NotReady>() => {
+                let deferrals = self
+                    .not_ready_deferrals
+                    .entry(task_id.clone())
+                    .or_default();
+
+                let delay = not_ready_backoff(*deferrals);
+
+                *deferrals = deferrals.saturating_add(1);
+
+                log!(warn!(
+                    task = %task_id.name(),
+                    ?error,
+                    ?delay,
+                    "Task's dependencies are not ready yet! Deferring.",
+                ));
+
+                self.place_on_restart_queue_after(task_id, delay)
+            },
             Err(error) => {
                 log!(error!(
                     task = %task_id.name(),
@@ -304,13 +432,22 @@ where
         BalanceReporter::new(&self.configuration.service_configuration)
     }
 
-    #[inline]
     fn create_broadcast_task(&mut self) -> Broadcast {
         let transaction_rx;
 
         (self.transaction_tx, transaction_rx) =
             channel::unbounded::Channel::new();
 
+        let handed_off =
+            self.transaction_reclaimer.drain_into(&self.transaction_tx);
+
+        if handed_off != 0 {
+            log!(info!(
+                count = handed_off,
+                "Handed off queued transactions to restarted broadcaster.",
+            ));
+        }
+
         self.create_broadcast_task_with(transaction_rx)
     }
 
@@ -322,7 +459,7 @@ where
     ) -> Broadcast {
         Broadcast::new(
             &self.configuration.service_configuration,
-            transaction_rx,
+            self.transaction_reclaimer.wrap(transaction_rx),
         )
     }
 
@@ -356,16 +493,24 @@ where
             "Placing task in deferred restart queue.",
         ));
 
+        let delay = if matches!(task_id, task::Id::ApplicationDefined { .. }) {
+            APPLICATION_DEFINED_RESTART_DELAY
+        } else {
+            const { Duration::from_secs(10) }
+        };
+
+        self.place_on_restart_queue_after(task_id, delay)
+    }
+
+    fn place_on_restart_queue_after(
+        &mut self,
+        task_id: task::Id<ApplicationDefined::Id>,
+        delay: Duration,
+    ) -> Result<()> {
         Instant::now()
-            .checked_add(
-                if matches!(task_id, task::Id::ApplicationDefined { .. }) {
-                    const { Duration::from_secs(180) }
-                } else {
-                    const { Duration::from_secs(10) }
-                },
-            )
+            .checked_add(delay)
             .map(|instant| {
-                () = self.restart_queue.push_back((instant, task_id));
+                () = self.restart_queue.push(instant, task_id);
             })
             .context("Failed to calculate task restart timestamp!")
     }
@@ -385,7 +530,14 @@ where
             } => {
                 log::broadcast_result(result);
 
+                // The broadcaster already exited, thus it won't be reported
+                // back while cancelling the rest.
+                _ = self.task_states.remove(&task::Id::Broadcast);
+
                 self.cancel_tasks().await.context("Killing tasks failed!")?;
+
+                self.restart_after_cooldown()
+                    .context("Failed to schedule restarting tasks!")?;
             },
             TaskResult {
                 identifier: task::Id::ProtocolWatcher,
@@ -447,54 +599,248 @@ where
         Ok(())
     }
 
+    /// Schedules the built-in tasks to be started again once the cooldown
+    /// elapses.
+    ///
+    /// Pending restarts of application-defined tasks, e.g. ones deferred
+    /// until their dependencies are ready, are kept, but postponed until the
+    /// built-in tasks are started again. The rest of the application-defined
+    /// tasks are left to the restarted protocol watcher.
+    fn restart_after_cooldown(&mut self) -> Result<()> {
+        let cooldown = self.configuration.restart_cooldown;
+
+        log!(warn!(
+            ?cooldown,
+            "All tasks cancelled. Restarting them after cooldown.",
+        ));
+
+        let restart_at = Instant::now()
+            .checked_add(cooldown)
+            .context("Failed to calculate task restart timestamp!")?;
+
+        let application_defined: Vec<_> = self
+            .restart_queue
+            .drain()
+            .filter(|(_, task_id)| {
+                matches!(task_id, task::Id::ApplicationDefined(_))
+            })
+            .collect();
+
+        for task_id in [
+            task::Id::BalanceReporter,
+            task::Id::Broadcast,
+            task::Id::ProtocolWatcher,
+        ] {
+            () = self.restart_queue.push(restart_at, task_id);
+        }
+
+        for (eta, task_id) in application_defined {
+            () = self.restart_queue.push(eta.max(restart_at), task_id);
+        }
+
+        Ok(())
+    }
+
     async fn handle_protocol_command(
         &mut self,
         protocol_command: ProtocolWatcherCommand,
     ) -> Result<()> {
         match protocol_command {
-            ProtocolWatcherCommand::ProtocolAdded(protocol) => {
-                for id in ApplicationDefined::protocol_task_set_ids(protocol) {
-                    self.run_task(task::Id::ApplicationDefined(id)).await?;
+            ProtocolWatcherCommand::ProtocolAdded(ref protocol) => {
+                for id in
+                    ApplicationDefined::protocol_task_set_ids(protocol.clone())
+                {
+                    let task_id = task::Id::ApplicationDefined(id);
+
+                    if self.task_states.contains_key(&task_id) {
+                        log!(debug!(
+                            task = %task_id.name(),
+                            "Protocol's task is already running. Skipping.",
+                        ));
+
+                        continue;
+                    }
+
+                    if self.restart_queue.contains(&task_id) {
+                        log!(debug!(
+                            task = %task_id.name(),
+                            "Protocol's task is already scheduled for restart. \
+                            Skipping.",
+                        ));
+
+                        continue;
+                    }
+
+                    self.run_task(task_id).await?;
                 }
             },
             ProtocolWatcherCommand::ProtocolRemoved(ref protocol) => {
-                () = self.task_states.retain(|id, _| match id {
-                    task::Id::ApplicationDefined(id) => {
+                let retained = |id: &task::Id<ApplicationDefined::Id>| {
+                    if let task::Id::ApplicationDefined(id) = id {
                         id.protocol().map_or(true, |task_protocol| {
                             task_protocol != protocol
                         })
-                    },
-                    _ => true,
-                });
+                    } else {
+                        true
+                    }
+                };
+
+                () = self.task_states.retain(|id, _| retained(id));
+
+                () = self.not_ready_deferrals.retain(|id, _| retained(id));
+
+                () = self.recent_errors.retain(|id, _| retained(id));
             },
         }
 
+        protocol_command.log_audit_event(AuditStage::Applied);
+
+        log!(info!(
+            protocol_tasks = ?self.protocol_task_names(),
+            "Protocols' active tasks.",
+        ));
+
         Ok(())
     }
+}
 
-    async fn next_restart_task_future<U>(
-        restart_queue: &mut VecDeque<(Instant, task::Id<U>)>,
-    ) -> task::Id<U>
-    where
-        U: application_defined::Id,
-    {
-        if let Some(&(instant, _)) = restart_queue.front() {
-            sleep_until(instant).await;
-
-            if let Some((_, id)) = restart_queue.pop_front() {
-                id
+/// Groups the application-defined tasks by the protocol they belong to,
+/// leaving out tasks which aren't tied to a protocol.
+fn protocol_tasks<'r, Id>(
+    task_ids: impl IntoIterator<Item = &'r task::Id<Id>>,
+) -> BTreeMap<Arc<str>, Vec<Id>>
+where
+    Id: application_defined::Id,
+{
+    task_ids
+        .into_iter()
+        .filter_map(|task_id| {
+            if let task::Id::ApplicationDefined(id) = task_id {
+                id.protocol().map(|protocol| (protocol.clone(), id.clone()))
             } else {
-                unreachable!(
-                    "Restart queue cannot be empty as it's behind a mutable \
-                    reference and already known to have at least one element!",
-                )
+                None
             }
-        } else {
-            pending().await
-        }
-    }
+        })
+        .fold(BTreeMap::new(), |mut protocol_tasks, (protocol, id)| {
+            protocol_tasks.entry(protocol).or_default().push(id);
+
+            protocol_tasks
+        })
+}
+
+const APPLICATION_DEFINED_RESTART_DELAY: Duration = Duration::from_secs(180);
+
+/// Delay before retrying the creation of a task whose dependencies aren't
+/// ready yet, doubling with each consecutive deferral up to the regular
+/// restart delay.
+fn not_ready_backoff(deferrals: u32) -> Duration {
+    const INITIAL_DELAY: Duration = Duration::from_secs(15);
+
+    1_u32
+        .checked_shl(deferrals)
+        .and_then(|multiplier| INITIAL_DELAY.checked_mul(multiplier))
+        .map_or(APPLICATION_DEFINED_RESTART_DELAY, |delay| {
+            delay.min(APPLICATION_DEFINED_RESTART_DELAY)
+        })
+}
+
+/// Decides whether an exited task is placed on the deferred restart queue
+/// instead of being restarted right away.
+fn defer_restart(
+    retry: u8,
+    panicked: bool,
+    panic_restart_policy: PanicRestartPolicy,
+) -> bool {
+    const MAX_CONSEQUENT_RETRIES: u8 = 2;
+
+    retry >= MAX_CONSEQUENT_RETRIES
+        || (panicked
+            && matches!(panic_restart_policy, PanicRestartPolicy::Defer))
 }
 
 #[cold]
 #[inline]
 fn cold() {}
+
+#[tokio::test]
+async fn test_panicked_task_restart_decision() {
+    async fn panicked<F>(task: F) -> bool
+    where
+        F: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        tokio::spawn(task)
+            .await
+            .as_ref()
+            .is_err_and(JoinError::is_panic)
+    }
+
+    let erroring =
+        panicked(async { Err(anyhow::anyhow!("Task failed!")) }).await;
+
+    let panicking = panicked(async { panic!("Task panicked!") }).await;
+
+    assert!(!erroring);
+
+    assert!(panicking);
+
+    assert!(!defer_restart(0, erroring, PanicRestartPolicy::Defer));
+
+    assert!(!defer_restart(1, erroring, PanicRestartPolicy::Defer));
+
+    assert!(defer_restart(2, erroring, PanicRestartPolicy::Defer));
+
+    assert!(defer_restart(0, panicking, PanicRestartPolicy::Defer));
+
+    assert!(!defer_restart(0, panicking, PanicRestartPolicy::Retry));
+
+    assert!(defer_restart(2, panicking, PanicRestartPolicy::Retry));
+}
+
+#[test]
+fn test_not_ready_backoff() {
+    assert_eq!(not_ready_backoff(0), Duration::from_secs(15));
+
+    assert_eq!(not_ready_backoff(1), Duration::from_secs(30));
+
+    assert_eq!(not_ready_backoff(3), Duration::from_secs(120));
+
+    assert_eq!(not_ready_backoff(4), APPLICATION_DEFINED_RESTART_DELAY);
+
+    assert_eq!(
+        not_ready_backoff(u32::MAX),
+        APPLICATION_DEFINED_RESTART_DELAY,
+    );
+}
+
+#[test]
+fn test_protocol_tasks_mapping() {
+    use crate::testing::TestId;
+
+    let id = TestId::with_protocol;
+
+    let task_states: BTreeMap<_, ()> = [
+        task::Id::BalanceReporter,
+        task::Id::Broadcast,
+        task::Id::ProtocolWatcher,
+        task::Id::ApplicationDefined(id("NEUTRON", "Price Fetcher")),
+        task::Id::ApplicationDefined(id("OSMOSIS", "Price Alarms")),
+        task::Id::ApplicationDefined(id("OSMOSIS", "Price Fetcher")),
+    ]
+    .into_iter()
+    .map(|task_id| (task_id, ()))
+    .collect();
+
+    assert_eq!(
+        protocol_tasks(task_states.keys()),
+        BTreeMap::from([
+            ("NEUTRON".into(), vec![id("NEUTRON", "Price Fetcher")]),
+            (
+                "OSMOSIS".into(),
+                vec![
+                    id("OSMOSIS", "Price Alarms"),
+                    id("OSMOSIS", "Price Fetcher"),
+                ],
+            ),
+        ]),
+    );
+}
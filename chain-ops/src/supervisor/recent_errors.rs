@@ -0,0 +1,133 @@
+use std::collections::VecDeque;
+
+use anyhow::Result;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{ser::SerializeStruct as _, Serialize, Serializer};
+use tokio::task::JoinError;
+
+/// Bounded history of the errors a task exited with, kept by the supervisor
+/// across restarts, including deferred ones, so the recent failure pattern
+/// can be inspected at once instead of being pieced together from separate
+/// log entries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+#[must_use]
+pub struct RecentErrors(VecDeque<RecordedError>);
+
+impl RecentErrors {
+    pub const CAPACITY: usize = 8;
+
+    pub(super) const fn new() -> Self {
+        Self(VecDeque::new())
+    }
+
+    /// Records the error the task exited with, if any.
+    pub(super) fn record(&mut self, result: &Result<Result<()>, JoinError>) {
+        match result {
+            Ok(Ok(())) => {},
+            Ok(Err(error)) => self.push(Utc::now(), format!("{error:#}")),
+            Err(error) => self.push(Utc::now(), error.to_string()),
+        }
+    }
+
+    /// Returns the recorded errors, ordered from the oldest to the most
+    /// recent one.
+    pub fn iter(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = &RecordedError> + ExactSizeIterator
+    {
+        self.0.iter()
+    }
+
+    /// Returns the history as a JSON array of objects, each carrying the
+    /// RFC 3339 timestamp and the error.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json_wasm::to_string(self).unwrap_or_else(|error| {
+            unreachable!(
+                "Recorded errors consist only of strings and can't fail to \
+                serialize! Error: {error}",
+            )
+        })
+    }
+
+    fn push(&mut self, at: DateTime<Utc>, error: String) {
+        if self.0.len() == Self::CAPACITY {
+            _ = self.0.pop_front();
+        }
+
+        self.0.push_back(RecordedError {
+            at,
+            error: error.into(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedError {
+    at: DateTime<Utc>,
+    error: Box<str>,
+}
+
+impl RecordedError {
+    #[must_use]
+    pub const fn at(&self) -> DateTime<Utc> {
+        self.at
+    }
+
+    #[must_use]
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+}
+
+impl Serialize for RecordedError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut recorded = serializer.serialize_struct("RecordedError", 2)?;
+
+        () = recorded.serialize_field(
+            "at",
+            &self.at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        )?;
+
+        () = recorded.serialize_field("error", &self.error)?;
+
+        recorded.end()
+    }
+}
+
+#[test]
+fn test_recent_errors_retain_latest() {
+    use chrono::TimeDelta;
+
+    let started_at = DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+        .unwrap()
+        .to_utc();
+
+    let mut recent_errors = RecentErrors::new();
+
+    for error in 0..RecentErrors::CAPACITY + 3 {
+        recent_errors.push(
+            started_at + TimeDelta::seconds(error.try_into().unwrap()),
+            format!("Error #{error}"),
+        );
+    }
+
+    assert_eq!(recent_errors.iter().len(), RecentErrors::CAPACITY);
+
+    for (index, recorded) in recent_errors.iter().enumerate() {
+        assert_eq!(recorded.error(), format!("Error #{}", index + 3));
+
+        assert_eq!(
+            recorded.at() - started_at,
+            TimeDelta::seconds((index + 3).try_into().unwrap()),
+        );
+    }
+
+    assert!(recent_errors.to_json().starts_with(
+        r#"[{"at":"2024-01-01T00:00:03.000Z","error":"Error #3"},{"at":"#,
+    ));
+}
@@ -0,0 +1,225 @@
+use std::{borrow::Cow, collections::VecDeque, future::pending};
+
+use tokio::time::{sleep_until, Instant};
+
+use crate::task::{self, application_defined};
+
+/// Tasks scheduled for a deferred restart, in the order they are due.
+#[must_use]
+pub(super) struct RestartQueue<Id>(VecDeque<(Instant, task::Id<Id>)>)
+where
+    Id: application_defined::Id;
+
+impl<Id> RestartQueue<Id>
+where
+    Id: application_defined::Id,
+{
+    pub const fn new() -> Self {
+        Self(VecDeque::new())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, task_id: &task::Id<Id>) -> bool {
+        self.0.iter().any(|(_, queued)| queued == task_id)
+    }
+
+    /// Removes all entries, in the order they are due.
+    pub fn drain(
+        &mut self,
+    ) -> impl Iterator<Item = (Instant, task::Id<Id>)> + '_ {
+        self.0.drain(..)
+    }
+
+    /// Schedules the task's restart, keeping entries due at the same time in
+    /// the order they were added.
+    pub fn push(&mut self, eta: Instant, task_id: task::Id<Id>) {
+        log!(info!(
+            task = %task_id.name(),
+            eta = ?eta.saturating_duration_since(Instant::now()),
+            "Task added to restart queue.",
+        ));
+
+        let index = self.0.partition_point(|&(queued, _)| queued <= eta);
+
+        () = self.0.insert(index, (eta, task_id));
+
+        self.log_contents();
+    }
+
+    pub fn snapshot(&self) -> Vec<QueuedRestart> {
+        self.0
+            .iter()
+            .map(|(eta, task_id)| QueuedRestart {
+                task: task_id.name(),
+                eta: *eta,
+            })
+            .collect()
+    }
+
+    /// Waits until the task at the front of the queue is due and removes it.
+    ///
+    /// Never resolves when the queue is empty.
+    pub async fn next(&mut self) -> task::Id<Id> {
+        if let Some(&(eta, _)) = self.0.front() {
+            sleep_until(eta).await;
+
+            if let Some((_, task_id)) = self.0.pop_front() {
+                log!(info!(
+                    task = %task_id.name(),
+                    "Restart queue entry fired.",
+                ));
+
+                self.log_contents();
+
+                task_id
+            } else {
+                unreachable!(
+                    "Restart queue cannot be empty as it's behind a mutable \
+                    reference and already known to have at least one element!",
+                )
+            }
+        } else {
+            pending().await
+        }
+    }
+
+    fn log_contents(&self) {
+        let now = Instant::now();
+
+        log!(info!(
+            queue = ?self
+                .0
+                .iter()
+                .map(|(eta, task_id)| {
+                    (task_id.name(), eta.saturating_duration_since(now))
+                })
+                .collect::<Vec<_>>(),
+            "Restart queue contents.",
+        ));
+    }
+}
+
+/// Snapshot of a task scheduled for a deferred restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct QueuedRestart {
+    pub task: Cow<'static, str>,
+    pub eta: Instant,
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_queue_snapshot() {
+    use std::time::Duration;
+
+    use crate::testing::TestId;
+
+    let now = Instant::now();
+
+    let mut restart_queue = RestartQueue::new();
+
+    restart_queue.push(now + Duration::from_secs(10), task::Id::Broadcast);
+
+    restart_queue.push(
+        now + Duration::from_secs(180),
+        task::Id::ApplicationDefined(TestId::new("OSMOSIS")),
+    );
+
+    restart_queue
+        .push(now + Duration::from_secs(190), task::Id::ProtocolWatcher);
+
+    assert_eq!(
+        restart_queue.snapshot(),
+        [
+            QueuedRestart {
+                task: "Broadcast".into(),
+                eta: now + Duration::from_secs(10),
+            },
+            QueuedRestart {
+                task: "OSMOSIS".into(),
+                eta: now + Duration::from_secs(180),
+            },
+            QueuedRestart {
+                task: "Protocol Watcher".into(),
+                eta: now + Duration::from_secs(190),
+            },
+        ],
+    );
+
+    assert_eq!(restart_queue.next().await, task::Id::Broadcast);
+
+    assert_eq!(now.elapsed(), Duration::from_secs(10));
+
+    assert!(!restart_queue.contains(&task::Id::Broadcast));
+
+    assert!(restart_queue
+        .contains(&task::Id::ApplicationDefined(TestId::new("OSMOSIS"))));
+
+    assert_eq!(
+        restart_queue
+            .snapshot()
+            .into_iter()
+            .map(|queued| queued.task)
+            .collect::<Vec<_>>(),
+        ["OSMOSIS", "Protocol Watcher"],
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_queue_ordered_by_eta() {
+    use std::time::Duration;
+
+    use crate::testing::TestId;
+
+    let now = Instant::now();
+
+    let mut restart_queue = RestartQueue::new();
+
+    restart_queue.push(
+        now + Duration::from_secs(180),
+        task::Id::ApplicationDefined(TestId::new("OSMOSIS")),
+    );
+
+    restart_queue.push(now + Duration::from_secs(10), task::Id::Broadcast);
+
+    restart_queue
+        .push(now + Duration::from_secs(180), task::Id::ProtocolWatcher);
+
+    restart_queue.push(
+        now + Duration::from_secs(60),
+        task::Id::ApplicationDefined(TestId::new("NEUTRON")),
+    );
+
+    assert_eq!(
+        restart_queue
+            .snapshot()
+            .into_iter()
+            .map(|queued| queued.task)
+            .collect::<Vec<_>>(),
+        ["Broadcast", "NEUTRON", "OSMOSIS", "Protocol Watcher"],
+    );
+
+    assert_eq!(restart_queue.next().await, task::Id::Broadcast);
+
+    assert_eq!(now.elapsed(), Duration::from_secs(10));
+
+    assert_eq!(
+        restart_queue.next().await,
+        task::Id::ApplicationDefined(TestId::new("NEUTRON")),
+    );
+
+    assert_eq!(now.elapsed(), Duration::from_secs(60));
+
+    assert_eq!(
+        restart_queue.next().await,
+        task::Id::ApplicationDefined(TestId::new("OSMOSIS")),
+    );
+
+    assert_eq!(restart_queue.next().await, task::Id::ProtocolWatcher);
+
+    assert_eq!(now.elapsed(), Duration::from_secs(180));
+
+    assert!(restart_queue.is_empty());
+}
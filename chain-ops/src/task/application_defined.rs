@@ -1,6 +1,7 @@
 use std::{borrow::Cow, fmt::Debug, future::Future, sync::Arc};
 
 use anyhow::Result;
+use thiserror::Error as ThisError;
 
 use crate::channel;
 
@@ -38,3 +39,12 @@ pub trait Id: Debug + Clone + Ord + Send + Sized + 'static {
         >,
     ) -> impl Future<Output = Result<Self::Task>> + Send + 'r;
 }
+
+/// Attached as context to task creation errors caused by dependencies which
+/// aren't available yet, e.g. contracts of a protocol which is still being
+/// deployed.
+///
+/// Such tasks are deferred with a backoff instead of being treated as failed.
+#[derive(Debug, ThisError)]
+#[error("Task's dependencies are not ready yet!")]
+pub struct NotReady;
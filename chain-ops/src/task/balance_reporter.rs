@@ -93,7 +93,11 @@ impl BuiltIn for BalanceReporter {
 impl super::BalanceReporter for BalanceReporter {
     fn new(service_configuration: &Self::ServiceConfiguration) -> Self {
         Self::new(
-            service_configuration.node_client().clone().query_bank(),
+            service_configuration
+                .node_client()
+                .clone()
+                .query_bank()
+                .with_priority(node::Priority::Low),
             service_configuration.signer().address().into(),
             service_configuration.signer().fee_token().into(),
             service_configuration.balance_reporter_idle_duration(),
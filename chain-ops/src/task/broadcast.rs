@@ -1,17 +1,43 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    borrow::Borrow,
+    fs::OpenOptions,
+    future::Future,
+    io::Write as _,
+    num::NonZeroU32,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
 
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, bail, Context as _, Error, Result};
 use cosmrs::{
     proto::cosmos::base::abci::v1beta1::TxResponse,
     tendermint::abci::Code as TxCode,
-    tx::{Body, Raw, Raw as RawTx},
+    tx::{Body, Raw, Raw as RawTx, SequenceNumber},
     Gas,
 };
-use tokio::{sync::mpsc, time::sleep};
+use data_encoding::BASE64;
+use thiserror::Error as ThisError;
+use tokio::{
+    sync::oneshot,
+    task::spawn_blocking,
+    time::{sleep, Instant},
+};
+use tonic::{Code, Status};
+use tracing::Instrument as _;
 
-use crate::{channel, node, signer::Signer, supervisor::configuration};
+use crate::{
+    channel,
+    env::ReadFromVar,
+    node,
+    signer::{self, SequenceDrift},
+    supervisor::configuration,
+    tx::{self, OutcomeCategory},
+};
 
-use super::{BuiltIn, Runnable, RunnableState, TxExpiration, TxPackage};
+use super::{
+    BuiltIn, Runnable, RunnableState, TxExpiration, TxFeedback, TxPackage,
+};
 
 macro_rules! log_simulation {
     ($macro:ident![$source:expr]($($body:tt)+)) => {
@@ -43,37 +69,197 @@ macro_rules! log_broadcast_with_source {
     };
 }
 
+/// Node endpoints through which the broadcaster simulates and broadcasts
+/// transactions.
+pub trait BroadcastClient: Send + 'static {
+    fn simulate(
+        &mut self,
+        tx: RawTx,
+    ) -> impl Future<Output = Result<Gas>> + Send + '_;
+
+    fn broadcast(
+        &mut self,
+        tx: RawTx,
+        mode: node::BroadcastMode,
+    ) -> impl Future<Output = Result<TxResponse>> + Send + '_;
+}
+
+impl BroadcastClient for node::BroadcastTx {
+    #[inline]
+    fn simulate(
+        &mut self,
+        tx: RawTx,
+    ) -> impl Future<Output = Result<Gas>> + Send + '_ {
+        self.simulate(tx)
+    }
+
+    #[inline]
+    fn broadcast(
+        &mut self,
+        tx: RawTx,
+        mode: node::BroadcastMode,
+    ) -> impl Future<Output = Result<TxResponse>> + Send + '_ {
+        self.broadcast(tx, mode)
+    }
+}
+
+/// Account on whose behalf the broadcaster signs transactions, along with
+/// its sequence number and fee multiplier.
+pub trait TxSigner: Send + 'static {
+    fn tx(&self, body: &Body, gas_limit: Gas) -> Result<RawTx>;
+
+    fn tx_with_gas_adjustment(
+        &self,
+        body: &Body,
+        required_gas: Gas,
+        hard_gas_limit: Gas,
+    ) -> Result<RawTx>;
+
+    fn sequence_number(&self) -> SequenceNumber;
+
+    fn increment_sequence_number(&mut self);
+
+    fn fetch_sequence_number(
+        &mut self,
+    ) -> impl Future<Output = Result<()>> + Send + '_;
+
+    fn refresh_sequence_number(
+        &mut self,
+    ) -> impl Future<Output = Result<bool>> + Send + '_;
+
+    fn query_sequence_drift(
+        &mut self,
+    ) -> impl Future<Output = Result<SequenceDrift>> + Send + '_;
+
+    fn escalate_fee(&mut self) -> Option<u32>;
+
+    fn decay_fee(&mut self) -> Option<u32>;
+}
+
+impl TxSigner for signer::Signer {
+    #[inline]
+    fn tx(&self, body: &Body, gas_limit: Gas) -> Result<RawTx> {
+        self.tx(body, gas_limit)
+    }
+
+    #[inline]
+    fn tx_with_gas_adjustment(
+        &self,
+        body: &Body,
+        required_gas: Gas,
+        hard_gas_limit: Gas,
+    ) -> Result<RawTx> {
+        self.tx_with_gas_adjustment(body, required_gas, hard_gas_limit)
+    }
+
+    #[inline]
+    fn sequence_number(&self) -> SequenceNumber {
+        self.sequence_number()
+    }
+
+    #[inline]
+    fn increment_sequence_number(&mut self) {
+        self.increment_sequence_number();
+    }
+
+    #[inline]
+    fn fetch_sequence_number(
+        &mut self,
+    ) -> impl Future<Output = Result<()>> + Send + '_ {
+        self.fetch_sequence_number()
+    }
+
+    #[inline]
+    fn refresh_sequence_number(
+        &mut self,
+    ) -> impl Future<Output = Result<bool>> + Send + '_ {
+        self.refresh_sequence_number()
+    }
+
+    #[inline]
+    fn query_sequence_drift(
+        &mut self,
+    ) -> impl Future<Output = Result<SequenceDrift>> + Send + '_ {
+        self.query_sequence_drift()
+    }
+
+    #[inline]
+    fn escalate_fee(&mut self) -> Option<u32> {
+        self.escalate_fee()
+    }
+
+    #[inline]
+    fn decay_fee(&mut self) -> Option<u32> {
+        self.decay_fee()
+    }
+}
+
 #[must_use]
-pub struct Broadcast<Expiration>
-where
+pub struct Broadcast<
+    Expiration,
+    Client = node::BroadcastTx,
+    Signer = signer::Signer,
+> where
     Expiration: TxExpiration,
+    Client: BroadcastClient,
+    Signer: TxSigner,
 {
-    client: node::BroadcastTx,
+    client: Client,
+    mode: node::BroadcastMode,
     signer: Signer,
-    transaction_rx: mpsc::UnboundedReceiver<TxPackage<Expiration>>,
+    transaction_rx:
+        channel::unbounded::ReclaimableReceiver<TxPackage<Expiration>>,
     delay_duration: Duration,
     retry_delay_duration: Duration,
+    gas_estimation: GasEstimation,
+    max_simulation_gas: Option<Gas>,
+    simulation_failure: SimulationFailure,
+    sequence_refresh: Option<SequenceRefresh>,
+    offline_output: Option<OfflineOutput>,
     consecutive_errors: u8,
 }
 
-impl<Expiration> Broadcast<Expiration>
+impl<Expiration, Client, Signer> Broadcast<Expiration, Client, Signer>
 where
     Expiration: TxExpiration,
+    Client: BroadcastClient,
+    Signer: TxSigner,
 {
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
-        client: node::BroadcastTx,
+        client: Client,
+        mode: node::BroadcastMode,
         signer: Signer,
-        transaction_rx: mpsc::UnboundedReceiver<TxPackage<Expiration>>,
+        transaction_rx: channel::unbounded::ReclaimableReceiver<
+            TxPackage<Expiration>,
+        >,
         delay_duration: Duration,
         retry_delay_duration: Duration,
+        gas_strategy: GasStrategy,
+        max_simulation_gas: Option<Gas>,
+        simulation_failure: SimulationFailure,
+        sequence_refresh_interval: Option<Duration>,
+        offline_output: Option<OfflineOutput>,
     ) -> Self {
         Self {
             client,
+            mode,
             signer,
             transaction_rx,
             delay_duration,
             retry_delay_duration,
+            gas_estimation: GasEstimation::new(gas_strategy),
+            max_simulation_gas,
+            simulation_failure,
+            // Written transactions are never confirmed, thus the on-chain
+            // sequence number never catches up with the local one.
+            sequence_refresh: match (sequence_refresh_interval, &offline_output)
+            {
+                (Some(interval), None) => Some(SequenceRefresh::new(interval)),
+                _ => None,
+            },
+            offline_output,
             consecutive_errors: 0,
         }
     }
@@ -84,7 +270,41 @@ where
         source: &Arc<str>,
         hard_gas_limit: Gas,
         fallback_gas: Gas,
-    ) -> Result<RawTx> {
+    ) -> Result<(RawTx, Option<Gas>)> {
+        if self.offline_output.is_some() {
+            let gas = if fallback_gas == 0 {
+                hard_gas_limit
+            } else {
+                fallback_gas
+            };
+
+            log_simulation!(info![source](
+                %gas,
+                "Signing offline. Skipping simulation.",
+            ));
+
+            return self
+                .signer
+                .tx(tx, gas)
+                .map(|raw_tx| (raw_tx, None))
+                .context("Failed to sign transaction intended for writing!");
+        }
+
+        if !self.gas_estimation.should_simulate(fallback_gas) {
+            log_simulation!(info![source](
+                %fallback_gas,
+                "Skipping simulation. Using fallback gas.",
+            ));
+
+            return self
+                .signer
+                .tx(tx, fallback_gas)
+                .map(|raw_tx| (raw_tx, None))
+                .context(
+                    "Failed to sign transaction intended for broadcasting!",
+                );
+        }
+
         let result = self
             .client
             .simulate(
@@ -98,40 +318,39 @@ where
             Ok(gas) => {
                 log_simulation!(info![source]("Estimated gas: {gas}"));
 
-                self.signer.tx_with_gas_adjustment(tx, gas, hard_gas_limit)
+                if let Some(gas) =
+                    within_max_simulation_gas(gas, self.max_simulation_gas)
+                {
+                    self.signer
+                        .tx_with_gas_adjustment(tx, gas, hard_gas_limit)
+                        .map(|raw_tx| (raw_tx, Some(gas)))
+                } else {
+                    log_simulation!(warn![source](
+                        simulated_gas = gas,
+                        max_simulation_gas = self.max_simulation_gas,
+                        %fallback_gas,
+                        "Simulated gas exceeds the maximum allowed. Using \
+                        fallback gas.",
+                    ));
+
+                    self.signer
+                        .tx(tx, fallback_gas)
+                        .map(|raw_tx| (raw_tx, None))
+                }
             },
             Err(error) => {
-                log_simulation!(error![source](
-                    %fallback_gas,
-                    ?error,
-                    "Simulation failed. Using fallback gas.",
-                ));
+                let gas = self.simulation_failure.fallback_gas(
+                    source,
+                    fallback_gas,
+                    error,
+                )?;
 
-                self.signer.tx(tx, fallback_gas)
+                self.signer.tx(tx, gas).map(|raw_tx| (raw_tx, None))
             },
         }
         .context("Failed to sign transaction intended for broadcasting!")
     }
 
-    fn log_tx_response(source: &str, tx_code: TxCode, response: &TxResponse) {
-        match tx_code {
-            TxCode::Ok => {
-                log_broadcast_with_source!(info![source](
-                    hash = %response.txhash,
-                    "Transaction broadcast successful.",
-                ));
-            },
-            TxCode::Err(code) => {
-                log_broadcast_with_source!(error![source](
-                    hash = %response.txhash,
-                    log = ?response.raw_log,
-                    code = %code,
-                    "Transaction broadcast failed!",
-                ));
-            },
-        }
-    }
-
     async fn fetch_sequence_number(&mut self) -> Result<()> {
         log_broadcast!(info!("Fetching sequence number."));
 
@@ -143,6 +362,24 @@ where
         })
     }
 
+    async fn refresh_sequence_number(&mut self) {
+        match self.signer.refresh_sequence_number().await {
+            Ok(true) => {
+                log_broadcast!(warn!(
+                    value = self.signer.sequence_number(),
+                    "Sequence number drifted. Advanced to on-chain value.",
+                ));
+            },
+            Ok(false) => {},
+            Err(error) => {
+                log_broadcast!(error!(
+                    ?error,
+                    "Periodic sequence number refresh failed!",
+                ));
+            },
+        }
+    }
+
     async fn broadcast_tx(
         &mut self,
         TxPackage {
@@ -152,12 +389,21 @@ where
             fallback_gas,
             feedback_sender,
             expiration,
+            correlation_id: _,
         }: TxPackage<Expiration>,
     ) -> Result<()> {
         const SIGNATURE_VERIFICATION_ERROR_CODE: u32 = 32;
 
+        let expiration = expiration.with_attempt_window();
+
         'broadcast_loop: loop {
-            let raw_tx = self
+            // Checked before signing, so a skipped transaction never
+            // consumes a sequence number.
+            if feedback_receiver_gone(&source, &feedback_sender) {
+                break 'broadcast_loop Ok(());
+            }
+
+            let (raw_tx, simulated_gas) = match self
                 .simulate_and_sign_tx(
                     tx_body,
                     &source,
@@ -165,7 +411,56 @@ where
                     fallback_gas,
                 )
                 .await
-                .context("Failed to simulate and sign transaction!")?;
+            {
+                Ok(signed) => signed,
+                Err(error) if error.is::<SimulationFailed>() => {
+                    if feedback_sender
+                        .send(TxFeedback::new(
+                            tx::simulation_failed_response(&error),
+                            None,
+                        ))
+                        .is_err()
+                    {
+                        log_broadcast_with_source!(debug![source](
+                            "Source stopped awaiting the transaction's \
+                            result.",
+                        ));
+                    }
+
+                    break 'broadcast_loop Ok(());
+                },
+                Err(error) => {
+                    return Err(error)
+                        .context("Failed to simulate and sign transaction!");
+                },
+            };
+
+            if let Some(offline_output) = &self.offline_output {
+                offline_output
+                    .write(raw_tx)
+                    .await
+                    .context("Failed to write signed transaction!")?;
+
+                // Written transactions are expected to be broadcast in the
+                // same order, thus each of them consumes a sequence number.
+                self.signer.increment_sequence_number();
+
+                log_broadcast_with_source!(info![source](
+                    sequence = self.signer.sequence_number(),
+                    "Signed transaction written instead of being broadcast.",
+                ));
+
+                if feedback_sender
+                    .send(TxFeedback::new(tx::not_broadcast_response(), None))
+                    .is_err()
+                {
+                    log_broadcast_with_source!(debug![source](
+                        "Source stopped awaiting the transaction's result.",
+                    ));
+                }
+
+                break 'broadcast_loop Ok(());
+            }
 
             let Some(broadcast_result) = self
                 .broadcast_with_expiration(&source, expiration, raw_tx)
@@ -189,16 +484,41 @@ where
 
                 let tx_code: TxCode = response.code.into();
 
+                if tx_code.value() == SIGNATURE_VERIFICATION_ERROR_CODE {
+                    log_sequence_drift(
+                        &source,
+                        self.signer.query_sequence_drift().await,
+                    );
+                }
+
+                // In block mode, transactions which failed during execution
+                // are still included in a block and consume the sequence
+                // number.
                 if tx_code.is_ok()
                     || tx_code.value() == SIGNATURE_VERIFICATION_ERROR_CODE
+                    || (self.mode == node::BroadcastMode::Block
+                        && response.height > 0)
                 {
                     self.signer.increment_sequence_number();
                 }
 
-                Self::log_tx_response(source.as_ref(), tx_code, &response);
+                crate::log_tx_response!(
+                    target: "broadcast",
+                    stage: "broadcast",
+                    source: source,
+                    failure: error,
+                    response,
+                );
 
                 if tx_code.is_ok() {
                     self.consecutive_errors = 0;
+
+                    if let Some(multiplier) = self.signer.decay_fee() {
+                        log_broadcast_with_source!(info![source](
+                            multiplier_percent = multiplier,
+                            "Transaction accepted. Decayed fee multiplier.",
+                        ));
+                    }
                 } else {
                     self.consecutive_errors = (self.consecutive_errors + 1) % 5;
 
@@ -209,8 +529,28 @@ where
                     }
                 }
 
+                if is_insufficient_fee(&response) {
+                    if let Some(multiplier) = self.signer.escalate_fee() {
+                        log_broadcast_with_source!(warn![source](
+                            multiplier_percent = multiplier,
+                            "Transaction rejected due to insufficient fee. \
+                            Escalated fee multiplier.",
+                        ));
+
+                        break 'process;
+                    }
+                }
+
                 if tx_code.value() != SIGNATURE_VERIFICATION_ERROR_CODE {
-                    _ = feedback_sender.send(response);
+                    if feedback_sender
+                        .send(TxFeedback::new(response, simulated_gas))
+                        .is_err()
+                    {
+                        log_broadcast_with_source!(debug![source](
+                            "Source stopped awaiting the transaction's \
+                            result. Discarding it.",
+                        ));
+                    }
 
                     break 'broadcast_loop Ok(());
                 }
@@ -227,7 +567,10 @@ where
         raw_tx: Raw,
     ) -> Option<Result<TxResponse>> {
         Some(
-            match expiration.with_expiration(self.client.sync(raw_tx)).await {
+            match expiration
+                .with_expiration(self.client.broadcast(raw_tx, self.mode))
+                .await
+            {
                 Ok(result) => result,
                 Err(error) => {
                     log_broadcast_with_source!(error![source](
@@ -243,9 +586,12 @@ where
     }
 }
 
-impl<Expiration> Runnable for Broadcast<Expiration>
+impl<Expiration, Client, Signer> Runnable
+    for Broadcast<Expiration, Client, Signer>
 where
     Expiration: TxExpiration,
+    Client: BroadcastClient,
+    Signer: TxSigner,
 {
     async fn run(mut self, _: RunnableState) -> Result<()> {
         loop {
@@ -255,7 +601,18 @@ where
                 .await
                 .context("Transaction receiving channel closed!")?;
 
+            if self
+                .sequence_refresh
+                .as_mut()
+                .is_some_and(SequenceRefresh::due)
+            {
+                self.refresh_sequence_number().await;
+            }
+
+            let span = tx_package.correlation_id.span();
+
             self.broadcast_tx(tx_package)
+                .instrument(span)
                 .await
                 .context("Failed to broadcast transaction!")?;
 
@@ -280,16 +637,829 @@ where
     #[inline]
     fn new(
         service_configuration: &Self::ServiceConfiguration,
-        transaction_rx: channel::unbounded::Receiver<
+        transaction_rx: channel::unbounded::ReclaimableReceiver<
             TxPackage<Self::TxExpiration>,
         >,
     ) -> Self {
         Self::new(
             service_configuration.node_client().clone().broadcast_tx(),
+            service_configuration.broadcast_mode(),
             service_configuration.signer().clone(),
             transaction_rx,
             service_configuration.broadcast_delay_duration(),
             service_configuration.broadcast_retry_delay_duration(),
+            service_configuration.gas_strategy(),
+            service_configuration.max_simulation_gas(),
+            service_configuration.simulation_failure(),
+            service_configuration.sequence_refresh_interval(),
+            service_configuration.offline_output().cloned(),
+        )
+    }
+}
+
+/// Selects whether the gas limit of each transaction is estimated through
+/// simulation or whether the fallback gas, learned from previously delivered
+/// transactions, is used instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasStrategy {
+    AlwaysSimulate,
+    /// Uses the fallback gas for most transactions, re-simulating on every
+    /// N-th one to keep the estimate fresh.
+    FallbackFirst { resimulate_every: NonZeroU32 },
+}
+
+impl ReadFromVar for GasStrategy {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        let mut variable = variable.into();
+
+        match &*String::read_from_var(variable.as_str())
+            .context("Failed to read gas strategy!")?
+        {
+            "always_simulate" => Ok(Self::AlwaysSimulate),
+            "fallback_first" => {
+                variable.push_str("__RESIMULATE_EVERY");
+
+                NonZeroU32::read_from_var(variable)
+                    .map(|resimulate_every| Self::FallbackFirst {
+                        resimulate_every,
+                    })
+                    .context("Failed to read re-simulation interval!")
+            },
+            strategy => bail!(
+                "Unknown gas strategy \"{strategy}\"! Expected either \
+                \"always_simulate\" or \"fallback_first\"."
+            ),
+        }
+    }
+}
+
+/// Selects how a failed transaction simulation is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationFailure {
+    /// Skips broadcasting the transaction when its messages failed
+    /// simulation, as it is most likely going to be rejected anyway, only
+    /// wasting fees.
+    ///
+    /// Other failures, e.g. an unreachable node, are handled as with
+    /// [`SimulationFailure::Fallback`].
+    Fatal,
+    /// Signs the transaction with the fallback gas and broadcasts it
+    /// regardless.
+    Fallback,
+}
+
+impl SimulationFailure {
+    /// Logs why the simulation failed and returns the gas the transaction
+    /// should be signed with, unless the failure is fatal.
+    fn fallback_gas(
+        self,
+        source: &str,
+        fallback_gas: Gas,
+        error: Error,
+    ) -> Result<Gas> {
+        match self {
+            Self::Fatal if is_execution_error(&error) => {
+                log_simulation!(error![source](
+                    ?error,
+                    "Simulation of transaction's messages failed. Skipping \
+                    broadcast.",
+                ));
+
+                Err(error.context(SimulationFailed))
+            },
+            Self::Fatal | Self::Fallback => {
+                log_simulation!(error![source](
+                    %fallback_gas,
+                    ?error,
+                    "Simulation failed. Using fallback gas.",
+                ));
+
+                Ok(fallback_gas)
+            },
+        }
+    }
+}
+
+impl ReadFromVar for SimulationFailure {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        match &*String::read_from_var(variable)
+            .context("Failed to read simulation failure handling!")?
+        {
+            "fatal" => Ok(Self::Fatal),
+            "fallback" => Ok(Self::Fallback),
+            handling => bail!(
+                "Unknown simulation failure handling \"{handling}\"! \
+                Expected either \"fatal\" or \"fallback\"."
+            ),
+        }
+    }
+}
+
+/// Checks whether the node rejected the simulated transaction itself, e.g.
+/// because a message failed execution, as opposed to the simulation not
+/// being carried out, e.g. because the node is unreachable.
+fn is_execution_error(error: &Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<Status>().is_some_and(|status| {
+            matches!(status.code(), Code::InvalidArgument | Code::Unknown)
+        })
+    })
+}
+
+/// Attached as context to simulation errors which are fatal, so the
+/// transaction is skipped instead of the broadcasting task failing.
+#[derive(Debug, ThisError)]
+#[error("Transaction simulation failed!")]
+struct SimulationFailed;
+
+struct GasEstimation {
+    strategy: GasStrategy,
+    remaining_before_simulation: u32,
+}
+
+impl GasEstimation {
+    const fn new(strategy: GasStrategy) -> Self {
+        Self {
+            strategy,
+            remaining_before_simulation: 0,
+        }
+    }
+
+    fn should_simulate(&mut self, fallback_gas: Gas) -> bool {
+        match self.strategy {
+            GasStrategy::AlwaysSimulate => true,
+            GasStrategy::FallbackFirst { resimulate_every } => {
+                if fallback_gas == 0 || self.remaining_before_simulation == 0 {
+                    self.remaining_before_simulation =
+                        resimulate_every.get() - 1;
+
+                    true
+                } else {
+                    self.remaining_before_simulation -= 1;
+
+                    false
+                }
+            },
+        }
+    }
+}
+
+/// Schedules periodic re-synchronization of the signer's sequence number,
+/// bounding its drift when the signing key is shared with other tooling.
+///
+/// Refreshes happen only in between transactions, never while one is being
+/// broadcast.
+struct SequenceRefresh {
+    interval: Duration,
+    next_at: Option<Instant>,
+}
+
+impl SequenceRefresh {
+    const fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_at: None,
+        }
+    }
+
+    fn due(&mut self) -> bool {
+        let now = Instant::now();
+
+        let due = self.next_at.is_some_and(|next_at| now >= next_at);
+
+        if due || self.next_at.is_none() {
+            self.next_at = Some(now + self.interval);
+        }
+
+        due
+    }
+}
+
+/// Logs by how much the local sequence number differs from the on-chain one
+/// after a transaction got rejected due to a sequence mismatch.
+fn log_sequence_drift(source: &str, drift: Result<SequenceDrift>) {
+    match drift {
+        Ok(drift) => {
+            log_broadcast_with_source!(warn![source](
+                local = drift.local,
+                on_chain = drift.on_chain,
+                drift = %drift.drift(),
+                "Account sequence mismatch.",
+            ));
+        },
+        Err(error) => {
+            log_broadcast_with_source!(error![source](
+                ?error,
+                "Account sequence mismatch. Failed to query drift!",
+            ));
+        },
+    }
+}
+
+/// File to which transactions which are signed, but not broadcast, are
+/// written, so they can be reviewed and broadcast by a separate process.
+///
+/// Each transaction is written base64 encoded on a separate line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfflineOutput {
+    path: PathBuf,
+}
+
+impl OfflineOutput {
+    async fn write(&self, tx: RawTx) -> Result<()> {
+        let path = self.path.clone();
+
+        spawn_blocking(move || {
+            let mut line = tx
+                .to_bytes()
+                .map(|tx| BASE64.encode(&tx))
+                .map_err(|error| anyhow!(error))
+                .context("Failed to encode signed transaction!")?;
+
+            line.push('\n');
+
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+                .context("Failed to append to offline signing output!")
+        })
+        .await
+        .context("Failed to join back offline signing output writer!")?
+    }
+}
+
+impl ReadFromVar for OfflineOutput {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        String::read_from_var(variable)
+            .and_then(|output| match &*output {
+                "" => bail!("Offline signing output can't be empty!"),
+                // Logs are written to the standard output, thus interleaving
+                // them with the transactions would make both unusable.
+                "-" => bail!(
+                    "Offline signing output can't be the standard output, as \
+                    it's used for logging!"
+                ),
+                _ => Ok(Self {
+                    path: output.into(),
+                }),
+            })
+            .context("Failed to read offline signing output!")
+    }
+}
+
+/// Checks whether the source stopped awaiting the transaction's result,
+/// e.g. because its task exited, which makes broadcasting it moot.
+fn feedback_receiver_gone(
+    source: &str,
+    feedback_sender: &oneshot::Sender<TxFeedback>,
+) -> bool {
+    let gone = feedback_sender.is_closed();
+
+    if gone {
+        log_broadcast_with_source!(debug![source](
+            "Source stopped awaiting the transaction's result. Skipping \
+            broadcast.",
+        ));
+    }
+
+    gone
+}
+
+/// Rejects simulated gas above the sanity cap, as an absurd estimate returned
+/// by a faulty node would otherwise translate into an absurd fee.
+fn within_max_simulation_gas(
+    gas: Gas,
+    max_simulation_gas: Option<Gas>,
+) -> Option<Gas> {
+    match max_simulation_gas {
+        Some(max_simulation_gas) if gas > max_simulation_gas => None,
+        _ => Some(gas),
+    }
+}
+
+fn is_insufficient_fee(response: &TxResponse) -> bool {
+    OutcomeCategory::of(response) == OutcomeCategory::InsufficientFee
+}
+
+#[test]
+fn test_gas_strategy_simulation_count() {
+    fn count_simulations(strategy: GasStrategy, fallback_gas: &[Gas]) -> usize {
+        let mut gas_estimation = GasEstimation::new(strategy);
+
+        fallback_gas
+            .iter()
+            .filter(|&&fallback_gas| {
+                gas_estimation.should_simulate(fallback_gas)
+            })
+            .count()
+    }
+
+    let learned = [150_000; 20];
+
+    assert_eq!(count_simulations(GasStrategy::AlwaysSimulate, &learned), 20);
+
+    let fallback_first = GasStrategy::FallbackFirst {
+        resimulate_every: NonZeroU32::new(5).unwrap(),
+    };
+
+    assert_eq!(count_simulations(fallback_first, &learned), 4);
+
+    let mut not_learned = learned;
+
+    not_learned[..3].fill(0);
+
+    assert_eq!(count_simulations(fallback_first, &not_learned), 6);
+
+    assert_eq!(
+        count_simulations(
+            GasStrategy::FallbackFirst {
+                resimulate_every: NonZeroU32::MIN,
+            },
+            &learned,
+        ),
+        20,
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_sequence_refresh_cadence() {
+    use tokio::time::advance;
+
+    let mut sequence_refresh = SequenceRefresh::new(Duration::from_secs(30));
+
+    let mut refreshed_at = vec![];
+
+    for second in (0..=120).step_by(10) {
+        if sequence_refresh.due() {
+            refreshed_at.push(second);
+        }
+
+        advance(Duration::from_secs(10)).await;
+    }
+
+    assert_eq!(refreshed_at, [30, 60, 90, 120]);
+}
+
+#[test]
+fn test_insufficient_fee_detection() {
+    fn response(codespace: &str, code: u32) -> TxResponse {
+        TxResponse {
+            codespace: codespace.into(),
+            code,
+            raw_log: "insufficient fees; got: 1000unls required: 2000unls"
+                .into(),
+            ..TxResponse::default()
+        }
+    }
+
+    assert!(is_insufficient_fee(&response("sdk", 13)));
+
+    assert!(!is_insufficient_fee(&response("sdk", 0)));
+
+    assert!(!is_insufficient_fee(&response("sdk", 32)));
+
+    assert!(!is_insufficient_fee(&response("wasm", 13)));
+}
+
+#[cfg(test)]
+type StubBroadcast = Broadcast<
+    super::NoExpiration,
+    crate::testing::StubClient,
+    crate::testing::StubSigner,
+>;
+
+#[cfg(test)]
+fn stub_broadcast(
+    gas_strategy: GasStrategy,
+    simulation_failure: SimulationFailure,
+    offline_output: Option<OfflineOutput>,
+) -> (
+    StubBroadcast,
+    crate::testing::StubClient,
+    crate::testing::StubSigner,
+) {
+    stub_broadcast_with_signer(
+        gas_strategy,
+        simulation_failure,
+        offline_output,
+        crate::testing::StubSigner::new(),
+    )
+}
+
+#[cfg(test)]
+fn stub_broadcast_with_signer(
+    gas_strategy: GasStrategy,
+    simulation_failure: SimulationFailure,
+    offline_output: Option<OfflineOutput>,
+    signer: crate::testing::StubSigner,
+) -> (
+    StubBroadcast,
+    crate::testing::StubClient,
+    crate::testing::StubSigner,
+) {
+    use tokio::sync::mpsc;
+
+    use crate::testing::StubClient;
+
+    let client = StubClient::new();
+
+    let broadcast = Broadcast::new(
+        client.clone(),
+        node::BroadcastMode::Sync,
+        signer.clone(),
+        channel::unbounded::Reclaimer::new().wrap(mpsc::unbounded_channel().1),
+        Duration::ZERO,
+        Duration::ZERO,
+        gas_strategy,
+        None,
+        simulation_failure,
+        Some(Duration::from_secs(30)),
+        offline_output,
+    );
+
+    (broadcast, client, signer)
+}
+
+#[cfg(test)]
+fn stub_package(
+    fallback_gas: Gas,
+) -> (
+    TxPackage<super::NoExpiration>,
+    oneshot::Receiver<TxFeedback>,
+) {
+    let (feedback_sender, feedback_receiver) = oneshot::channel();
+
+    (
+        TxPackage {
+            tx_body: Body::new([], "", 0_u32),
+            source: "Test".into(),
+            hard_gas_limit: 500_000,
+            fallback_gas,
+            feedback_sender,
+            expiration: super::NoExpiration,
+            correlation_id: super::CorrelationId::next(),
+        },
+        feedback_receiver,
+    )
+}
+
+#[tokio::test]
+async fn test_dropped_feedback_receiver_skips_broadcast() {
+    let (mut broadcast, client, signer) = stub_broadcast(
+        GasStrategy::AlwaysSimulate,
+        SimulationFailure::Fatal,
+        None,
+    );
+
+    let (package, feedback_receiver) = stub_package(0);
+
+    drop(feedback_receiver);
+
+    broadcast.broadcast_tx(package).await.unwrap();
+
+    assert!(signer.signed().is_empty());
+
+    assert_eq!(broadcast.signer.sequence_number(), 0);
+
+    assert_eq!(client.simulated(), 0);
+
+    assert_eq!(client.broadcast(), 0);
+}
+
+#[tokio::test]
+async fn test_signed_tx_written_offline() {
+    use std::{env, fs, process};
+
+    use cosmrs::proto::cosmos::tx::v1beta1::TxRaw;
+    use prost::Message as _;
+
+    use crate::testing::SignedTx;
+
+    let path = env::temp_dir()
+        .join(format!("offline-signed-txs-{}.txt", process::id()));
+
+    let (mut broadcast, client, signer) = stub_broadcast(
+        GasStrategy::AlwaysSimulate,
+        SimulationFailure::Fatal,
+        Some(OfflineOutput { path: path.clone() }),
+    );
+
+    assert!(broadcast.sequence_refresh.is_none());
+
+    for fallback_gas in [0, 200_000] {
+        let (package, feedback_receiver) = stub_package(fallback_gas);
+
+        broadcast.broadcast_tx(package).await.unwrap();
+
+        let feedback = feedback_receiver.await.unwrap();
+
+        assert!(tx::is_not_broadcast(&feedback.response));
+
+        assert_eq!(feedback.simulated_gas, None);
+    }
+
+    let written = fs::read_to_string(&path).unwrap();
+
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(client.simulated(), 0);
+
+    assert_eq!(client.broadcast(), 0);
+
+    assert_eq!(
+        signer.signed(),
+        [
+            SignedTx {
+                sequence_number: 0,
+                gas_limit: 500_000,
+                fee_multiplier_percent: 100,
+            },
+            SignedTx {
+                sequence_number: 1,
+                gas_limit: 200_000,
+                fee_multiplier_percent: 100,
+            },
+        ],
+    );
+
+    assert_eq!(broadcast.signer.sequence_number(), 2);
+
+    let written_sequence_numbers: Vec<_> = written
+        .lines()
+        .map(|line| {
+            TxRaw::decode(&*BASE64.decode(line.as_bytes()).unwrap())
+                .unwrap()
+                .auth_info_bytes
+        })
+        .collect();
+
+    assert_eq!(
+        written_sequence_numbers,
+        [0_u64.to_be_bytes(), 1_u64.to_be_bytes()],
+    );
+
+    env::set_var("TEST_SIGN_OFFLINE_OUTPUT_STDOUT", "-");
+
+    assert!(
+        OfflineOutput::read_from_var("TEST_SIGN_OFFLINE_OUTPUT_STDOUT")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_absurd_simulated_gas_falls_back() {
+    assert_eq!(within_max_simulation_gas(u64::MAX, None), Some(u64::MAX));
+
+    assert_eq!(
+        within_max_simulation_gas(250_000, Some(5_000_000)),
+        Some(250_000),
+    );
+
+    assert_eq!(
+        within_max_simulation_gas(5_000_000, Some(5_000_000)),
+        Some(5_000_000),
+    );
+
+    assert_eq!(within_max_simulation_gas(u64::MAX, Some(5_000_000)), None);
+}
+
+#[test]
+fn test_sequence_drift_logged_on_mismatch() {
+    use tracing::{subscriber, Level};
+
+    use crate::testing::LogCapture;
+
+    let drift = SequenceDrift {
+        local: 40,
+        on_chain: 43,
+    };
+
+    assert_eq!(drift.drift(), 3);
+
+    assert_eq!(
+        SequenceDrift {
+            local: 43,
+            on_chain: 40,
+        }
+        .drift(),
+        -3,
+    );
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        log_sequence_drift("Test", Ok(drift));
+
+        log_sequence_drift("Test", Err(anyhow::anyhow!("Node unavailable!")));
+    });
+
+    let events = capture.events();
+
+    let [mismatch, failed] = events.as_slice() else {
+        panic!("Expected exactly two events!");
+    };
+
+    assert_eq!(mismatch.level, Level::WARN);
+
+    assert_eq!(failed.level, Level::ERROR);
+
+    assert_eq!(mismatch.fields["local"], "40");
+
+    assert_eq!(mismatch.fields["on_chain"], "43");
+
+    assert_eq!(mismatch.fields["drift"], "3");
+
+    assert_eq!(mismatch.fields["source"], "Test");
+
+    assert!(failed.fields["error"].contains("Node unavailable!"));
+}
+
+#[tokio::test]
+async fn test_simulation_failure_handling() {
+    use crate::testing::SignedTx;
+
+    async fn broadcast_after_failed_simulation(
+        simulation_failure: SimulationFailure,
+        code: Code,
+    ) -> (usize, Vec<SignedTx>, TxResponse) {
+        let (mut broadcast, client, signer) = stub_broadcast(
+            GasStrategy::AlwaysSimulate,
+            simulation_failure,
+            None,
+        );
+
+        client.push_simulation(Err(Error::new(Status::new(
+            code,
+            "failed to execute message; message index: 0: out of gas in \
+            location: wasm",
+        ))
+        .context("Failed to simulate transaction!")));
+
+        let (package, feedback_receiver) = stub_package(200_000);
+
+        broadcast.broadcast_tx(package).await.unwrap();
+
+        (
+            client.broadcast(),
+            signer.signed(),
+            feedback_receiver.await.unwrap().response,
         )
     }
+
+    let simulated = SignedTx {
+        sequence_number: 0,
+        gas_limit: 500_000,
+        fee_multiplier_percent: 100,
+    };
+
+    let with_fallback_gas = SignedTx {
+        sequence_number: 0,
+        gas_limit: 200_000,
+        ..simulated
+    };
+
+    let (broadcast, signed, response) = broadcast_after_failed_simulation(
+        SimulationFailure::Fatal,
+        Code::Unknown,
+    )
+    .await;
+
+    assert_eq!(broadcast, 0);
+
+    assert_eq!(signed, [simulated]);
+
+    assert!(!TxCode::from(response.code).is_ok());
+
+    assert!(response.raw_log.contains("out of gas in location: wasm"));
+
+    for (simulation_failure, code) in [
+        (SimulationFailure::Fatal, Code::Unavailable),
+        (SimulationFailure::Fallback, Code::Unknown),
+    ] {
+        let (broadcast, signed, response) =
+            broadcast_after_failed_simulation(simulation_failure, code).await;
+
+        assert_eq!(broadcast, 1);
+
+        assert_eq!(signed, [simulated, with_fallback_gas]);
+
+        assert!(TxCode::from(response.code).is_ok());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_escalated_fee_decays_after_acceptance() {
+    use crate::{signer::FeeEscalation, testing::StubSigner};
+
+    let (mut broadcast, client, signer) = stub_broadcast_with_signer(
+        GasStrategy::AlwaysSimulate,
+        SimulationFailure::Fallback,
+        None,
+        StubSigner::new()
+            .with_fee_escalation(FeeEscalation::new(150, 400).unwrap()),
+    );
+
+    let insufficient_fee = || TxResponse {
+        codespace: "sdk".into(),
+        code: 13,
+        raw_log: "insufficient fees; got: 1000unls required: 2000unls".into(),
+        ..TxResponse::default()
+    };
+
+    client.push_response(insufficient_fee());
+
+    client.push_response(insufficient_fee());
+
+    for _ in 0..3 {
+        let (package, feedback_receiver) = stub_package(0);
+
+        broadcast.broadcast_tx(package).await.unwrap();
+
+        let feedback = feedback_receiver.await.unwrap();
+
+        assert!(TxCode::from(feedback.response.code).is_ok());
+
+        assert_eq!(
+            feedback.simulated_gas,
+            Some(crate::testing::StubClient::DEFAULT_SIMULATED_GAS),
+        );
+    }
+
+    // Each broadcast attempt is preceded by a simulation, signed with the
+    // same fee multiplier.
+    assert_eq!(
+        signer
+            .signed()
+            .into_iter()
+            .step_by(2)
+            .map(|signed| signed.fee_multiplier_percent)
+            .collect::<Vec<_>>(),
+        [100, 150, 225, 150, 100],
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_broadcasts_spaced_by_delay() {
+    use tokio::{spawn, sync::mpsc};
+
+    use crate::testing::{StubClient, StubSigner};
+
+    const DELAY: Duration = Duration::from_secs(5);
+
+    let client = StubClient::new();
+
+    let (transaction_tx, transaction_rx) = mpsc::unbounded_channel();
+
+    let broadcast = Broadcast::new(
+        client.clone(),
+        node::BroadcastMode::Sync,
+        StubSigner::new(),
+        channel::unbounded::Reclaimer::new().wrap(transaction_rx),
+        DELAY,
+        Duration::ZERO,
+        GasStrategy::AlwaysSimulate,
+        None,
+        SimulationFailure::Fatal,
+        None,
+        None,
+    );
+
+    // Feeds queued at once, e.g. by providers ticking faster than the
+    // broadcaster's delay.
+    let _feedback_receivers: Vec<_> = (0..4)
+        .map(|_| {
+            let (package, feedback_receiver) = stub_package(0);
+
+            transaction_tx.send(package).unwrap();
+
+            feedback_receiver
+        })
+        .collect();
+
+    let broadcaster = spawn(broadcast.run(RunnableState::New));
+
+    let mut broadcasts = vec![];
+
+    sleep(Duration::from_secs(1)).await;
+
+    for _ in 0..4 {
+        broadcasts.push(client.broadcast());
+
+        sleep(DELAY).await;
+    }
+
+    broadcaster.abort();
+
+    assert_eq!(broadcasts, [1, 2, 3, 4]);
 }
@@ -3,22 +3,28 @@ use std::{
     collections::{btree_map::Entry as BTreeMapEntry, BTreeMap},
     convert::Infallible,
     error::Error,
+    fmt::{self, Display, Formatter},
     future::Future,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
 };
 
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use cosmrs::{
     proto::cosmos::base::abci::v1beta1::TxResponse, tx::Body as TxBody, Gas,
 };
 use tokio::{
     sync::oneshot,
-    time::{error::Elapsed, timeout_at, Instant},
+    time::{error::Elapsed, sleep, timeout_at, Instant},
 };
-use tracing::{error, error_span};
+use tracing::{error, error_span, info_span, warn, Span};
 
 use crate::{
     channel,
+    env::ReadFromVar,
     service::task_spawner::{CancellationToken, ServiceStopped, TaskSpawner},
 };
 
@@ -52,7 +58,7 @@ pub trait Broadcast: BuiltIn {
 
     fn new(
         service_configuration: &Self::ServiceConfiguration,
-        transaction_rx: channel::unbounded::Receiver<
+        transaction_rx: channel::unbounded::ReclaimableReceiver<
             TxPackage<Self::TxExpiration>,
         >,
     ) -> Self;
@@ -111,9 +117,12 @@ where
         self,
         task_spawner: &TaskSpawner<Id<ApplicationDefined::Id>, Result<()>>,
         task_states: &mut BTreeMap<Id<ApplicationDefined::Id>, State>,
+        start_delays: &StartDelays,
     ) -> Result<(), ServiceStopped> {
         let task_id = self.identifier();
 
+        let start_delay = start_delays.for_task(&task_id);
+
         let task_state = task_states.entry(task_id.clone());
 
         let state = if matches!(task_state, BTreeMapEntry::Vacant { .. }) {
@@ -125,22 +134,34 @@ where
         match self {
             Self::BalanceReporter(task) => {
                 task_spawner
-                    .spawn(task_id.clone(), run(task_id, task, state))
+                    .spawn(
+                        task_id.clone(),
+                        run(task_id, task, state, start_delay),
+                    )
                     .await
             },
             Self::Broadcast(task) => {
                 task_spawner
-                    .spawn(task_id.clone(), run(task_id, task, state))
+                    .spawn(
+                        task_id.clone(),
+                        run(task_id, task, state, start_delay),
+                    )
                     .await
             },
             Self::ProtocolWatcher(task) => {
                 task_spawner
-                    .spawn(task_id.clone(), run(task_id, task, state))
+                    .spawn(
+                        task_id.clone(),
+                        run(task_id, task, state, start_delay),
+                    )
                     .await
             },
             Self::ApplicationDefined(task) => {
                 task_spawner
-                    .spawn(task_id.clone(), run(task_id, task, state))
+                    .spawn(
+                        task_id.clone(),
+                        run(task_id, task, state, start_delay),
+                    )
                     .await
             },
         }
@@ -199,8 +220,56 @@ where
     pub source: Arc<str>,
     pub hard_gas_limit: Gas,
     pub fallback_gas: Gas,
-    pub feedback_sender: oneshot::Sender<TxResponse>,
+    pub feedback_sender: oneshot::Sender<TxFeedback>,
     pub expiration: Expiration,
+    pub correlation_id: CorrelationId,
+}
+
+/// Outcome of a transaction handed back to its source, first by the
+/// broadcaster and then, once fetched, for its delivered counterpart.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct TxFeedback {
+    pub response: TxResponse,
+    /// Gas estimated by simulating the transaction, before the gas
+    /// adjustment is applied, or `None` when it wasn't simulated.
+    pub simulated_gas: Option<Gas>,
+}
+
+impl TxFeedback {
+    #[inline]
+    pub const fn new(response: TxResponse, simulated_gas: Option<Gas>) -> Self {
+        Self {
+            response,
+            simulated_gas,
+        }
+    }
+}
+
+/// Process-wide unique identifier linking the log lines of a single
+/// transaction across the stages it passes through, from its creation,
+/// through broadcasting, to fetching its delivery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[must_use]
+pub struct CorrelationId(u64);
+
+impl CorrelationId {
+    pub fn next() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(1);
+
+        Self(COUNTER.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// Span under which all stages log, carrying the identifier as a field.
+    pub fn span(self) -> Span {
+        info_span!("tx", correlation_id = self.0)
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
 }
 
 pub trait TxExpiration: Copy + Send + Sized + 'static {
@@ -212,6 +281,14 @@ pub trait TxExpiration: Copy + Send + Sized + 'static {
     ) -> impl Future<Output = Result<F::Output, Self::Expired>> + Send
     where
         F: Future + Send;
+
+    /// Extends the expiration, if necessary, so the transaction is given at
+    /// least one broadcast attempt, even when it nominally expired while
+    /// queued.
+    #[inline]
+    fn with_attempt_window(self) -> Self {
+        self
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -240,6 +317,8 @@ pub struct TimeBasedExpiration {
 }
 
 impl TimeBasedExpiration {
+    const MINIMUM_ATTEMPT_WINDOW: Duration = Duration::from_secs(5);
+
     pub const fn new(expires_at: Instant) -> Self {
         Self { expires_at }
     }
@@ -258,6 +337,89 @@ impl TxExpiration for TimeBasedExpiration {
     {
         timeout_at(self.expires_at, future)
     }
+
+    fn with_attempt_window(self) -> Self {
+        let attempt_deadline = Instant::now() + Self::MINIMUM_ATTEMPT_WINDOW;
+
+        if self.expires_at < attempt_deadline {
+            warn!(
+                target: "broadcast",
+                remaining = ?self.expires_at.saturating_duration_since(
+                    Instant::now(),
+                ),
+                "Transaction's expiration is too close. Extending it to allow \
+                one broadcast attempt.",
+            );
+
+            Self {
+                expires_at: attempt_deadline,
+            }
+        } else {
+            self
+        }
+    }
+}
+
+/// Delays applied before newly started tasks begin running, per task type,
+/// e.g. to hold off feeding prices until the chain stabilizes after an
+/// upgrade.
+///
+/// Restarted tasks are not delayed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub struct StartDelays {
+    pub balance_reporter: Duration,
+    pub broadcast: Duration,
+    pub protocol_watcher: Duration,
+    pub application_defined: Duration,
+}
+
+impl StartDelays {
+    /// Reads the delays from the `START_DELAY_<TASK TYPE>_SECONDS` variables.
+    ///
+    /// Application-defined tasks, i.e. the price feeders, read
+    /// `START_DELAY_PRICE_FEEDER_SECONDS`, or, when that one is not set,
+    /// `START_DELAY_APPLICATION_DEFINED_SECONDS`.
+    pub fn read_from_env() -> Result<Self> {
+        fn read(variable: &str) -> Result<Option<Duration>> {
+            Option::<u64>::read_from_var(variable)
+                .map(|delay| delay.map(Duration::from_secs))
+                .with_context(|| {
+                    format!(r#"Failed to read start delay, "{variable}"!"#)
+                })
+        }
+
+        let application_defined =
+            match read("START_DELAY_PRICE_FEEDER_SECONDS")? {
+                Some(delay) => Some(delay),
+                None => read("START_DELAY_APPLICATION_DEFINED_SECONDS")?,
+            };
+
+        Ok(Self {
+            balance_reporter: read("START_DELAY_BALANCE_REPORTER_SECONDS")?
+                .unwrap_or_default(),
+            broadcast: read("START_DELAY_BROADCAST_SECONDS")?
+                .unwrap_or_default(),
+            protocol_watcher: read("START_DELAY_PROTOCOL_WATCHER_SECONDS")?
+                .unwrap_or_default(),
+            application_defined: application_defined.unwrap_or_default(),
+        })
+    }
+
+    fn for_task<ApplicationDefined>(
+        &self,
+        id: &Id<ApplicationDefined>,
+    ) -> Duration
+    where
+        ApplicationDefined: application_defined::Id,
+    {
+        match id {
+            Id::BalanceReporter => self.balance_reporter,
+            Id::Broadcast => self.broadcast,
+            Id::ProtocolWatcher => self.protocol_watcher,
+            Id::ApplicationDefined(_) => self.application_defined,
+        }
+    }
 }
 
 #[must_use]
@@ -291,11 +453,16 @@ async fn run<Id, T>(
     id: self::Id<Id>,
     runnable: T,
     state: RunnableState,
+    start_delay: Duration,
 ) -> Result<()>
 where
     Id: application_defined::Id,
     T: Runnable,
 {
+    if matches!(state, RunnableState::New) && !start_delay.is_zero() {
+        sleep(start_delay).await;
+    }
+
     runnable.run(state).await.inspect_err(|error| {
         error_span!("run").in_scope(|| {
             error!(
@@ -307,3 +474,118 @@ where
         });
     })
 }
+
+#[tokio::test(start_paused = true)]
+async fn test_expired_package_gets_one_attempt() {
+    use tokio::time::{advance, sleep};
+
+    let expiration = TimeBasedExpiration::new(Instant::now());
+
+    advance(Duration::from_secs(10)).await;
+
+    let attempt = || sleep(Duration::from_secs(1));
+
+    assert!(expiration.with_expiration(attempt()).await.is_err());
+
+    assert!(expiration
+        .with_attempt_window()
+        .with_expiration(attempt())
+        .await
+        .is_ok());
+
+    let distant =
+        TimeBasedExpiration::new(Instant::now() + Duration::from_secs(60));
+
+    assert_eq!(distant.with_attempt_window().expires_at, distant.expires_at);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_start_delays_per_task_type() {
+    use crate::testing::{TestId, TestTask};
+
+    let task = || TestTask::new(TestId::new("Test"));
+
+    let start_delays = StartDelays {
+        balance_reporter: Duration::ZERO,
+        broadcast: Duration::from_secs(1),
+        protocol_watcher: Duration::from_secs(2),
+        application_defined: Duration::from_secs(300),
+    };
+
+    for (id, expected) in [
+        (Id::BalanceReporter, Duration::ZERO),
+        (Id::Broadcast, Duration::from_secs(1)),
+        (Id::ProtocolWatcher, Duration::from_secs(2)),
+        (
+            Id::ApplicationDefined(TestId::new("Test")),
+            Duration::from_secs(300),
+        ),
+    ] {
+        let start_delay = start_delays.for_task(&id);
+
+        let started_at = Instant::now();
+
+        run(id.clone(), task(), RunnableState::New, start_delay)
+            .await
+            .unwrap();
+
+        assert_eq!(started_at.elapsed(), expected, "Task: {}", id.name());
+
+        let started_at = Instant::now();
+
+        run(id, task(), RunnableState::Restart, start_delay)
+            .await
+            .unwrap();
+
+        assert_eq!(started_at.elapsed(), Duration::ZERO);
+    }
+}
+
+#[test]
+fn test_correlation_id_shared_between_stages() {
+    use tracing::{info, subscriber};
+
+    use crate::testing::LogCapture;
+
+    let capture = LogCapture::leak();
+
+    let (first, second) = (CorrelationId::next(), CorrelationId::next());
+
+    assert_ne!(first, second);
+
+    subscriber::with_default(capture, || {
+        first
+            .span()
+            .in_scope(|| info!(target: "provider", "Feeding."));
+
+        second
+            .span()
+            .in_scope(|| info!(target: "provider", "Feeding."));
+
+        first
+            .span()
+            .in_scope(|| info!(target: "broadcast", "Broadcast."));
+    });
+
+    let events: Vec<_> = capture
+        .events()
+        .into_iter()
+        .map(|event| {
+            (
+                event.target,
+                event.span.and_then(|span| {
+                    span.fields.get("correlation_id").cloned()
+                }),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        events,
+        [
+            ("provider", Some(first.0.to_string())),
+            ("provider", Some(second.0.to_string())),
+            ("broadcast", Some(first.0.to_string())),
+        ],
+    );
+}
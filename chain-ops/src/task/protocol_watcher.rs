@@ -1,9 +1,16 @@
 use std::{
-    collections::BTreeMap, collections::BTreeSet, sync::Arc, time::Duration,
+    collections::{BTreeMap, BTreeSet},
+    num::NonZeroUsize,
+    sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Context as _, Result};
-use tokio::time::sleep;
+use chrono::{SecondsFormat, Utc};
+use tokio::{
+    sync::watch,
+    time::{sleep, timeout},
+};
 
 use crate::{
     channel, contract::Admin as AdminContract, supervisor::configuration, task,
@@ -12,6 +19,12 @@ use crate::{
 use super::{application_defined, BuiltIn, Runnable, RunnableState, State};
 
 macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "protocol-watcher",
+            $($body)+
+        );
+    };
     ($macro:ident![$protocol:expr]($($body:tt)+)) => {
         ::tracing::$macro!(
             target: "protocol-watcher",
@@ -26,6 +39,9 @@ pub struct ProtocolWatcher {
     admin_contract: AdminContract,
     protocol_tasks: BTreeSet<Arc<str>>,
     command_tx: channel::bounded::Sender<Command>,
+    readiness: Readiness,
+    batching: Option<Batching>,
+    max_protocols: Option<NonZeroUsize>,
 }
 
 impl ProtocolWatcher {
@@ -33,11 +49,17 @@ impl ProtocolWatcher {
         admin_contract: AdminContract,
         protocol_tasks: BTreeSet<Arc<str>>,
         command_tx: channel::bounded::Sender<Command>,
+        readiness: Readiness,
+        batching: Option<Batching>,
+        max_protocols: Option<NonZeroUsize>,
     ) -> Self {
         Self {
             admin_contract,
             protocol_tasks,
             command_tx,
+            readiness,
+            batching,
+            max_protocols,
         }
     }
 }
@@ -56,24 +78,18 @@ impl Runnable for ProtocolWatcher {
                 .map(Into::into)
                 .collect();
 
-            for command in
-                protocols_diff_commands(&self.protocol_tasks, &active_protocols)
-            {
-                match &command {
-                    Command::ProtocolAdded(protocol) => {
-                        log!(info![protocol]("Protocol added."));
-
-                        assert!(self.protocol_tasks.insert(protocol.clone()));
-                    },
-                    Command::ProtocolRemoved(protocol) => {
-                        log!(info![protocol]("Protocol removed."));
-
-                        _ = self.protocol_tasks.remove(protocol);
-                    },
-                }
-
-                self.command_tx.send(command).await?;
-            }
+            send_commands(
+                &self.command_tx,
+                &mut self.protocol_tasks,
+                &self.readiness,
+                self.batching,
+                protocols_diff_commands(
+                    &self.protocol_tasks,
+                    &active_protocols,
+                    self.max_protocols,
+                ),
+            )
+            .await?;
 
             sleep(IDLE_DURATION).await;
         }
@@ -93,42 +109,521 @@ impl super::ProtocolWatcher for ProtocolWatcher {
     where
         ApplicationDefined: application_defined::Id,
     {
+        let protocol_tasks = task_states
+            .keys()
+            .filter_map(|id| {
+                if let task::Id::ApplicationDefined(id) = id {
+                    id.protocol().cloned()
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let readiness = service_configuration.protocol_readiness().clone();
+
+        // Protocols without running tasks, e.g. after all tasks were torn
+        // down, have to pass their healthchecks again when re-added.
+        () = readiness.retain(&protocol_tasks);
+
         Self::new(
             service_configuration.admin_contract().clone(),
-            task_states
-                .keys()
-                .filter_map(|id| {
-                    if let task::Id::ApplicationDefined(id) = id {
-                        id.protocol().cloned()
-                    } else {
-                        None
-                    }
-                })
-                .collect(),
+            protocol_tasks,
             command_tx,
+            readiness,
+            service_configuration.protocol_startup_batching(),
+            service_configuration.max_protocols(),
+        )
+    }
+}
+
+/// Limits how many protocols are reported as added at once, staggering the
+/// creation of their tasks so a cold start doesn't query the admin contract
+/// and all DEX nodes at the same time.
+///
+/// Each batch is reported once the tasks of the previous one have passed
+/// their first healthcheck, or once the timeout elapses, so a single
+/// unhealthy protocol can't hold back the rest indefinitely.
+#[derive(Debug, Clone, Copy)]
+#[must_use]
+pub struct Batching {
+    size: NonZeroUsize,
+    timeout: Duration,
+}
+
+impl Batching {
+    #[inline]
+    pub const fn new(size: NonZeroUsize, timeout: Duration) -> Self {
+        Self { size, timeout }
+    }
+}
+
+/// Set of protocols whose tasks have passed their first healthcheck, shared
+/// between the protocol watcher and the protocols' tasks.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Readiness(watch::Sender<BTreeSet<Arc<str>>>);
+
+impl Readiness {
+    pub fn new() -> Self {
+        Self(watch::Sender::new(BTreeSet::new()))
+    }
+
+    /// Marks the protocol's tasks as having passed their first healthcheck,
+    /// until the returned guard is dropped.
+    ///
+    /// The guard is meant to be held by the task for as long as it runs, so
+    /// the protocol stops counting as ready once the task exits.
+    pub fn mark_ready(&self, protocol: Arc<str>) -> Ready {
+        _ = self
+            .0
+            .send_if_modified(|ready| ready.insert(protocol.clone()));
+
+        Ready {
+            readiness: self.clone(),
+            protocol,
+        }
+    }
+
+    fn forget(&self, protocol: &Arc<str>) {
+        _ = self.0.send_if_modified(|ready| ready.remove(protocol));
+    }
+
+    fn retain(&self, protocols: &BTreeSet<Arc<str>>) {
+        _ = self.0.send_if_modified(|ready| {
+            let count = ready.len();
+
+            ready.retain(|protocol| protocols.contains(protocol));
+
+            ready.len() != count
+        });
+    }
+
+    #[cfg(test)]
+    fn is_ready(&self, protocol: &Arc<str>) -> bool {
+        self.0.borrow().contains(protocol)
+    }
+
+    /// Waits until all of the protocols are marked as ready.
+    ///
+    /// Returns `false` when the timeout elapses first.
+    async fn wait_for(&self, protocols: &[Arc<str>], within: Duration) -> bool {
+        let mut ready_rx = self.0.subscribe();
+
+        matches!(
+            timeout(
+                within,
+                ready_rx.wait_for(|ready| {
+                    protocols.iter().all(|protocol| ready.contains(protocol))
+                }),
+            )
+            .await,
+            Ok(Ok(_)),
         )
     }
 }
 
+impl Default for Readiness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps a protocol marked as ready until dropped.
+#[must_use]
+pub struct Ready {
+    readiness: Readiness,
+    protocol: Arc<str>,
+}
+
+impl Drop for Ready {
+    fn drop(&mut self) {
+        self.readiness.forget(&self.protocol);
+    }
+}
+
 #[derive(Debug)]
 pub enum Command {
     ProtocolAdded(Arc<str>),
     ProtocolRemoved(Arc<str>),
 }
 
+impl Command {
+    /// Emits a structured event, with a stable set of fields, for audit
+    /// pipelines under the `protocol-audit` target.
+    pub(crate) fn log_audit_event(&self, stage: AuditStage) {
+        let (action, protocol) = match self {
+            Self::ProtocolAdded(protocol) => ("added", protocol),
+            Self::ProtocolRemoved(protocol) => ("removed", protocol),
+        };
+
+        ::tracing::info!(
+            target: "protocol-audit",
+            action,
+            protocol = &**protocol,
+            timestamp = %Utc::now()
+                .to_rfc3339_opts(SecondsFormat::Millis, true),
+            stage = stage.as_str(),
+            "Protocol set changed.",
+        );
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AuditStage {
+    /// The protocol watcher detected the change in the admin contract.
+    Detected,
+    /// The supervisor started or stopped the protocol's tasks.
+    Applied,
+}
+
+impl AuditStage {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Detected => "detected",
+            Self::Applied => "applied",
+        }
+    }
+}
+
+async fn send_commands(
+    command_tx: &channel::bounded::Sender<Command>,
+    protocol_tasks: &mut BTreeSet<Arc<str>>,
+    readiness: &Readiness,
+    batching: Option<Batching>,
+    commands: Vec<Command>,
+) -> Result<()> {
+    let mut batch = vec![];
+
+    for command in commands {
+        match &command {
+            Command::ProtocolAdded(protocol) => {
+                if let Some(batching) = batching {
+                    if batch.len() == batching.size.get() {
+                        log!(info![protocol](
+                            "Delaying protocol addition until the previous \
+                            batch's tasks pass their first healthcheck.",
+                        ));
+
+                        if !readiness.wait_for(&batch, batching.timeout).await {
+                            log!(warn![protocol](
+                                timeout = ?batching.timeout,
+                                "Previous batch's tasks didn't pass their \
+                                first healthcheck in time! Continuing with \
+                                the next batch.",
+                            ));
+                        }
+
+                        batch.clear();
+                    }
+
+                    batch.push(protocol.clone());
+                }
+
+                log!(info![protocol]("Protocol added."));
+
+                assert!(protocol_tasks.insert(protocol.clone()));
+            },
+            Command::ProtocolRemoved(protocol) => {
+                log!(info![protocol]("Protocol removed."));
+
+                _ = protocol_tasks.remove(protocol);
+
+                () = readiness.forget(protocol);
+            },
+        }
+
+        command.log_audit_event(AuditStage::Detected);
+
+        command_tx.send(command).await?;
+    }
+
+    Ok(())
+}
+
+/// Produces the commands bringing the running protocols in line with the
+/// active ones.
+///
+/// When a maximum is configured, additions beyond it are refused, guarding
+/// against a misbehaving admin contract reporting an excessive amount of
+/// protocols.
 fn protocols_diff_commands(
     protocols: &BTreeSet<Arc<str>>,
     active_protocols: &BTreeSet<Arc<str>>,
+    max_protocols: Option<NonZeroUsize>,
 ) -> Vec<Command> {
-    active_protocols
-        .difference(protocols)
+    let removed = protocols.difference(active_protocols).count();
+
+    let allowed_additions = max_protocols.map_or(usize::MAX, |max_protocols| {
+        max_protocols
+            .get()
+            .saturating_sub(protocols.len() - removed)
+    });
+
+    let mut added = active_protocols.difference(protocols);
+
+    // Removals go first, so they aren't held back by batched additions.
+    let commands = protocols
+        .difference(active_protocols)
         .cloned()
-        .map(Command::ProtocolAdded)
+        .map(Command::ProtocolRemoved)
         .chain(
-            protocols
-                .difference(active_protocols)
+            added
+                .by_ref()
+                .take(allowed_additions)
                 .cloned()
-                .map(Command::ProtocolRemoved),
+                .map(Command::ProtocolAdded),
         )
-        .collect()
+        .collect();
+
+    let refused = added.count();
+
+    if refused != 0 {
+        log!(error!(
+            active = active_protocols.len(),
+            max = max_protocols.map_or(usize::MAX, NonZeroUsize::get),
+            refused,
+            "Amount of active protocols exceeds the configured maximum! \
+            Refusing to start tasks for the excess ones.",
+        ));
+    }
+
+    commands
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_startup_batching() {
+    use tokio::{spawn, time::Instant};
+
+    use crate::channel::Channel as _;
+
+    const HEALTHCHECK_DURATION: Duration = Duration::from_secs(3);
+
+    const BATCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+    let (command_tx, mut command_rx) = channel::bounded::Channel::new();
+
+    let readiness = Readiness::new();
+
+    let active_protocols: BTreeSet<Arc<str>> = (0..10)
+        .map(|index| format!("PROTOCOL-{index}").into())
+        .collect();
+
+    let started_at = Instant::now();
+
+    let sender = spawn({
+        let readiness = readiness.clone();
+
+        async move {
+            let mut protocol_tasks = BTreeSet::new();
+
+            send_commands(
+                &command_tx,
+                &mut protocol_tasks,
+                &readiness,
+                Some(Batching::new(
+                    NonZeroUsize::new(4).unwrap(),
+                    BATCH_TIMEOUT,
+                )),
+                protocols_diff_commands(
+                    &BTreeSet::new(),
+                    &active_protocols,
+                    None,
+                ),
+            )
+            .await
+            .map(|()| protocol_tasks)
+        }
+    });
+
+    let mut batch_offsets = vec![];
+
+    let mut healthy_tasks = vec![];
+
+    while let Some(command) = command_rx.recv().await {
+        let Command::ProtocolAdded(protocol) = command else {
+            panic!("Expected only protocol additions!");
+        };
+
+        batch_offsets.push(started_at.elapsed());
+
+        // One protocol of the second batch never becomes healthy, holding
+        // the third batch back until the timeout.
+        if &*protocol != "PROTOCOL-5" {
+            let readiness = readiness.clone();
+
+            // Guards are kept in the tasks' outputs, keeping the protocols
+            // ready.
+            healthy_tasks.push(spawn(async move {
+                sleep(HEALTHCHECK_DURATION).await;
+
+                readiness.mark_ready(protocol)
+            }));
+        }
+    }
+
+    assert_eq!(sender.await.unwrap().unwrap().len(), 10);
+
+    assert_eq!(
+        batch_offsets,
+        [
+            [Duration::ZERO; 4].as_slice(),
+            &[HEALTHCHECK_DURATION; 4],
+            &[HEALTHCHECK_DURATION + BATCH_TIMEOUT; 2],
+        ]
+        .concat(),
+    );
+}
+
+#[test]
+fn test_readiness_follows_running_tasks() {
+    let readiness = Readiness::new();
+
+    let first: Arc<str> = "PROTOCOL-1".into();
+
+    let second: Arc<str> = "PROTOCOL-2".into();
+
+    let first_ready = readiness.mark_ready(first.clone());
+
+    let _second_ready = readiness.mark_ready(second.clone());
+
+    assert!(readiness.is_ready(&first));
+
+    drop(first_ready);
+
+    assert!(!readiness.is_ready(&first));
+
+    assert!(readiness.is_ready(&second));
+
+    // Re-created protocol watcher after all tasks were torn down.
+    () = readiness.retain(&BTreeSet::new());
+
+    assert!(!readiness.is_ready(&second));
+}
+
+#[test]
+fn test_removals_sent_before_additions() {
+    let protocols = |names: &[&str]| -> BTreeSet<Arc<str>> {
+        names.iter().map(|&name| name.into()).collect()
+    };
+
+    let commands = protocols_diff_commands(
+        &protocols(&["A", "B"]),
+        &protocols(&["B", "C", "D"]),
+        None,
+    );
+
+    assert!(matches!(
+        commands.as_slice(),
+        [
+            Command::ProtocolRemoved(removed),
+            Command::ProtocolAdded(first),
+            Command::ProtocolAdded(second),
+        ] if &**removed == "A" && &**first == "C" && &**second == "D",
+    ));
+}
+
+#[test]
+fn test_audit_event_fields() {
+    use tracing::subscriber;
+
+    use crate::testing::LogCapture;
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        Command::ProtocolAdded("OSMOSIS-OSMOSIS-USDC_NOBLE".into())
+            .log_audit_event(AuditStage::Detected);
+
+        Command::ProtocolRemoved("NEUTRON-ASTROPORT-USDC_NOBLE".into())
+            .log_audit_event(AuditStage::Applied);
+    });
+
+    let events = capture.events();
+
+    let expected = [
+        ("added", "OSMOSIS-OSMOSIS-USDC_NOBLE", "detected"),
+        ("removed", "NEUTRON-ASTROPORT-USDC_NOBLE", "applied"),
+    ];
+
+    assert_eq!(events.len(), expected.len());
+
+    for (event, (action, protocol, stage)) in events.iter().zip(expected) {
+        assert_eq!(event.target, "protocol-audit");
+
+        assert_eq!(event.fields["action"], action);
+
+        assert_eq!(event.fields["protocol"], protocol);
+
+        assert_eq!(event.fields["stage"], stage);
+
+        assert!(chrono::DateTime::parse_from_rfc3339(
+            &event.fields["timestamp"]
+        )
+        .is_ok());
+    }
+}
+
+#[test]
+fn test_max_protocols_cap() {
+    use tracing::{subscriber, Level};
+
+    use crate::testing::LogCapture;
+
+    fn protocols<I>(indices: I) -> BTreeSet<Arc<str>>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        indices
+            .into_iter()
+            .map(|index| format!("PROTOCOL-{index:04}").into())
+            .collect()
+    }
+
+    fn count_added(commands: &[Command]) -> usize {
+        commands
+            .iter()
+            .filter(|command| matches!(command, Command::ProtocolAdded(_)))
+            .count()
+    }
+
+    let capture = LogCapture::leak();
+
+    let max_protocols = NonZeroUsize::new(10);
+
+    subscriber::with_default(capture, || {
+        let commands = protocols_diff_commands(
+            &BTreeSet::new(),
+            &protocols(0..5000),
+            max_protocols,
+        );
+
+        assert_eq!(count_added(&commands), 10);
+
+        assert_eq!(commands.len(), 10);
+
+        assert_eq!(capture.count(Level::ERROR), 1);
+
+        let commands = protocols_diff_commands(
+            &protocols(0..10),
+            &protocols(5..5000),
+            max_protocols,
+        );
+
+        assert_eq!(count_added(&commands), 5);
+
+        assert_eq!(commands.len() - count_added(&commands), 5);
+
+        assert_eq!(capture.count(Level::ERROR), 2);
+
+        let commands = protocols_diff_commands(
+            &protocols(0..5),
+            &protocols(0..10),
+            max_protocols,
+        );
+
+        assert_eq!(count_added(&commands), 5);
+
+        assert_eq!(capture.count(Level::ERROR), 2);
+    });
 }
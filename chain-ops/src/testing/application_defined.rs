@@ -0,0 +1,97 @@
+use std::{borrow::Cow, sync::Arc};
+
+use anyhow::Result;
+
+use crate::{
+    channel,
+    task::{
+        application_defined, NoExpiration, Runnable, RunnableState, TxPackage,
+    },
+};
+
+/// Application-defined task which exits right away, for exercising the
+/// task-agnostic machinery.
+pub struct TestTask {
+    id: TestId,
+}
+
+impl TestTask {
+    #[must_use]
+    pub const fn new(id: TestId) -> Self {
+        Self { id }
+    }
+}
+
+impl Runnable for TestTask {
+    async fn run(self, _: RunnableState) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl application_defined::Task for TestTask {
+    type TxExpiration = NoExpiration;
+
+    type Id = TestId;
+
+    fn id(&self) -> Self::Id {
+        self.id.clone()
+    }
+
+    fn protocol_task_set_ids(
+        protocol: Arc<str>,
+    ) -> impl Iterator<Item = Self::Id> + Send + 'static {
+        [TestId::with_protocol(protocol, "Test")].into_iter()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TestId {
+    protocol: Option<Arc<str>>,
+    name: &'static str,
+}
+
+impl TestId {
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            protocol: None,
+            name,
+        }
+    }
+
+    #[must_use]
+    pub fn with_protocol<T>(protocol: T, name: &'static str) -> Self
+    where
+        T: Into<Arc<str>>,
+    {
+        Self {
+            protocol: Some(protocol.into()),
+            name,
+        }
+    }
+}
+
+impl application_defined::Id for TestId {
+    type ServiceConfiguration = ();
+
+    type TaskCreationContext = ();
+
+    type Task = TestTask;
+
+    fn protocol(&self) -> Option<&Arc<str>> {
+        self.protocol.as_ref()
+    }
+
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(self.name)
+    }
+
+    async fn into_task(
+        self,
+        _: &mut (),
+        _: &mut (),
+        _: &channel::unbounded::Sender<TxPackage<NoExpiration>>,
+    ) -> Result<Self::Task> {
+        Ok(TestTask::new(self))
+    }
+}
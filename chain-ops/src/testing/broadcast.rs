@@ -0,0 +1,198 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use anyhow::Result;
+use cosmrs::{
+    proto::cosmos::{base::abci::v1beta1::TxResponse, tx::v1beta1::TxRaw},
+    tx::{Body, Raw as RawTx, SequenceNumber},
+    Gas,
+};
+
+use crate::{
+    node,
+    signer::{FeeEscalation, SequenceDrift},
+    task::broadcast::{BroadcastClient, TxSigner},
+};
+
+/// Node stub answering simulations and broadcasts with queued results,
+/// falling back to successful ones, while counting the calls made.
+#[derive(Clone, Default)]
+#[must_use]
+pub struct StubClient(Arc<Mutex<ClientState>>);
+
+impl StubClient {
+    pub const DEFAULT_SIMULATED_GAS: Gas = 100_000;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push_simulation(&self, result: Result<Gas>) {
+        self.lock().simulations.push_back(result);
+    }
+
+    pub fn push_response(&self, response: TxResponse) {
+        self.lock().responses.push_back(response);
+    }
+
+    #[must_use]
+    pub fn simulated(&self) -> usize {
+        self.lock().simulated
+    }
+
+    #[must_use]
+    pub fn broadcast(&self) -> usize {
+        self.lock().broadcast
+    }
+
+    fn lock(&self) -> impl std::ops::DerefMut<Target = ClientState> + '_ {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl BroadcastClient for StubClient {
+    async fn simulate(&mut self, _: RawTx) -> Result<Gas> {
+        let mut state = self.lock();
+
+        state.simulated += 1;
+
+        state
+            .simulations
+            .pop_front()
+            .unwrap_or(Ok(Self::DEFAULT_SIMULATED_GAS))
+    }
+
+    async fn broadcast(
+        &mut self,
+        _: RawTx,
+        _: node::BroadcastMode,
+    ) -> Result<TxResponse> {
+        let mut state = self.lock();
+
+        state.broadcast += 1;
+
+        Ok(state.responses.pop_front().unwrap_or_default())
+    }
+}
+
+#[derive(Default)]
+struct ClientState {
+    simulations: VecDeque<Result<Gas>>,
+    responses: VecDeque<TxResponse>,
+    simulated: usize,
+    broadcast: usize,
+}
+
+/// Signer stub recording the sequence number, gas limit and fee multiplier
+/// of each signed transaction instead of signing it.
+#[derive(Clone)]
+#[must_use]
+pub struct StubSigner {
+    sequence_number: SequenceNumber,
+    fee_multiplier_percent: u32,
+    fee_escalation: Option<FeeEscalation>,
+    signed: Arc<Mutex<Vec<SignedTx>>>,
+}
+
+impl StubSigner {
+    pub fn new() -> Self {
+        Self {
+            sequence_number: 0,
+            fee_multiplier_percent: FeeEscalation::BASELINE_PERCENT,
+            fee_escalation: None,
+            signed: Arc::default(),
+        }
+    }
+
+    pub fn with_fee_escalation(self, fee_escalation: FeeEscalation) -> Self {
+        Self {
+            fee_escalation: Some(fee_escalation),
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn signed(&self) -> Vec<SignedTx> {
+        self.signed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl TxSigner for StubSigner {
+    fn tx(&self, _: &Body, gas_limit: Gas) -> Result<RawTx> {
+        self.signed
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(SignedTx {
+                sequence_number: self.sequence_number,
+                gas_limit,
+                fee_multiplier_percent: self.fee_multiplier_percent,
+            });
+
+        Ok(RawTx::from(TxRaw {
+            body_bytes: vec![],
+            auth_info_bytes: self.sequence_number.to_be_bytes().into(),
+            signatures: vec![gas_limit.to_be_bytes().into()],
+        }))
+    }
+
+    fn tx_with_gas_adjustment(
+        &self,
+        body: &Body,
+        required_gas: Gas,
+        hard_gas_limit: Gas,
+    ) -> Result<RawTx> {
+        self.tx(body, required_gas.min(hard_gas_limit))
+    }
+
+    fn sequence_number(&self) -> SequenceNumber {
+        self.sequence_number
+    }
+
+    fn increment_sequence_number(&mut self) {
+        self.sequence_number += 1;
+    }
+
+    async fn fetch_sequence_number(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn refresh_sequence_number(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+
+    async fn query_sequence_drift(&mut self) -> Result<SequenceDrift> {
+        Ok(SequenceDrift {
+            local: self.sequence_number,
+            on_chain: self.sequence_number,
+        })
+    }
+
+    fn escalate_fee(&mut self) -> Option<u32> {
+        self.fee_escalation
+            .and_then(|fee_escalation| {
+                fee_escalation.next(self.fee_multiplier_percent)
+            })
+            .inspect(|&multiplier| self.fee_multiplier_percent = multiplier)
+    }
+
+    fn decay_fee(&mut self) -> Option<u32> {
+        self.fee_escalation
+            .and_then(|fee_escalation| {
+                fee_escalation.decayed(self.fee_multiplier_percent)
+            })
+            .inspect(|&multiplier| self.fee_multiplier_percent = multiplier)
+    }
+}
+
+/// Transaction signed by [`StubSigner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedTx {
+    pub sequence_number: SequenceNumber,
+    pub gas_limit: Gas,
+    pub fee_multiplier_percent: u32,
+}
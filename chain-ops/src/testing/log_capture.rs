@@ -0,0 +1,163 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    num::NonZeroU64,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+pub type Fields = BTreeMap<&'static str, String>;
+
+/// Subscriber recording every span and event, so tests can assert on what got
+/// logged.
+///
+/// Field values are recorded as strings, with string values kept as they are
+/// and the rest recorded through their [`Debug`] implementation.
+#[derive(Default)]
+#[must_use]
+pub struct LogCapture {
+    spans: Mutex<Vec<CapturedSpan>>,
+    entered: Mutex<Vec<usize>>,
+    events: Mutex<Vec<CapturedEvent>>,
+}
+
+impl LogCapture {
+    /// Constructs a capture with a `'static` lifetime, as required for it to
+    /// be used as a subscriber.
+    pub fn leak() -> &'static Self {
+        Box::leak(Box::default())
+    }
+
+    #[must_use]
+    pub fn events(&self) -> Vec<CapturedEvent> {
+        lock(&self.events).clone()
+    }
+
+    #[must_use]
+    pub fn spans(&self) -> Vec<CapturedSpan> {
+        lock(&self.spans).clone()
+    }
+
+    #[must_use]
+    pub fn count(&self, level: Level) -> usize {
+        lock(&self.events)
+            .iter()
+            .filter(|event| event.level == level)
+            .count()
+    }
+}
+
+impl Subscriber for &'static LogCapture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut fields = FieldsVisitor::default();
+
+        span.record(&mut fields);
+
+        let mut spans = lock(&self.spans);
+
+        spans.push(CapturedSpan {
+            name: span.metadata().name(),
+            fields: fields.0,
+        });
+
+        span::Id::from_non_zero_u64(
+            u64::try_from(spans.len())
+                .ok()
+                .and_then(NonZeroU64::new)
+                .unwrap_or(NonZeroU64::MAX),
+        )
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        if let Some(span) = lock(&self.spans).get_mut(index(span)) {
+            let mut fields = FieldsVisitor(span.fields.clone());
+
+            values.record(&mut fields);
+
+            span.fields = fields.0;
+        }
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = FieldsVisitor::default();
+
+        event.record(&mut fields);
+
+        let span = lock(&self.entered)
+            .last()
+            .and_then(|&index| lock(&self.spans).get(index).cloned());
+
+        lock(&self.events).push(CapturedEvent {
+            level: *event.metadata().level(),
+            target: event.metadata().target(),
+            fields: fields.0,
+            span,
+        });
+    }
+
+    fn enter(&self, span: &span::Id) {
+        lock(&self.entered).push(index(span));
+    }
+
+    fn exit(&self, _: &span::Id) {
+        _ = lock(&self.entered).pop();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedSpan {
+    pub name: &'static str,
+    pub fields: Fields,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedEvent {
+    pub level: Level,
+    pub target: &'static str,
+    pub fields: Fields,
+    /// The innermost span entered when the event got recorded.
+    pub span: Option<CapturedSpan>,
+}
+
+impl CapturedEvent {
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        self.field("message").unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct FieldsVisitor(Fields);
+
+impl Visit for FieldsVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        _ = self.0.insert(field.name(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        _ = self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn index(span: &span::Id) -> usize {
+    usize::try_from(span.into_u64() - 1).unwrap_or(usize::MAX)
+}
@@ -0,0 +1,12 @@
+//! Fixtures shared between tests, also available to dependent crates
+//! through the `testing` feature.
+
+pub use self::{
+    application_defined::{TestId, TestTask},
+    broadcast::{SignedTx, StubClient, StubSigner},
+    log_capture::{CapturedEvent, CapturedSpan, Fields, LogCapture},
+};
+
+mod application_defined;
+mod broadcast;
+mod log_capture;
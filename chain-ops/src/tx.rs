@@ -1,4 +1,10 @@
-use std::{convert::identity, time::Duration};
+use std::{
+    collections::VecDeque,
+    convert::identity,
+    fmt::{self, Display, Formatter},
+    future::Future,
+    time::Duration,
+};
 
 use anyhow::{Context as _, Result};
 use cosmrs::{
@@ -15,7 +21,7 @@ use prost::Message;
 use serde::{de::DeserializeOwned, Serialize};
 use tokio::time::{sleep, timeout};
 
-use crate::node;
+use crate::{json, node};
 
 macro_rules! log {
     ($macro:ident!($($body:tt)+)) => {
@@ -69,20 +75,83 @@ impl ExecuteTemplate {
     }
 }
 
+/// Codespace of the response handed back to sources in place of a node's
+/// response, when their transaction was signed, but not broadcast.
+const NOT_BROADCAST_CODESPACE: &str = "not_broadcast";
+
+/// Constructs the response handed back to a source in place of a node's
+/// response, when its transaction was signed, but not broadcast, e.g. when
+/// signing offline.
+#[must_use]
+pub fn not_broadcast_response() -> TxResponse {
+    TxResponse {
+        codespace: NOT_BROADCAST_CODESPACE.into(),
+        raw_log: "Transaction was signed, but not broadcast.".into(),
+        ..TxResponse::default()
+    }
+}
+
+/// Codespace of the response handed back to sources in place of a node's
+/// response, when their transaction was skipped because its simulation
+/// failed.
+const SIMULATION_FAILED_CODESPACE: &str = "simulation_failed";
+
+/// Constructs the response handed back to a source in place of a node's
+/// response, when its transaction was skipped because its simulation failed.
+#[must_use]
+pub fn simulation_failed_response(error: &anyhow::Error) -> TxResponse {
+    TxResponse {
+        codespace: SIMULATION_FAILED_CODESPACE.into(),
+        code: 1,
+        raw_log: format!("{error:#}"),
+        ..TxResponse::default()
+    }
+}
+
+/// Checks whether the response stands in for a transaction which was signed,
+/// but not broadcast, thus there is nothing to await.
+#[must_use]
+pub fn is_not_broadcast(response: &TxResponse) -> bool {
+    response.codespace == NOT_BROADCAST_CODESPACE
+}
+
+/// Checks whether the raw log reports a currency pair which the oracle
+/// contract doesn't support, the single classifier behind both the outcome
+/// category and the attribution of rejections to fed pairs.
+#[must_use]
+pub fn reports_unsupported_pair(raw_log: &str) -> bool {
+    const UNSUPPORTED_PAIR_MARKER: &str = "unsupported";
+
+    raw_log
+        .to_ascii_lowercase()
+        .contains(UNSUPPORTED_PAIR_MARKER)
+}
+
 pub async fn fetch_delivered(
     query_tx: &mut node::QueryTx,
     source: &str,
-    TxResponse {
-        code,
-        txhash: hash,
-        raw_log: log,
-        ..
-    }: TxResponse,
+    response: TxResponse,
     timeout_duration: Duration,
 ) -> Result<Option<TxResponse>> {
     const PRINT_ON_NTH: u8 = 5;
     const IDLE_SLEEP_DURATION: Duration = Duration::from_secs(2);
 
+    if is_not_broadcast(&response) {
+        log!(info!(
+            %source,
+            "Transaction was signed, but not broadcast. Nothing to fetch.",
+        ));
+
+        return Ok(None);
+    }
+
+    let TxResponse {
+        code,
+        txhash: hash,
+        raw_log: log,
+        ..
+    } = response;
+
     if TxCode::from(code).is_ok() {
         timeout(timeout_duration * PRINT_ON_NTH.into(), async move {
             let mut not_included_counter = 0;
@@ -131,6 +200,71 @@ pub async fn fetch_delivered(
     }
 }
 
+pub trait LatestBlockHeight: Send {
+    fn latest_block_height(
+        &mut self,
+    ) -> impl Future<Output = Result<u64>> + Send + '_;
+}
+
+impl LatestBlockHeight for node::QueryTendermint {
+    #[inline]
+    fn latest_block_height(
+        &mut self,
+    ) -> impl Future<Output = Result<u64>> + Send + '_ {
+        self.get_latest_block()
+    }
+}
+
+/// Waits until the block which included the delivered transaction is buried
+/// under at least `confirmations` blocks.
+///
+/// Returns immediately when no confirmations are required, and fails when
+/// they aren't reached within `timeout_duration`.
+pub async fn await_confirmations<C>(
+    client: &mut C,
+    source: &str,
+    delivered: &TxResponse,
+    confirmations: u64,
+    timeout_duration: Duration,
+) -> Result<()>
+where
+    C: LatestBlockHeight + ?Sized,
+{
+    const IDLE_SLEEP_DURATION: Duration = Duration::from_secs(2);
+
+    if confirmations == 0 {
+        return Ok(());
+    }
+
+    let confirmed_height = u64::try_from(delivered.height)
+        .ok()
+        .and_then(|height| height.checked_add(confirmations))
+        .context("Delivered transaction reported an invalid block height!")?;
+
+    timeout(timeout_duration, async {
+        loop {
+            match client.latest_block_height().await {
+                Ok(latest_height) if latest_height >= confirmed_height => {
+                    break;
+                },
+                Ok(_) => {},
+                Err(error) => {
+                    log!(error!(
+                        %source,
+                        hash = %delivered.txhash,
+                        ?error,
+                        "Error occurred while fetching latest block height!",
+                    ));
+                },
+            }
+
+            sleep(IDLE_SLEEP_DURATION).await;
+        }
+    })
+    .await
+    .context("Timed out while waiting for transaction's confirmations!")
+}
+
 pub fn adjust_fallback_gas(fallback_gas: Gas, gas_used: Gas) -> Result<Gas> {
     const FALLBACK_GAS_MAJOR_COEFFICIENT: u128 = 2;
     const FALLBACK_GAS_MINOR_COEFFICIENT: u128 = 1;
@@ -152,6 +286,101 @@ pub fn adjust_fallback_gas(fallback_gas: Gas, gas_used: Gas) -> Result<Gas> {
         .map_err(Into::into)
 }
 
+/// Rolling average of the ratio between the gas of delivered transactions,
+/// as estimated by simulating them before the gas adjustment is applied, and
+/// the gas they actually used.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct GasAccuracy {
+    ratios_percent: VecDeque<u64>,
+}
+
+impl GasAccuracy {
+    const WINDOW: usize = 10;
+
+    pub const fn new() -> Self {
+        Self {
+            ratios_percent: VecDeque::new(),
+        }
+    }
+
+    /// Records a delivered transaction's simulated and used gas, logging
+    /// whether the simulations consistently fall short of the used gas.
+    ///
+    /// Transactions which weren't simulated are ignored.
+    pub fn record_delivered(
+        &mut self,
+        source: &str,
+        simulated_gas: Option<Gas>,
+        delivered: &TxResponse,
+    ) {
+        let Some(simulated_gas) = simulated_gas else {
+            return;
+        };
+
+        self.record(simulated_gas, delivered.gas_used.unsigned_abs());
+
+        let average_percent = self.average_percent();
+
+        if self.under_provisions() {
+            log!(warn!(
+                %source,
+                ?average_percent,
+                "Simulated gas consistently falls short of the used gas! \
+                Transactions rely on the gas adjustment to not run out of \
+                gas.",
+            ));
+        } else {
+            log!(debug!(
+                %source,
+                ?average_percent,
+                "Simulated to used gas ratio.",
+            ));
+        }
+    }
+
+    /// Records a delivered transaction's simulated and used gas.
+    ///
+    /// Transactions which report no used gas are ignored.
+    fn record(&mut self, simulated_gas: Gas, gas_used: Gas) {
+        let Some(ratio_percent) = u128::from(simulated_gas)
+            .saturating_mul(100)
+            .checked_div(gas_used.into())
+        else {
+            return;
+        };
+
+        if self.ratios_percent.len() == Self::WINDOW {
+            _ = self.ratios_percent.pop_front();
+        }
+
+        self.ratios_percent
+            .push_back(ratio_percent.try_into().unwrap_or(u64::MAX));
+    }
+
+    /// Returns the average ratio, in percent, over the recorded window.
+    #[must_use]
+    pub fn average_percent(&self) -> Option<u64> {
+        let sum = self
+            .ratios_percent
+            .iter()
+            .fold(0_u64, |sum, &ratio| sum.saturating_add(ratio));
+
+        u64::try_from(self.ratios_percent.len())
+            .ok()
+            .and_then(|count| sum.checked_div(count))
+    }
+
+    /// Checks whether the simulations didn't cover the used gas on average
+    /// over a full window, leaving only the gas adjustment to prevent
+    /// transactions from running out of gas.
+    #[must_use]
+    pub fn under_provisions(&self) -> bool {
+        self.ratios_percent.len() == Self::WINDOW
+            && self.average_percent().is_some_and(|average| average < 100)
+    }
+}
+
 pub fn decode_execute_response<T>(tx_response: &TxResponse) -> Result<T>
 where
     T: DeserializeOwned,
@@ -167,9 +396,71 @@ where
         })
         .and_then(|response| response.to_msg().map_err(Into::into))
         .map(|response: MsgExecuteContractResponse| response.data)
-        .and_then(|response| {
-            serde_json_wasm::from_slice(&response).map_err(Into::into)
-        })
+        .and_then(|response| json::from_slice(&response))
+}
+
+/// Category of a transaction's outcome, derived from its code and raw log,
+/// so failures can be charted by their cause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OutcomeCategory {
+    Success,
+    SequenceMismatch,
+    InsufficientFee,
+    OutOfGas,
+    UnsupportedPair,
+    Other,
+}
+
+impl OutcomeCategory {
+    const SDK_CODESPACE: &'static str = "sdk";
+
+    const INSUFFICIENT_FEE_ERROR_CODE: u32 = 13;
+
+    const SEQUENCE_MISMATCH_ERROR_CODE: u32 = 32;
+
+    #[must_use]
+    pub fn of(response: &TxResponse) -> Self {
+        if response.code == 0 {
+            return Self::Success;
+        }
+
+        if response.codespace == Self::SDK_CODESPACE {
+            match response.code {
+                Self::SEQUENCE_MISMATCH_ERROR_CODE => {
+                    return Self::SequenceMismatch;
+                },
+                Self::INSUFFICIENT_FEE_ERROR_CODE => {
+                    return Self::InsufficientFee;
+                },
+                OUT_OF_GAS_ERROR_CODE => return Self::OutOfGas,
+                _ => {},
+            }
+        }
+
+        if reports_unsupported_pair(&response.raw_log) {
+            Self::UnsupportedPair
+        } else {
+            Self::Other
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::SequenceMismatch => "sequence_mismatch",
+            Self::InsufficientFee => "insufficient_fee",
+            Self::OutOfGas => "out_of_gas",
+            Self::UnsupportedPair => "unsupported_pair",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl Display for OutcomeCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 #[derive(Message)]
@@ -177,3 +468,187 @@ struct Package {
     #[prost(bytes, tag = "2")]
     data: Vec<u8>,
 }
+
+#[tokio::test(start_paused = true)]
+async fn test_await_confirmations() {
+    use tokio::time::Instant;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    struct Stub {
+        height: u64,
+        queries: usize,
+    }
+
+    impl LatestBlockHeight for Stub {
+        async fn latest_block_height(&mut self) -> Result<u64> {
+            self.queries += 1;
+
+            self.height += 1;
+
+            Ok(self.height)
+        }
+    }
+
+    let delivered = TxResponse {
+        height: 100,
+        ..TxResponse::default()
+    };
+
+    let mut stub = Stub {
+        height: 100,
+        queries: 0,
+    };
+
+    await_confirmations(&mut stub, "test", &delivered, 0, TIMEOUT)
+        .await
+        .unwrap();
+
+    assert_eq!(stub.queries, 0);
+
+    let started_at = Instant::now();
+
+    await_confirmations(&mut stub, "test", &delivered, 3, TIMEOUT)
+        .await
+        .unwrap();
+
+    assert_eq!(stub.height, 103);
+
+    assert_eq!(stub.queries, 3);
+
+    assert_eq!(started_at.elapsed(), Duration::from_secs(4));
+
+    let invalid = TxResponse {
+        height: -1,
+        ..TxResponse::default()
+    };
+
+    assert!(await_confirmations(&mut stub, "test", &invalid, 1, TIMEOUT)
+        .await
+        .is_err());
+
+    let mut lagging = Stub {
+        height: 100,
+        queries: 0,
+    };
+
+    let started_at = Instant::now();
+
+    assert!(await_confirmations(
+        &mut lagging,
+        "test",
+        &delivered,
+        1000,
+        TIMEOUT
+    )
+    .await
+    .is_err());
+
+    assert_eq!(started_at.elapsed(), TIMEOUT);
+}
+
+#[test]
+fn test_outcome_categories() {
+    fn category(codespace: &str, code: u32, raw_log: &str) -> OutcomeCategory {
+        OutcomeCategory::of(&TxResponse {
+            codespace: codespace.into(),
+            code,
+            raw_log: raw_log.into(),
+            ..TxResponse::default()
+        })
+    }
+
+    assert_eq!(category("", 0, "[]"), OutcomeCategory::Success);
+
+    assert_eq!(
+        category(
+            "sdk",
+            32,
+            "account sequence mismatch, expected 43, got 40: incorrect \
+            account sequence",
+        ),
+        OutcomeCategory::SequenceMismatch,
+    );
+
+    assert_eq!(
+        category(
+            "sdk",
+            13,
+            "insufficient fees; got: 1000unls required: 2000unls: \
+            insufficient fee",
+        ),
+        OutcomeCategory::InsufficientFee,
+    );
+
+    assert_eq!(
+        category(
+            "sdk",
+            11,
+            "out of gas in location: WriteFlat; gasWanted: 200000, gasUsed: \
+            200153: out of gas",
+        ),
+        OutcomeCategory::OutOfGas,
+    );
+
+    assert_eq!(
+        category(
+            "wasm",
+            5,
+            "failed to execute message; message index: 0: Unsupported \
+            currency pair: ATOM/USDC_NOBLE: execute wasm contract failed",
+        ),
+        OutcomeCategory::UnsupportedPair,
+    );
+
+    assert_eq!(
+        category("wasm", 13, "insufficient funds"),
+        OutcomeCategory::Other,
+    );
+
+    assert_eq!(OutcomeCategory::OutOfGas.to_string(), "out_of_gas");
+}
+
+#[test]
+fn test_gas_accuracy_ratio() {
+    let mut gas_accuracy = GasAccuracy::new();
+
+    assert_eq!(gas_accuracy.average_percent(), None);
+
+    gas_accuracy.record(300_000, 200_000);
+
+    gas_accuracy.record(200_000, 200_000);
+
+    gas_accuracy.record(100_000, 0);
+
+    assert_eq!(gas_accuracy.average_percent(), Some(125));
+
+    assert!(!gas_accuracy.under_provisions());
+
+    for _ in 0..GasAccuracy::WINDOW - 1 {
+        gas_accuracy.record(180_000, 200_000);
+    }
+
+    assert_eq!(gas_accuracy.average_percent(), Some(91));
+
+    assert!(gas_accuracy.under_provisions());
+
+    gas_accuracy.record(1_000_000, 200_000);
+
+    assert_eq!(gas_accuracy.average_percent(), Some(131));
+
+    assert!(!gas_accuracy.under_provisions());
+
+    let delivered = TxResponse {
+        gas_wanted: 1_000_000,
+        gas_used: 200_000,
+        ..TxResponse::default()
+    };
+
+    gas_accuracy.record_delivered("Test", None, &delivered);
+
+    assert_eq!(gas_accuracy.average_percent(), Some(131));
+
+    gas_accuracy.record_delivered("Test", Some(20_000), &delivered);
+
+    assert_eq!(gas_accuracy.average_percent(), Some(123));
+}
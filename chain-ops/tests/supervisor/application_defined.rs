@@ -7,7 +7,7 @@ use std::{
     },
 };
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use tracing::info;
 
 use chain_ops::{
@@ -19,9 +19,16 @@ use chain_ops::{
 
 use super::Context;
 
+/// Protocol whose tasks always fail to initialize.
+pub(crate) const BROKEN_PROTOCOL: &str = "broken";
+
+/// Protocol whose tasks' dependencies are never ready.
+pub(crate) const DEFERRED_PROTOCOL: &str = "deferred";
+
 pub(crate) struct Task {
     protocol: Arc<str>,
     app_defined_tasks_count: Arc<AtomicUsize>,
+    app_defined_tasks_started: Arc<AtomicUsize>,
 }
 
 impl Drop for Task {
@@ -38,6 +45,9 @@ impl Runnable for Task {
 
         self.app_defined_tasks_count.fetch_add(1, Ordering::AcqRel);
 
+        self.app_defined_tasks_started
+            .fetch_add(1, Ordering::AcqRel);
+
         pending().await
     }
 }
@@ -87,11 +97,22 @@ impl application_defined::Id for Id {
         &mut (): &'r mut Self::TaskCreationContext,
         _: &'r channel::unbounded::Sender<TxPackage<NoExpiration>>,
     ) -> Result<Self::Task> {
+        if &*self.protocol == BROKEN_PROTOCOL {
+            bail!("Protocol is misconfigured!");
+        }
+
+        if &*self.protocol == DEFERRED_PROTOCOL {
+            bail!(application_defined::NotReady);
+        }
+
         Ok(Self::Task {
             protocol: self.protocol.clone(),
             app_defined_tasks_count: service_configuration
                 .application_defined_tasks_count
                 .clone(),
+            app_defined_tasks_started: service_configuration
+                .application_defined_tasks_started
+                .clone(),
         })
     }
 }
@@ -17,7 +17,7 @@ use chain_ops::{
     },
 };
 
-use super::Context;
+use super::{application_defined::BROKEN_PROTOCOL, Context};
 
 pub(crate) struct TestingBalanceReporter;
 
@@ -74,7 +74,9 @@ impl Broadcast for TestingBroadcast {
     #[inline]
     fn new(
         _: &Self::ServiceConfiguration,
-        _: channel::unbounded::Receiver<TxPackage<Self::TxExpiration>>,
+        _: channel::unbounded::ReclaimableReceiver<
+            TxPackage<Self::TxExpiration>,
+        >,
     ) -> Self {
         const { Self {} }
     }
@@ -106,6 +108,17 @@ impl Runnable for TestingProtocolWatcher {
             let protocols: [Arc<str>; 2] =
                 std::array::from_fn(|i| (i + 1).to_string().into());
 
+            info!(
+                protocol = BROKEN_PROTOCOL,
+                "Starting application defined task of broken protocol.",
+            );
+
+            self.command_tx
+                .send(protocol_watcher::Command::ProtocolAdded(
+                    BROKEN_PROTOCOL.into(),
+                ))
+                .await?;
+
             for protocol in protocols.iter().cloned() {
                 info!(%protocol, "Starting application defined task.");
 
@@ -129,6 +142,14 @@ impl Runnable for TestingProtocolWatcher {
 
             info!("Protocols spawned.");
 
+            info!(protocol = %protocols[0], "Re-announcing running protocol.");
+
+            self.command_tx
+                .send(protocol_watcher::Command::ProtocolAdded(
+                    protocols[0].clone(),
+                ))
+                .await?;
+
             for (count, protocol) in protocols.into_iter().enumerate().rev() {
                 info!(%protocol, "Stopping application defined task.");
 
@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, future::pending, sync::Arc};
+
+use anyhow::Result;
+
+use chain_ops::{
+    channel,
+    task::{
+        protocol_watcher, BuiltIn, Id, ProtocolWatcher, Runnable,
+        RunnableState, State,
+    },
+};
+
+use super::{application_defined::BROKEN_PROTOCOL, Context};
+
+/// Protocols announced by [`AnnouncingProtocolWatcher`], besides the broken
+/// one.
+pub(crate) const ANNOUNCED_PROTOCOLS: [&str; 2] = ["neutron", "osmosis"];
+
+/// Protocol watcher which announces a fixed set of protocols, including a
+/// broken one, and then idles.
+pub(crate) struct AnnouncingProtocolWatcher {
+    command_tx: channel::bounded::Sender<protocol_watcher::Command>,
+}
+
+impl Runnable for AnnouncingProtocolWatcher {
+    async fn run(self, _: RunnableState) -> Result<()> {
+        for protocol in ANNOUNCED_PROTOCOLS.into_iter().chain([BROKEN_PROTOCOL])
+        {
+            self.command_tx
+                .send(protocol_watcher::Command::ProtocolAdded(Arc::from(
+                    protocol,
+                )))
+                .await?;
+        }
+
+        pending().await
+    }
+}
+
+impl BuiltIn for AnnouncingProtocolWatcher {
+    type ServiceConfiguration = Context;
+}
+
+impl ProtocolWatcher for AnnouncingProtocolWatcher {
+    #[inline]
+    fn new<ApplicationDefined>(
+        _: &Self::ServiceConfiguration,
+        _: &BTreeMap<Id<ApplicationDefined>, State>,
+        command_tx: channel::bounded::Sender<protocol_watcher::Command>,
+    ) -> Self
+    where
+        ApplicationDefined: chain_ops::task::application_defined::Id,
+    {
+        Self { command_tx }
+    }
+}
@@ -12,19 +12,34 @@ use tracing::Level;
 
 use chain_ops::{
     service::{run, ShutdownResult},
-    supervisor::{configuration::Configuration, Supervisor},
+    supervisor::{
+        configuration::{Configuration, PanicRestartPolicy},
+        RecordedError, Supervisor,
+    },
+    task::StartDelays,
 };
 
-use self::builtin_tasks::{
-    TestingBalanceReporter, TestingBroadcast, TestingProtocolWatcher,
+use self::{
+    application_defined::{BROKEN_PROTOCOL, DEFERRED_PROTOCOL},
+    builtin_tasks::{
+        TestingBalanceReporter, TestingBroadcast, TestingProtocolWatcher,
+    },
+    introspection::{AnnouncingProtocolWatcher, ANNOUNCED_PROTOCOLS},
+    restart_cooldown::{
+        DeferringProtocolWatcher, FailingBroadcast, IdleProtocolWatcher,
+        LateFailingBroadcast, BROADCAST_RESTARTED, BROADCAST_STARTS,
+    },
 };
 
 mod application_defined;
 mod builtin_tasks;
+mod introspection;
+mod restart_cooldown;
 
 #[derive(Clone)]
 struct Context {
     application_defined_tasks_count: Arc<AtomicUsize>,
+    application_defined_tasks_started: Arc<AtomicUsize>,
     notify: Arc<Notify>,
 }
 
@@ -40,6 +55,9 @@ async fn supervisor() {
 
             let application_defined_tasks_count = Arc::new(AtomicUsize::new(0));
 
+            let application_defined_tasks_started =
+                Arc::new(AtomicUsize::new(0));
+
             let abort_handle = spawn(
                 Supervisor::<
                     TestingBalanceReporter,
@@ -51,9 +69,14 @@ async fn supervisor() {
                         Context {
                             application_defined_tasks_count:
                                 application_defined_tasks_count.clone(),
+                            application_defined_tasks_started:
+                                application_defined_tasks_started.clone(),
                             notify: notify.clone(),
                         },
                         (),
+                        PanicRestartPolicy::Defer,
+                        StartDelays::default(),
+                        Duration::ZERO,
                     ),
                     task_spawner,
                     task_result_rx,
@@ -78,6 +101,250 @@ async fn supervisor() {
                 0
             );
 
+            assert_eq!(
+                application_defined_tasks_started.load(Ordering::Acquire),
+                2,
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    () = match shutdown_result {
+        ShutdownResult::Exited(join_result) => join_result.unwrap().unwrap(),
+        ShutdownResult::StopSignalReceived => unreachable!(),
+    };
+}
+
+#[tokio::test(start_paused = true)]
+async fn restart_after_broadcast_failure_waits_cooldown() {
+    const COOLDOWN: Duration = Duration::from_secs(30);
+
+    let shutdown_result: ShutdownResult<Result<()>> =
+        run(|task_spawner, task_result_rx| async move {
+            let restarted = BROADCAST_RESTARTED.notified();
+
+            let supervisor = Supervisor::<
+                TestingBalanceReporter,
+                FailingBroadcast,
+                IdleProtocolWatcher,
+                application_defined::Task,
+            >::new(
+                Configuration::new(
+                    Context {
+                        application_defined_tasks_count: Arc::default(),
+                        application_defined_tasks_started: Arc::default(),
+                        notify: Arc::default(),
+                    },
+                    (),
+                    PanicRestartPolicy::Defer,
+                    StartDelays::default(),
+                    COOLDOWN,
+                ),
+                task_spawner,
+                task_result_rx,
+                "supervisor-test",
+                "0.0.0",
+                [] as [application_defined::Id; 0],
+            )
+            .await?;
+
+            let mut introspection = supervisor.introspection();
+
+            let abort_handle = spawn(supervisor.run());
+
+            () = timeout(COOLDOWN * 2, restarted).await.unwrap();
+
+            let recent_errors = timeout(
+                COOLDOWN,
+                introspection.wait_for(|introspection| {
+                    introspection.recent_errors.contains_key("Broadcast")
+                }),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .recent_errors["Broadcast"]
+                .clone();
+
+            () = abort_handle.abort();
+
+            _ = abort_handle.await.unwrap_err();
+
+            let starts = BROADCAST_STARTS.lock().unwrap().clone();
+
+            let [failed_at, restarted_at] = starts.as_slice() else {
+                panic!("Expected broadcaster to be started exactly twice!");
+            };
+
+            assert!(restarted_at.duration_since(*failed_at) >= COOLDOWN);
+
+            assert_eq!(
+                recent_errors
+                    .iter()
+                    .map(RecordedError::error)
+                    .collect::<Vec<_>>(),
+                ["Node is unreachable!"],
+            );
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    () = match shutdown_result {
+        ShutdownResult::Exited(join_result) => join_result.unwrap().unwrap(),
+        ShutdownResult::StopSignalReceived => unreachable!(),
+    };
+}
+
+#[tokio::test(start_paused = true)]
+async fn deferred_restart_survives_broadcast_cooldown() {
+    const COOLDOWN: Duration = Duration::from_secs(30);
+
+    let shutdown_result: ShutdownResult<Result<()>> =
+        run(|task_spawner, task_result_rx| async move {
+            let supervisor = Supervisor::<
+                TestingBalanceReporter,
+                LateFailingBroadcast,
+                DeferringProtocolWatcher,
+                application_defined::Task,
+            >::new(
+                Configuration::new(
+                    Context {
+                        application_defined_tasks_count: Arc::default(),
+                        application_defined_tasks_started: Arc::default(),
+                        notify: Arc::default(),
+                    },
+                    (),
+                    PanicRestartPolicy::Defer,
+                    StartDelays::default(),
+                    COOLDOWN,
+                ),
+                task_spawner,
+                task_result_rx,
+                "supervisor-test",
+                "0.0.0",
+                [] as [application_defined::Id; 0],
+            )
+            .await?;
+
+            let mut introspection = supervisor.introspection();
+
+            let abort_handle = spawn(supervisor.run());
+
+            let snapshot = timeout(
+                COOLDOWN,
+                introspection.wait_for(|introspection| {
+                    introspection.recent_errors.contains_key("Broadcast")
+                }),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .clone();
+
+            () = abort_handle.abort();
+
+            _ = abort_handle.await.unwrap_err();
+
+            assert_eq!(
+                snapshot
+                    .restart_queue
+                    .iter()
+                    .map(|queued| &*queued.task)
+                    .collect::<Vec<_>>(),
+                [
+                    "Balance Reporter",
+                    "Broadcast",
+                    "Protocol Watcher",
+                    DEFERRED_PROTOCOL,
+                ],
+            );
+
+            assert!(snapshot
+                .restart_queue
+                .iter()
+                .all(|queued| queued.eta == snapshot.restart_queue[0].eta));
+
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+    () = match shutdown_result {
+        ShutdownResult::Exited(join_result) => join_result.unwrap().unwrap(),
+        ShutdownResult::StopSignalReceived => unreachable!(),
+    };
+}
+
+#[tokio::test]
+async fn introspection_reflects_added_protocols() {
+    let shutdown_result: ShutdownResult<Result<()>> =
+        run(|task_spawner, task_result_rx| async move {
+            let supervisor = Supervisor::<
+                TestingBalanceReporter,
+                TestingBroadcast,
+                AnnouncingProtocolWatcher,
+                application_defined::Task,
+            >::new(
+                Configuration::new(
+                    Context {
+                        application_defined_tasks_count: Arc::default(),
+                        application_defined_tasks_started: Arc::default(),
+                        notify: Arc::default(),
+                    },
+                    (),
+                    PanicRestartPolicy::Defer,
+                    StartDelays::default(),
+                    Duration::ZERO,
+                ),
+                task_spawner,
+                task_result_rx,
+                "supervisor-test",
+                "0.0.0",
+                [] as [application_defined::Id; 0],
+            )
+            .await?;
+
+            let mut introspection = supervisor.introspection();
+
+            let abort_handle = spawn(supervisor.run());
+
+            let snapshot = timeout(
+                Duration::from_secs(5),
+                introspection.wait_for(|introspection| {
+                    introspection.protocol_tasks.len()
+                        == ANNOUNCED_PROTOCOLS.len()
+                        && !introspection.restart_queue.is_empty()
+                }),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .clone();
+
+            () = abort_handle.abort();
+
+            _ = abort_handle.await.unwrap_err();
+
+            assert_eq!(
+                snapshot.protocol_tasks,
+                ANNOUNCED_PROTOCOLS
+                    .map(|protocol| (protocol.into(), vec![protocol.into()]))
+                    .into(),
+            );
+
+            assert_eq!(
+                snapshot
+                    .restart_queue
+                    .iter()
+                    .map(|queued| &*queued.task)
+                    .collect::<Vec<_>>(),
+                [BROKEN_PROTOCOL],
+            );
+
             Ok(())
         })
         .await
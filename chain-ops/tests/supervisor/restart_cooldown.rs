@@ -0,0 +1,177 @@
+use std::{
+    collections::BTreeMap,
+    future::pending,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use tokio::{
+    sync::Notify,
+    time::{sleep, Instant},
+};
+use tracing::info;
+
+use chain_ops::{
+    channel,
+    task::{
+        protocol_watcher, Broadcast, BuiltIn, Id, NoExpiration,
+        ProtocolWatcher, Runnable, RunnableState, State, TxPackage,
+    },
+};
+
+use super::{application_defined::DEFERRED_PROTOCOL, Context};
+
+/// Instants at which the broadcaster was started.
+pub(crate) static BROADCAST_STARTS: Mutex<Vec<Instant>> =
+    Mutex::new(Vec::new());
+
+pub(crate) static BROADCAST_RESTARTED: Notify = Notify::const_new();
+
+/// Broadcaster which fails the first time it's started.
+pub(crate) struct FailingBroadcast;
+
+impl Runnable for FailingBroadcast {
+    async fn run(self, _: RunnableState) -> Result<()> {
+        let starts = {
+            let mut starts = BROADCAST_STARTS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+
+            starts.push(Instant::now());
+
+            starts.len()
+        };
+
+        if starts == 1 {
+            info!("Broadcast failing.");
+
+            bail!("Node is unreachable!");
+        }
+
+        info!("Broadcast restarted.");
+
+        () = BROADCAST_RESTARTED.notify_waiters();
+
+        pending().await
+    }
+}
+
+impl BuiltIn for FailingBroadcast {
+    type ServiceConfiguration = Context;
+}
+
+impl Broadcast for FailingBroadcast {
+    type TxExpiration = NoExpiration;
+
+    #[inline]
+    fn new(
+        _: &Self::ServiceConfiguration,
+        _: channel::unbounded::ReclaimableReceiver<
+            TxPackage<Self::TxExpiration>,
+        >,
+    ) -> Self {
+        const { Self {} }
+    }
+}
+
+pub(crate) struct IdleProtocolWatcher;
+
+impl Runnable for IdleProtocolWatcher {
+    #[inline]
+    async fn run(self, _: RunnableState) -> Result<()> {
+        pending().await
+    }
+}
+
+impl BuiltIn for IdleProtocolWatcher {
+    type ServiceConfiguration = Context;
+}
+
+impl ProtocolWatcher for IdleProtocolWatcher {
+    #[inline]
+    fn new<ApplicationDefined>(
+        _: &Self::ServiceConfiguration,
+        _: &BTreeMap<Id<ApplicationDefined>, State>,
+        _: channel::bounded::Sender<protocol_watcher::Command>,
+    ) -> Self
+    where
+        ApplicationDefined: chain_ops::task::application_defined::Id,
+    {
+        const { Self {} }
+    }
+}
+
+static LATE_BROADCAST_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Broadcaster which fails a second after it's started for the first time.
+pub(crate) struct LateFailingBroadcast;
+
+impl Runnable for LateFailingBroadcast {
+    async fn run(self, _: RunnableState) -> Result<()> {
+        if !LATE_BROADCAST_FAILED.swap(true, Ordering::AcqRel) {
+            sleep(Duration::from_secs(1)).await;
+
+            bail!("Node is unreachable!");
+        }
+
+        pending().await
+    }
+}
+
+impl BuiltIn for LateFailingBroadcast {
+    type ServiceConfiguration = Context;
+}
+
+impl Broadcast for LateFailingBroadcast {
+    type TxExpiration = NoExpiration;
+
+    #[inline]
+    fn new(
+        _: &Self::ServiceConfiguration,
+        _: channel::unbounded::ReclaimableReceiver<
+            TxPackage<Self::TxExpiration>,
+        >,
+    ) -> Self {
+        const { Self {} }
+    }
+}
+
+/// Protocol watcher which announces a protocol whose tasks are deferred
+/// until their dependencies are ready, and then idles.
+pub(crate) struct DeferringProtocolWatcher {
+    command_tx: channel::bounded::Sender<protocol_watcher::Command>,
+}
+
+impl Runnable for DeferringProtocolWatcher {
+    async fn run(self, _: RunnableState) -> Result<()> {
+        self.command_tx
+            .send(protocol_watcher::Command::ProtocolAdded(Arc::from(
+                DEFERRED_PROTOCOL,
+            )))
+            .await?;
+
+        pending().await
+    }
+}
+
+impl BuiltIn for DeferringProtocolWatcher {
+    type ServiceConfiguration = Context;
+}
+
+impl ProtocolWatcher for DeferringProtocolWatcher {
+    #[inline]
+    fn new<ApplicationDefined>(
+        _: &Self::ServiceConfiguration,
+        _: &BTreeMap<Id<ApplicationDefined>, State>,
+        command_tx: channel::bounded::Sender<protocol_watcher::Command>,
+    ) -> Self
+    where
+        ApplicationDefined: chain_ops::task::application_defined::Id,
+    {
+        Self { command_tx }
+    }
+}
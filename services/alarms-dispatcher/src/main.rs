@@ -2,6 +2,8 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::missing_errors_doc)]
 
+use std::time::Duration;
+
 use anyhow::{Context as _, Result};
 use cosmrs::Gas;
 
@@ -16,6 +18,7 @@ run_app!(
             time_alarms_per_message: read_time_alarms_per_message()?,
             gas_per_price_alarm: read_gas_per_price_alarm()?,
             price_alarms_per_message: read_price_alarms_per_message()?,
+            max_idle_duration: read_max_idle_duration()?,
         })
     },
     startup_tasks: [task::Id::TimeAlarmsGenerator].into_iter(),
@@ -26,6 +29,7 @@ pub struct ApplicationDefinedContext {
     pub time_alarms_per_message: u32,
     pub gas_per_price_alarm: Gas,
     pub price_alarms_per_message: u32,
+    pub max_idle_duration: Option<Duration>,
 }
 
 fn read_gas_per_time_alarm() -> Result<Gas> {
@@ -47,3 +51,9 @@ fn read_price_alarms_per_message() -> Result<u32> {
     u32::read_from_var("PRICE_ALARMS_MAX_ALARMS_GROUP")
         .context("Failed to read maximum count of price alarms per message!")
 }
+
+fn read_max_idle_duration() -> Result<Option<Duration>> {
+    Option::<u64>::read_from_var("MAX_IDLE_DURATION_SECONDS")
+        .map(|max_idle| max_idle.map(Duration::from_secs))
+        .context("Failed to read maximum idle period duration!")
+}
@@ -2,10 +2,7 @@ use std::{sync::Arc, time::Duration};
 
 use anyhow::{anyhow, Context as _, Result};
 use cosmrs::{
-    proto::{
-        cosmos::base::abci::v1beta1::TxResponse,
-        cosmwasm::wasm::v1::MsgExecuteContract,
-    },
+    proto::cosmwasm::wasm::v1::MsgExecuteContract,
     tendermint::{abci::Code as TxCode, block::Height},
     tx::Body as TxBody,
     Any, Gas,
@@ -15,12 +12,16 @@ use tokio::{
     sync::{mpsc, oneshot},
     time::sleep,
 };
+use tracing::Instrument as _;
 
 use chain_ops::{
     channel::unbounded,
     contract::{Compatibility, SemVer},
     node,
-    task::{NoExpiration, Runnable, RunnableState, TxPackage},
+    task::{
+        CorrelationId, NoExpiration, Runnable, RunnableState, TxFeedback,
+        TxPackage,
+    },
     tx,
 };
 
@@ -51,6 +52,7 @@ pub struct Configuration {
     pub alarms_per_message: u32,
     pub gas_per_alarm: Gas,
     pub idle_duration: Duration,
+    pub max_idle_duration: Option<Duration>,
     pub timeout_duration: Duration,
 }
 
@@ -71,10 +73,11 @@ where
     address: Arc<str>,
     alarms_per_message: u32,
     gas_per_alarm: Gas,
-    idle_duration: Duration,
+    idle_backoff: IdleBackoff,
     timeout_duration: Duration,
     tx_body: Arc<TxBody>,
     source: Arc<str>,
+    gas_accuracy: tx::GasAccuracy,
     alarms: T,
 }
 
@@ -115,6 +118,7 @@ where
             alarms_per_message,
             gas_per_alarm,
             idle_duration,
+            max_idle_duration,
             timeout_duration,
         }: Configuration,
         source: Arc<str>,
@@ -136,7 +140,7 @@ where
             address,
             alarms_per_message,
             gas_per_alarm,
-            idle_duration,
+            idle_backoff: IdleBackoff::new(idle_duration, max_idle_duration),
             timeout_duration,
             tx_body: Arc::new(TxBody {
                 messages: vec![message],
@@ -146,6 +150,7 @@ where
                 non_critical_extension_options: Vec::new(),
             }),
             source,
+            gas_accuracy: tx::GasAccuracy::new(),
             alarms,
         })
         .map_err(Into::into)
@@ -182,13 +187,15 @@ where
         let mut fallback_gas = 0;
 
         loop {
-            if self.alarms_status().await?.remaining_alarms {
+            let remaining_alarms = self.alarms_status().await?.remaining_alarms;
+
+            if remaining_alarms {
                 fallback_gas = self
                     .dispatch_alarms_streak(hard_gas_limit, fallback_gas)
                     .await?;
             }
 
-            sleep(self.idle_duration).await;
+            sleep(self.idle_backoff.next_idle_duration(remaining_alarms)).await;
         }
     }
 
@@ -206,7 +213,10 @@ where
         mut fallback_gas_per_alarm: Gas,
     ) -> Result<Gas> {
         loop {
-            let Some(response) = self
+            let Some(TxFeedback {
+                response,
+                simulated_gas,
+            }) = self
                 .broadcast(hard_gas_limit, fallback_gas_per_alarm)
                 .await?
             else {
@@ -215,6 +225,20 @@ where
                 continue;
             };
 
+            chain_ops::log_tx_response!(
+                target: "alarms-dispatcher",
+                stage: "delivered",
+                source: self.source,
+                failure: info,
+                response,
+            );
+
+            self.gas_accuracy.record_delivered(
+                &self.source,
+                simulated_gas,
+                &response,
+            );
+
             let code: TxCode = response.code.into();
 
             let dispatched_alarms = if code.is_ok() {
@@ -228,14 +252,12 @@ where
                 dispatched_alarms
             } else if code.value() == tx::OUT_OF_GAS_ERROR_CODE {
                 log_with_hash!(warn![self, response](
-                    log = ?response.raw_log,
                     "Transaction failed, likely because it ran out of gas.",
                 ));
 
                 self.alarms_per_message
             } else {
                 log_with_hash!(error![self, response](
-                    log = ?response.raw_log,
                     "Transaction failed because of unknown reason!",
                 ));
 
@@ -275,24 +297,39 @@ where
         &mut self,
         hard_gas_limit: Gas,
         fallback_gas_per_alarm: Gas,
-    ) -> Result<Option<TxResponse>> {
-        let response_receiver =
-            self.send_for_broadcasting(hard_gas_limit, fallback_gas_per_alarm)?;
+    ) -> Result<Option<TxFeedback>> {
+        let correlation_id = CorrelationId::next();
+
+        let response_receiver = self.send_for_broadcasting(
+            hard_gas_limit,
+            fallback_gas_per_alarm,
+            correlation_id,
+        )?;
+
+        let TxFeedback {
+            response,
+            simulated_gas,
+        } = response_receiver.await?;
 
         tx::fetch_delivered(
             &mut self.query_tx,
             &self.source,
-            response_receiver.await?,
+            response,
             self.timeout_duration,
         )
+        .instrument(correlation_id.span())
         .await
+        .map(|delivered| {
+            delivered.map(|delivered| TxFeedback::new(delivered, simulated_gas))
+        })
     }
 
     fn send_for_broadcasting(
         &mut self,
         hard_gas_limit: Gas,
         fallback_gas_per_alarm: Gas,
-    ) -> Result<oneshot::Receiver<TxResponse>> {
+        correlation_id: CorrelationId,
+    ) -> Result<oneshot::Receiver<TxFeedback>> {
         let (response_sender, response_receiver) = oneshot::channel();
 
         self.transaction_tx
@@ -304,6 +341,7 @@ where
                     .wrapping_mul(self.alarms_per_message.into()),
                 feedback_sender: response_sender,
                 expiration: NoExpiration,
+                correlation_id,
             })
             .map(|()| response_receiver)
             .context("Failed to send transaction for broadcasting!")
@@ -321,6 +359,40 @@ where
     }
 }
 
+/// Grows the period between polls while consecutive polls find no alarms to
+/// dispatch, doubling it up to the configured maximum, and resets it back to
+/// the base period once alarms appear.
+#[derive(Debug, Clone, Copy)]
+struct IdleBackoff {
+    base: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl IdleBackoff {
+    fn new(base: Duration, max: Option<Duration>) -> Self {
+        Self {
+            base,
+            max: max.map_or(base, |max| max.max(base)),
+            current: base,
+        }
+    }
+
+    fn next_idle_duration(&mut self, dispatched: bool) -> Duration {
+        if dispatched {
+            self.current = self.base;
+
+            return self.base;
+        }
+
+        let idle_duration = self.current;
+
+        self.current = self.current.saturating_mul(2).min(self.max);
+
+        idle_duration
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[repr(transparent)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
@@ -362,3 +434,27 @@ impl Alarms for TimeAlarms {
 }
 
 type DispatchAlarmsResponse = u32;
+
+#[test]
+fn test_idle_backoff_growth_and_reset() {
+    const BASE: Duration = Duration::from_secs(5);
+
+    let mut idle_backoff =
+        IdleBackoff::new(BASE, Some(Duration::from_secs(30)));
+
+    let idle_durations: Vec<u64> = (0..6)
+        .map(|_| idle_backoff.next_idle_duration(false).as_secs())
+        .collect();
+
+    assert_eq!(idle_durations, [5, 10, 20, 30, 30, 30]);
+
+    assert_eq!(idle_backoff.next_idle_duration(true), BASE);
+
+    assert_eq!(idle_backoff.next_idle_duration(false), BASE);
+
+    assert_eq!(idle_backoff.next_idle_duration(false), BASE * 2);
+
+    let mut disabled = IdleBackoff::new(BASE, None);
+
+    assert!((0..4).all(|_| disabled.next_idle_duration(false) == BASE));
+}
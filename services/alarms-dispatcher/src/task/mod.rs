@@ -7,7 +7,8 @@ use chain_ops::{
     contract::admin::{BaseProtocol, ProtocolContracts},
     supervisor::configuration,
     task::{
-        application_defined, NoExpiration, Runnable, RunnableState, TxPackage,
+        application_defined, protocol_watcher, NoExpiration, Runnable,
+        RunnableState, TxPackage,
     },
 };
 
@@ -58,6 +59,8 @@ impl Id {
                             .time_alarms_per_message,
                         gas_per_alarm: task_creation_context.gas_per_time_alarm,
                         idle_duration: service_configuration.idle_duration(),
+                        max_idle_duration: task_creation_context
+                            .max_idle_duration,
                         timeout_duration: service_configuration
                             .timeout_duration(),
                     },
@@ -99,6 +102,8 @@ impl Id {
                                 .gas_per_price_alarm,
                             idle_duration: service_configuration
                                 .idle_duration(),
+                            max_idle_duration: task_creation_context
+                                .max_idle_duration,
                             timeout_duration: service_configuration
                                 .timeout_duration(),
                         },
@@ -106,7 +111,12 @@ impl Id {
                     )
                 },
             )
-            .map(Task::PriceAlarms)
+            .map(|alarms_generator| {
+                Task::PriceAlarms(
+                    alarms_generator,
+                    service_configuration.protocol_readiness().clone(),
+                )
+            })
     }
 }
 
@@ -172,7 +182,7 @@ impl application_defined::Id for Id {
 
 pub enum Task {
     TimeAlarms(AlarmsGenerator<TimeAlarms>),
-    PriceAlarms(AlarmsGenerator<PriceAlarms>),
+    PriceAlarms(AlarmsGenerator<PriceAlarms>, protocol_watcher::Readiness),
 }
 
 impl Runnable for Task {
@@ -181,7 +191,12 @@ impl Runnable for Task {
             Task::TimeAlarms(alarms_generator) => {
                 alarms_generator.run(is_retry).await
             },
-            Task::PriceAlarms(alarms_generator) => {
+            Task::PriceAlarms(alarms_generator, readiness) => {
+                // Price alarms generators don't run healthchecks, so they
+                // are ready for as long as they run.
+                let _ready = readiness
+                    .mark_ready(alarms_generator.alarms().protocol().clone());
+
                 alarms_generator.run(is_retry).await
             },
         }
@@ -196,7 +211,7 @@ impl application_defined::Task for Task {
     fn id(&self) -> Id {
         match self {
             Task::TimeAlarms(_) => Id::TimeAlarmsGenerator,
-            Task::PriceAlarms(alarms) => Id::PriceAlarmsGenerator {
+            Task::PriceAlarms(alarms, _) => Id::PriceAlarmsGenerator {
                 protocol: alarms.alarms().protocol().clone(),
             },
         }
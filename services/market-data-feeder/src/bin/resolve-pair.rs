@@ -0,0 +1,22 @@
+#![forbid(unsafe_code)]
+#![warn(clippy::pedantic)]
+#![allow(clippy::missing_errors_doc)]
+
+use std::env::args;
+
+use anyhow::{bail, Result};
+
+use market_data_feeder::task::print_pair_routing;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let mut args = args().skip(1);
+
+    let (Some(protocol), Some(pair), None) =
+        (args.next(), args.next(), args.next())
+    else {
+        bail!("Usage: resolve-pair <PROTOCOL> <BASE/QUOTE>");
+    };
+
+    print_pair_routing(&protocol, &pair).await
+}
@@ -1,44 +1,64 @@
-use std::{collections::BTreeMap, future::Future, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    num::NonZeroU8,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
 
 use anyhow::{anyhow, Context as _, Result};
 use serde::Deserialize;
-use tokio::time::Instant;
+use tokio::{
+    sync::Mutex as AsyncMutex,
+    time::{sleep, Instant},
+};
 
 use chain_ops::{
     contract::{Compatibility, SemVer},
-    node::{QueryWasm, Reconnect},
+    node::{self, QueryWasm, Reconnect},
 };
 
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "oracle",
+            $($body)+
+        )
+    };
+}
+
 pub struct Oracle {
     query_wasm: QueryWasm,
     address: String,
     last_update: Instant,
     update_interval: Duration,
     currencies: Currencies,
-    currency_pairs: CurrencyPairs,
+    currency_pairs: Arc<CurrencyPairs>,
+    shared_currency_pairs: SharedCurrencyPairs,
+    pair_filter: PairFilter,
+    version_recheck: Option<VersionRecheck>,
 }
 
 impl Oracle {
+    const CONTRACT_VERSION: SemVer = SemVer::new(0, 5, 15);
+
     pub async fn new(
         mut query_wasm: QueryWasm,
         address: String,
         update_interval: Duration,
+        shared_currency_pairs: SharedCurrencyPairs,
+        pair_filter: PairFilter,
+        version_recheck_interval: Option<Duration>,
+        version_query_attempts: NonZeroU8,
     ) -> Result<Self> {
-        const QUERY_MSG: &[u8; 23] = br#"{"contract_version":{}}"#;
+        Self::check_version_with_retry(version_query_attempts, || {
+            let mut query_wasm = query_wasm.clone();
 
-        const CONTRACT_VERSION: SemVer = SemVer::new(0, 5, 15);
+            let address = address.clone();
 
-        query_wasm
-            .smart::<SemVer>(address.clone(), QUERY_MSG.to_vec())
-            .await
-            .and_then(|version| {
-                match version.check_compatibility(CONTRACT_VERSION) {
-                    Compatibility::Compatible => Ok(()),
-                    Compatibility::Incompatible => Err(anyhow!(
-                        "Oracle contract has an incompatible version!",
-                    )),
-                }
-            })?;
+            async move { Self::query_version(&mut query_wasm, address).await }
+        })
+        .await?;
 
         let currencies =
             Self::query_currencies(&mut query_wasm, address.clone())
@@ -47,10 +67,13 @@ impl Oracle {
 
         let last_update = Instant::now();
 
-        let currency_pairs =
-            Self::query_currency_pairs(&mut query_wasm, address.clone())
-                .await
-                .context("Failed to query currency pairs!")?;
+        let currency_pairs = shared_currency_pairs
+            .get_or_fetch(update_interval, || {
+                Self::query_currency_pairs(&mut query_wasm, address.clone())
+            })
+            .await
+            .map(|currency_pairs| pair_filter.apply(currency_pairs))
+            .context("Failed to query currency pairs!")?;
 
         Ok(Self {
             query_wasm,
@@ -59,6 +82,9 @@ impl Oracle {
             update_interval,
             currencies,
             currency_pairs,
+            shared_currency_pairs,
+            pair_filter,
+            version_recheck: version_recheck_interval.map(VersionRecheck::new),
         })
     }
 
@@ -70,15 +96,19 @@ impl Oracle {
 
     #[inline]
     #[must_use]
-    pub const fn currency_pairs(&self) -> &CurrencyPairs {
+    pub fn currency_pairs(&self) -> &CurrencyPairs {
         &self.currency_pairs
     }
 
-    pub async fn update_currencies_and_pairs(&mut self) -> Result<bool> {
-        let update_interval_elapsed =
-            self.last_update.elapsed() > self.update_interval;
-
-        if update_interval_elapsed {
+    /// Re-queries currencies and currency pairs when the update interval
+    /// elapsed.
+    ///
+    /// Returns `None` when no update took place, otherwise returns the
+    /// currencies which were already known but changed their decimal digits.
+    pub async fn update_currencies_and_pairs(
+        &mut self,
+    ) -> Result<Option<Vec<DecimalDigitsChange>>> {
+        if self.last_update.elapsed() > self.update_interval {
             let currencies = Self::query_currencies(
                 &mut self.query_wasm,
                 self.address.clone(),
@@ -87,20 +117,215 @@ impl Oracle {
 
             let last_update = Instant::now();
 
-            let currency_pairs = Self::query_currency_pairs(
+            let currency_pairs = self
+                .shared_currency_pairs
+                .get_or_fetch(self.update_interval, || {
+                    Self::query_currency_pairs(
+                        &mut self.query_wasm,
+                        self.address.clone(),
+                    )
+                })
+                .await?;
+
+            self.last_update = last_update;
+
+            let decimal_digits_changes =
+                self.currencies.decimal_digits_changes(&currencies);
+
+            self.currencies = currencies;
+
+            self.currency_pairs = self.pair_filter.apply(currency_pairs);
+
+            Ok(Some(decimal_digits_changes))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Re-checks the contract's version when the configured recheck interval
+    /// elapsed, so an upgrade to an incompatible version is detected without
+    /// restarting.
+    pub async fn recheck_version(&mut self) -> Result<()> {
+        if self
+            .version_recheck
+            .as_mut()
+            .is_some_and(VersionRecheck::due)
+        {
+            Self::recheck_queried_version(Self::query_version(
                 &mut self.query_wasm,
                 self.address.clone(),
-            )
+            ))
             .await?;
+        }
 
-            self.last_update = last_update;
+        Ok(())
+    }
 
-            self.currencies = currencies;
+    /// Fails only when the reported version is incompatible.
+    ///
+    /// A failed query is logged and skipped, as it doesn't indicate an
+    /// upgrade and the version is queried again on the next recheck.
+    async fn recheck_queried_version<Fut>(query: Fut) -> Result<()>
+    where
+        Fut: Future<Output = Result<SemVer>>,
+    {
+        match query.await {
+            Ok(version) => Self::ensure_compatible(version)
+                .context("Oracle contract's version recheck failed!"),
+            Err(error) => {
+                log!(warn!(
+                    ?error,
+                    "Failed to recheck oracle contract's version! Skipping \
+                    until the next recheck.",
+                ));
 
-            self.currency_pairs = currency_pairs;
+                Ok(())
+            },
         }
+    }
+
+    /// Checks the contract's version, retrying the query with a backoff on
+    /// transient failures, e.g. a node hiccup during startup.
+    ///
+    /// An incompatible version or a missing contract is definitive, thus it
+    /// fails right away.
+    async fn check_version_with_retry<F, Fut>(
+        max_attempts: NonZeroU8,
+        mut query: F,
+    ) -> Result<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<SemVer>>,
+    {
+        let mut backoff = const { Duration::from_secs(1) };
+
+        let mut attempt = 1;
+
+        loop {
+            match query().await {
+                Ok(version) => break Self::ensure_compatible(version),
+                Err(error)
+                    if attempt >= max_attempts.get()
+                        || node::is_contract_not_found(&error) =>
+                {
+                    break Err(error);
+                },
+                Err(error) => {
+                    log!(warn!(
+                        ?error,
+                        attempt,
+                        ?backoff,
+                        "Failed to query oracle contract's version! Retrying.",
+                    ));
+
+                    sleep(backoff).await;
 
-        Ok(update_interval_elapsed)
+                    backoff *= 2;
+
+                    attempt += 1;
+                },
+            }
+        }
+    }
+
+    async fn query_version(
+        query_wasm: &mut QueryWasm,
+        address: String,
+    ) -> Result<SemVer> {
+        const QUERY_MSG: &[u8; 23] = br#"{"contract_version":{}}"#;
+
+        query_wasm
+            .smart::<SemVer>(address, QUERY_MSG.to_vec())
+            .await
+            .context("Failed to query oracle contract's version!")
+    }
+
+    fn ensure_compatible(version: SemVer) -> Result<()> {
+        match version.check_compatibility(Self::CONTRACT_VERSION) {
+            Compatibility::Compatible => Ok(()),
+            Compatibility::Incompatible => Err(anyhow!(
+                "Oracle contract has an incompatible version! \
+                Reported={version:?}",
+            )),
+        }
+    }
+
+    pub async fn query_feeders(&mut self) -> Result<Vec<String>> {
+        const QUERY_MESSAGE: &[u8; 14] = br#"{"feeders":{}}"#;
+
+        self.query_wasm
+            .smart(self.address.clone(), QUERY_MESSAGE.to_vec())
+            .await
+            .context("Failed to query for oracle contract's feeders!")
+    }
+
+    /// Checks whether any of the supported currency pairs' base currencies
+    /// lacks a price, as the contract only reports prices which haven't
+    /// expired yet.
+    pub async fn has_stale_prices(&mut self) -> Result<bool> {
+        self.query_prices().await.map(|prices| {
+            let priced: BTreeSet<String> = prices
+                .into_iter()
+                .map(|price| price.amount.ticker)
+                .collect();
+
+            self.currency_pairs
+                .keys()
+                .any(|(base, _)| !priced.contains(base))
+        })
+    }
+
+    /// Queries the oracle contract's stable currency, along with the prices
+    /// it currently holds, all of which are quoted in it.
+    pub fn query_stable_prices(
+        &self,
+    ) -> impl Future<Output = Result<StablePrices>> + Send + 'static {
+        const QUERY_MESSAGE: &[u8; 22] = br#"{"stable_currency":{}}"#;
+
+        let mut query_wasm = self.query_wasm.clone();
+
+        let address = self.address.clone();
+
+        let query_prices = self.query_prices();
+
+        async move {
+            let stable_currency = query_wasm
+                .smart(address, QUERY_MESSAGE.to_vec())
+                .await
+                .context(
+                    "Failed to query for oracle contract's stable currency!",
+                )?;
+
+            query_prices.await.map(|prices| StablePrices {
+                stable_currency,
+                prices,
+            })
+        }
+    }
+
+    /// Queries the prices currently held by the oracle contract, which only
+    /// include ones which haven't expired yet.
+    pub fn query_prices(
+        &self,
+    ) -> impl Future<Output = Result<Vec<StoredPrice>>> + Send + 'static {
+        #[derive(Deserialize)]
+        struct Prices {
+            prices: Vec<StoredPrice>,
+        }
+
+        const QUERY_MESSAGE: &[u8; 13] = br#"{"prices":{}}"#;
+
+        let mut query_wasm = self.query_wasm.clone();
+
+        let address = self.address.clone();
+
+        async move {
+            query_wasm
+                .smart::<Prices>(address, QUERY_MESSAGE.to_vec())
+                .await
+                .map(|Prices { prices }| prices)
+                .context("Failed to query for oracle contract's prices!")
+        }
     }
 
     async fn query_currencies(
@@ -194,6 +419,54 @@ impl Currencies {
             reported when currencies were queried!"#
         ))
     }
+
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, ticker: &str) -> bool {
+        self.0.contains_key(ticker)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Currency)> + Send + '_ {
+        self.0
+            .iter()
+            .map(|(ticker, currency)| (ticker.as_str(), currency))
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Lists currencies present in both sets whose decimal digits differ.
+    #[must_use]
+    pub fn decimal_digits_changes(
+        &self,
+        updated: &Self,
+    ) -> Vec<DecimalDigitsChange> {
+        self.iter()
+            .filter_map(|(ticker, currency)| {
+                updated
+                    .0
+                    .get(ticker)
+                    .filter(|updated| {
+                        updated.decimal_digits != currency.decimal_digits
+                    })
+                    .map(|updated| DecimalDigitsChange {
+                        ticker: ticker.into(),
+                        previous: currency.decimal_digits,
+                        current: updated.decimal_digits,
+                    })
+            })
+            .collect()
+    }
 }
 
 pub struct Currency {
@@ -201,6 +474,33 @@ pub struct Currency {
     pub decimal_digits: u8,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct DecimalDigitsChange {
+    pub ticker: String,
+    pub previous: u8,
+    pub current: u8,
+}
+
+/// Price held by the oracle contract.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StoredPrice {
+    pub amount: StoredAmount,
+    pub amount_quote: StoredAmount,
+}
+
+/// Prices held by the oracle contract, quoted in its stable currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StablePrices {
+    pub stable_currency: String,
+    pub prices: Vec<StoredPrice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct StoredAmount {
+    pub amount: String,
+    pub ticker: String,
+}
+
 #[repr(transparent)]
 pub struct CurrencyPairs(BTreeMap<(String, String), PoolId>);
 
@@ -216,6 +516,542 @@ impl CurrencyPairs {
     pub fn keys(&self) -> impl Iterator<Item = &(String, String)> + Send + '_ {
         self.0.keys()
     }
+
+    #[inline]
+    #[must_use]
+    pub fn get(&self, pair: &(String, String)) -> Option<&PoolId> {
+        self.0.get(pair)
+    }
+
+    /// Derives the subset of pairs for which the predicate holds, e.g. the
+    /// ones a given provider is able to price.
+    pub fn filter<F>(&self, mut predicate: F) -> Self
+    where
+        F: FnMut(&(String, String), &PoolId) -> bool,
+    {
+        Self(
+            self.0
+                .iter()
+                .filter(|&(pair, pool_id)| predicate(pair, pool_id))
+                .map(|(pair, &pool_id)| (pair.clone(), pool_id))
+                .collect(),
+        )
+    }
+}
+
+impl FromIterator<((String, String), PoolId)> for CurrencyPairs {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = ((String, String), PoolId)>,
+    {
+        Self(iter.into_iter().collect())
+    }
 }
 
 pub type PoolId = u64;
+
+/// Restricts the currency pairs fed by a provider, e.g. during incident
+/// response.
+///
+/// Pairs are written as `BASE/QUOTE` and separated by commas. When an allow
+/// list is set, only the listed pairs are kept, while listed denied pairs are
+/// always removed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct PairFilter {
+    allow: Option<BTreeSet<(String, String)>>,
+    deny: BTreeSet<(String, String)>,
+}
+
+impl PairFilter {
+    pub fn new(allow: Option<&str>, deny: Option<&str>) -> Result<Self> {
+        Ok(Self {
+            allow: allow
+                .map(Self::parse)
+                .transpose()
+                .context("Failed to parse allowed currency pairs!")?,
+            deny: deny
+                .map(Self::parse)
+                .transpose()
+                .context("Failed to parse denied currency pairs!")?
+                .unwrap_or_default(),
+        })
+    }
+
+    #[must_use]
+    pub fn is_unrestricted(&self) -> bool {
+        self.allow.is_none() && self.deny.is_empty()
+    }
+
+    #[must_use]
+    pub fn allows(&self, pair: &(String, String)) -> bool {
+        self.allow
+            .as_ref()
+            .map_or(true, |allow| allow.contains(pair))
+            && !self.deny.contains(pair)
+    }
+
+    fn apply(&self, currency_pairs: Arc<CurrencyPairs>) -> Arc<CurrencyPairs> {
+        if self.is_unrestricted() {
+            currency_pairs
+        } else {
+            Arc::new(currency_pairs.filter(|pair, _| self.allows(pair)))
+        }
+    }
+
+    fn parse(pairs: &str) -> Result<BTreeSet<(String, String)>> {
+        pairs
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                pair.split_once('/')
+                    .map(|(base, quote)| {
+                        (base.trim().into(), quote.trim().into())
+                    })
+                    .filter(|(base, quote): &(String, String)| {
+                        !base.is_empty() && !quote.is_empty()
+                    })
+                    .with_context(|| {
+                        format!(
+                            r#"Currency pair "{pair}" isn't in the "BASE/QUOTE" format!"#
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Shares oracles' supported currency pairs between the providers feeding
+/// the same protocol, so their refreshes within the time-to-live result in a
+/// single query per oracle address.
+#[derive(Clone, Default)]
+#[must_use]
+pub struct CurrencyPairsCache {
+    oracles: Arc<Mutex<BTreeMap<String, SharedCurrencyPairs>>>,
+}
+
+impl CurrencyPairsCache {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn for_oracle(&self, address: &str) -> SharedCurrencyPairs {
+        self.oracles
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(address.into())
+            .or_default()
+            .clone()
+    }
+}
+
+/// Supported currency pairs of a single oracle, shared between its
+/// consumers.
+#[derive(Clone, Default)]
+#[must_use]
+pub struct SharedCurrencyPairs(Arc<AsyncMutex<Option<CachedCurrencyPairs>>>);
+
+impl SharedCurrencyPairs {
+    /// Returns the cached currency pairs when they were fetched within the
+    /// time-to-live, otherwise fetches and caches them.
+    ///
+    /// The lock is held while fetching, so concurrent consumers wait for the
+    /// in-flight query instead of issuing their own.
+    async fn get_or_fetch<F, Fut>(
+        &self,
+        ttl: Duration,
+        fetch: F,
+    ) -> Result<Arc<CurrencyPairs>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<CurrencyPairs>>,
+    {
+        let mut cached = self.0.lock().await;
+
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| cached.fetched_at.elapsed() < ttl)
+        {
+            return Ok(cached.currency_pairs.clone());
+        }
+
+        let currency_pairs = Arc::new(fetch().await?);
+
+        *cached = Some(CachedCurrencyPairs {
+            fetched_at: Instant::now(),
+            currency_pairs: currency_pairs.clone(),
+        });
+
+        Ok(currency_pairs)
+    }
+}
+
+struct VersionRecheck {
+    interval: Duration,
+    last_check: Instant,
+}
+
+impl VersionRecheck {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_check: Instant::now(),
+        }
+    }
+
+    fn due(&mut self) -> bool {
+        let due = self.last_check.elapsed() >= self.interval;
+
+        if due {
+            self.last_check = Instant::now();
+        }
+
+        due
+    }
+}
+
+struct CachedCurrencyPairs {
+    fetched_at: Instant,
+    currency_pairs: Arc<CurrencyPairs>,
+}
+
+#[cfg(test)]
+impl Oracle {
+    /// Oracle supporting the given currency pairs, which never updates them
+    /// nor rechecks its version, thus issuing no queries on its own.
+    pub(crate) fn stub(
+        query_wasm: QueryWasm,
+        currency_pairs: &[(&str, &str)],
+    ) -> Self {
+        Self {
+            query_wasm,
+            address: "nolus1oracle".into(),
+            last_update: Instant::now(),
+            update_interval: Duration::MAX,
+            currencies: Currencies(BTreeMap::new()),
+            currency_pairs: Arc::new(CurrencyPairs(
+                (0..)
+                    .zip(currency_pairs)
+                    .map(|(pool_id, &(base, quote))| {
+                        ((base.into(), quote.into()), pool_id)
+                    })
+                    .collect(),
+            )),
+            shared_currency_pairs: SharedCurrencyPairs::default(),
+            pair_filter: PairFilter::default(),
+            version_recheck: None,
+        }
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrent_consumers_share_query() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tokio::time::{advance, sleep};
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    let queries = AtomicUsize::new(0);
+
+    let query = || async {
+        _ = queries.fetch_add(1, Ordering::AcqRel);
+
+        sleep(Duration::from_secs(1)).await;
+
+        anyhow::Ok(CurrencyPairs(BTreeMap::from([(
+            ("NLS".into(), "USDC".into()),
+            1,
+        )])))
+    };
+
+    let cache = CurrencyPairsCache::new();
+
+    let (first, second) = (
+        cache.for_oracle("nolus1oracle"),
+        cache.for_oracle("nolus1oracle"),
+    );
+
+    let (first_pairs, second_pairs) = tokio::join!(
+        first.get_or_fetch(TTL, query),
+        second.get_or_fetch(TTL, query),
+    );
+
+    assert_eq!(queries.load(Ordering::Acquire), 1);
+
+    assert!(Arc::ptr_eq(&first_pairs.unwrap(), &second_pairs.unwrap()));
+
+    _ = cache
+        .for_oracle("nolus1other")
+        .get_or_fetch(TTL, query)
+        .await
+        .unwrap();
+
+    assert_eq!(queries.load(Ordering::Acquire), 2);
+
+    advance(TTL).await;
+
+    _ = first.get_or_fetch(TTL, query).await.unwrap();
+
+    assert_eq!(queries.load(Ordering::Acquire), 3);
+}
+
+#[test]
+fn test_currencies_lookup_and_iteration() {
+    let currencies = Currencies(BTreeMap::from([
+        (
+            "USDC".into(),
+            Currency {
+                dex_symbol: "ibc/USDC".into(),
+                decimal_digits: 6,
+            },
+        ),
+        (
+            "NLS".into(),
+            Currency {
+                dex_symbol: "unls".into(),
+                decimal_digits: 6,
+            },
+        ),
+    ]));
+
+    assert!(currencies.contains("NLS"));
+
+    assert!(!currencies.contains("ATOM"));
+
+    assert_eq!(
+        currencies
+            .iter()
+            .map(|(ticker, currency)| (ticker, currency.dex_symbol.as_str()))
+            .collect::<Vec<_>>(),
+        [("NLS", "unls"), ("USDC", "ibc/USDC")],
+    );
+
+    assert_eq!(currencies.len(), 2);
+
+    assert!(!currencies.is_empty());
+
+    let empty = Currencies(BTreeMap::new());
+
+    assert_eq!(empty.len(), 0);
+
+    assert!(empty.is_empty());
+
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+fn test_currency_decimal_digits_change_detected() {
+    fn currencies(entries: &[(&str, u8)]) -> Currencies {
+        Currencies(
+            entries
+                .iter()
+                .map(|&(ticker, decimal_digits)| {
+                    (
+                        ticker.into(),
+                        Currency {
+                            dex_symbol: ticker.to_lowercase(),
+                            decimal_digits,
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    let previous = currencies(&[("NLS", 6), ("USDC", 6), ("WETH", 18)]);
+
+    let updated =
+        currencies(&[("ATOM", 6), ("NLS", 6), ("USDC", 8), ("WETH", 18)]);
+
+    assert_eq!(
+        previous.decimal_digits_changes(&updated),
+        [DecimalDigitsChange {
+            ticker: "USDC".into(),
+            previous: 6,
+            current: 8,
+        }],
+    );
+
+    assert!(updated.decimal_digits_changes(&updated).is_empty());
+
+    assert!(previous
+        .decimal_digits_changes(&currencies(&[("NLS", 6)]))
+        .is_empty());
+}
+
+#[test]
+fn test_currency_pairs_subset_and_lookup() {
+    let pair = |base: &str, quote: &str| (base.to_string(), quote.to_string());
+
+    let currency_pairs = CurrencyPairs(BTreeMap::from([
+        (pair("ATOM", "USDC"), 1),
+        (pair("NLS", "USDC"), 7),
+        (pair("OSMO", "ATOM"), 12),
+    ]));
+
+    assert_eq!(currency_pairs.get(&pair("NLS", "USDC")), Some(&7));
+
+    assert_eq!(currency_pairs.get(&pair("USDC", "NLS")), None);
+
+    let usdc_quoted =
+        currency_pairs.filter(|(_, quote), _| quote.as_str() == "USDC");
+
+    assert_eq!(
+        usdc_quoted.iter().collect::<Vec<_>>(),
+        [(&pair("ATOM", "USDC"), &1), (&pair("NLS", "USDC"), &7)],
+    );
+
+    assert_eq!(usdc_quoted.get(&pair("OSMO", "ATOM")), None);
+
+    assert_eq!(currency_pairs.filter(|_, _| false).keys().count(), 0);
+
+    assert_eq!(currency_pairs.keys().count(), 3);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_incompatible_version_bump_detected_on_recheck() {
+    use tokio::time::advance;
+
+    let mut version_recheck = VersionRecheck::new(Duration::from_secs(300));
+
+    let mut reported_versions = [
+        Ok(SemVer::new(0, 5, 16)),
+        Err(anyhow!("Transport error!")),
+        Ok(SemVer::new(0, 6, 0)),
+        Ok(SemVer::new(0, 6, 1)),
+    ]
+    .into_iter();
+
+    let mut rechecks = 0;
+
+    let result = loop {
+        advance(Duration::from_secs(60)).await;
+
+        if !version_recheck.due() {
+            continue;
+        }
+
+        let Some(reported_version) = reported_versions.next() else {
+            break Ok(());
+        };
+
+        rechecks += 1;
+
+        if let Err(error) =
+            Oracle::recheck_queried_version(async { reported_version }).await
+        {
+            break Err(error);
+        }
+    };
+
+    assert_eq!(rechecks, 3);
+
+    assert!(result.is_err());
+
+    assert!(Oracle::ensure_compatible(Oracle::CONTRACT_VERSION).is_ok());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_version_query_retries() {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    let max_attempts = NonZeroU8::new(3).unwrap();
+
+    let attempts = AtomicU8::new(0);
+
+    let started_at = Instant::now();
+
+    Oracle::check_version_with_retry(max_attempts, || {
+        let attempt = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async move {
+            if attempt == 0 {
+                Err(anyhow!("Transport error!"))
+            } else {
+                Ok(Oracle::CONTRACT_VERSION)
+            }
+        }
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(attempts.load(Ordering::Acquire), 2);
+
+    assert_eq!(started_at.elapsed(), Duration::from_secs(1));
+
+    attempts.store(0, Ordering::Release);
+
+    let started_at = Instant::now();
+
+    let error = Oracle::check_version_with_retry(max_attempts, || {
+        _ = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async { Ok(SemVer::new(0, 4, 0)) }
+    })
+    .await
+    .unwrap_err();
+
+    assert_eq!(attempts.load(Ordering::Acquire), 1);
+
+    assert_eq!(started_at.elapsed(), Duration::ZERO);
+
+    assert!(error.to_string().contains("incompatible version"));
+
+    attempts.store(0, Ordering::Release);
+
+    assert!(Oracle::check_version_with_retry(max_attempts, || {
+        _ = attempts.fetch_add(1, Ordering::AcqRel);
+
+        async { Err(anyhow!("Transport error!")) }
+    })
+    .await
+    .is_err());
+
+    assert_eq!(attempts.load(Ordering::Acquire), 3);
+}
+
+#[test]
+fn test_pair_filter() {
+    let pair = |base: &str, quote: &str| (base.to_string(), quote.to_string());
+
+    let currency_pairs = Arc::new(CurrencyPairs(BTreeMap::from([
+        (pair("ATOM", "USDC"), 1),
+        (pair("NLS", "USDC"), 7),
+        (pair("OSMO", "ATOM"), 12),
+    ])));
+
+    let filtered = |allow, deny| {
+        PairFilter::new(allow, deny)
+            .unwrap()
+            .apply(currency_pairs.clone())
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(filtered(None, None).len(), 3);
+
+    assert_eq!(
+        filtered(Some("NLS/USDC, OSMO/ATOM, AKT/USDC"), None),
+        [pair("NLS", "USDC"), pair("OSMO", "ATOM")],
+    );
+
+    assert_eq!(
+        filtered(None, Some("ATOM/USDC")),
+        [pair("NLS", "USDC"), pair("OSMO", "ATOM")],
+    );
+
+    assert_eq!(
+        filtered(Some("NLS/USDC,OSMO/ATOM"), Some("OSMO/ATOM")),
+        [pair("NLS", "USDC")],
+    );
+
+    assert_eq!(filtered(Some(""), None), []);
+
+    assert!(PairFilter::new(Some("NLS-USDC"), None).is_err());
+
+    assert!(PairFilter::new(None, Some("NLS/")).is_err());
+}
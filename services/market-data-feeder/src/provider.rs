@@ -22,8 +22,21 @@ pub trait Provider: Send + Sized {
     fn price_query(
         &self,
         dex_node_client: &node::Client,
+        currency_pair: &CurrencyPair,
         query_message: &Self::PriceQueryMessage,
-    ) -> impl Future<Output = Result<(Amount<Base>, Amount<Quote>)>> + Send + 'static;
+    ) -> impl Future<Output = Result<PriceQueryResult>> + Send + 'static;
+}
+
+/// Price returned by a provider, along with the pair it was queried for and
+/// the unprocessed value reported by the DEX, so a mis-priced pair can be
+/// traced back to its source.
+#[must_use]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceQueryResult {
+    pub pair: CurrencyPair,
+    pub base: Amount<Base>,
+    pub quote: Amount<Quote>,
+    pub raw_dex_value: String,
 }
 
 #[must_use]
@@ -6,7 +6,9 @@ use chain_ops::node;
 
 use crate::{
     oracle::Oracle,
-    provider::{Amount, Base, CurrencyPair, Decimal, Provider, Quote},
+    provider::{
+        Amount, Base, CurrencyPair, Decimal, PriceQueryResult, Provider,
+    },
 };
 
 use super::{
@@ -92,17 +94,19 @@ impl Provider for Astroport {
     fn price_query(
         &self,
         dex_node_client: &node::Client,
+        currency_pair: &CurrencyPair,
         &PriceQueryMessage {
             ref base_amount,
             quote_decimal_places,
             ref message,
         }: &Self::PriceQueryMessage,
-    ) -> impl Future<Output = Result<(Amount<Base>, Amount<Quote>)>> + Send + 'static
-    {
+    ) -> impl Future<Output = Result<PriceQueryResult>> + Send + 'static {
         let mut query_wasm = dex_node_client.clone().query_wasm();
 
         let router_address = self.router_address.clone();
 
+        let pair = currency_pair.clone();
+
         let base_amount = base_amount.clone();
 
         let message = message.clone();
@@ -112,10 +116,15 @@ impl Provider for Astroport {
                 .smart(router_address, message)
                 .await
                 .map(|SimulateSwapOperationsResponse { amount }| {
-                    (
-                        base_amount,
-                        Amount::new(Decimal::new(amount, quote_decimal_places)),
-                    )
+                    PriceQueryResult {
+                        pair,
+                        base: base_amount,
+                        quote: Amount::new(Decimal::new(
+                            amount.clone(),
+                            quote_decimal_places,
+                        )),
+                        raw_dex_value: amount,
+                    }
                 })
                 .context("Failed to query price from router contract!")
         }
@@ -1,11 +1,14 @@
-use std::sync::LazyLock;
+use std::{sync::LazyLock, time::Duration};
 
 use prost::Message;
 use tonic::codegen::http::uri::PathAndQuery;
 
+use self::pool_selection::SelectedPools;
+pub use self::pool_selection::PoolCandidates;
 #[cfg(test)]
 pub(crate) use self::sealed::{greater_than_max_quote_value, MAX_QUOTE_VALUE};
 
+mod pool_selection;
 mod sealed;
 #[cfg(test)]
 mod test;
@@ -13,18 +16,37 @@ mod test;
 #[must_use]
 pub struct Osmosis {
     path_and_query: &'static PathAndQuery,
+    liquidity_path_and_query: &'static PathAndQuery,
+    pool_candidates: PoolCandidates,
+    selected_pools: SelectedPools,
 }
 
 impl Osmosis {
-    pub fn new() -> Self {
+    pub const DEFAULT_POOL_SELECTION_REFRESH_INTERVAL: Duration =
+        SelectedPools::DEFAULT_REFRESH_INTERVAL;
+
+    pub fn new(
+        pool_candidates: PoolCandidates,
+        pool_selection_refresh_interval: Duration,
+    ) -> Self {
         static SINGLETON: LazyLock<PathAndQuery> = LazyLock::new(|| {
             PathAndQuery::from_static(
                 "/osmosis.poolmanager.v2.Query/SpotPriceV2",
             )
         });
 
+        static LIQUIDITY_SINGLETON: LazyLock<PathAndQuery> =
+            LazyLock::new(|| {
+                PathAndQuery::from_static(
+                    "/osmosis.poolmanager.v1beta1.Query/TotalPoolLiquidity",
+                )
+            });
+
         Self {
             path_and_query: &SINGLETON,
+            liquidity_path_and_query: &LIQUIDITY_SINGLETON,
+            pool_candidates,
+            selected_pools: SelectedPools::new(pool_selection_refresh_interval),
         }
     }
 }
@@ -32,7 +54,10 @@ impl Osmosis {
 impl Default for Osmosis {
     #[inline]
     fn default() -> Self {
-        Self::new()
+        Self::new(
+            PoolCandidates::default(),
+            Self::DEFAULT_POOL_SELECTION_REFRESH_INTERVAL,
+        )
     }
 }
 
@@ -51,3 +76,23 @@ pub struct SpotPriceResponse {
     #[prost(string, tag = "1")]
     pub spot_price: String,
 }
+
+#[derive(Clone, Message)]
+pub struct TotalPoolLiquidityRequest {
+    #[prost(uint64, tag = "1")]
+    pub pool_id: u64,
+}
+
+#[derive(Message)]
+pub struct TotalPoolLiquidityResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub liquidity: Vec<PoolCoin>,
+}
+
+#[derive(Clone, Message)]
+pub struct PoolCoin {
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
@@ -0,0 +1,326 @@
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+use tokio::time::Instant;
+
+use crate::provider::CurrencyPair;
+
+use super::TotalPoolLiquidityResponse;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "pool-selection",
+            $($body)+
+        )
+    };
+}
+
+/// Additional pools of specific currency pairs, which are weighed against the
+/// pool configured in the oracle, pricing the pair through the one holding
+/// the most of the quote currency, as a shallow pool's price is cheaper to
+/// manipulate.
+///
+/// Entries are written as `BASE/QUOTE:POOL_ID|POOL_ID` and separated by
+/// commas.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct PoolCandidates(BTreeMap<(String, String), Box<[u64]>>);
+
+impl PoolCandidates {
+    pub fn parse(candidates: &str) -> Result<Self> {
+        candidates
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                Self::parse_entry(entry).with_context(|| {
+                    format!(r#"Invalid pool candidates entry, "{entry}"!"#)
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// Returns the pools to weigh when pricing the pair, with the configured
+    /// one first, or none when only the configured one is used.
+    #[must_use]
+    pub(super) fn for_pair(
+        &self,
+        base: &str,
+        quote: &str,
+        configured_pool_id: u64,
+    ) -> Box<[u64]> {
+        self.0
+            .get(&(base.into(), quote.into()))
+            .map(|candidates| {
+                let mut pool_ids = vec![configured_pool_id];
+
+                pool_ids.extend(
+                    candidates
+                        .iter()
+                        .filter(|&&pool_id| pool_id != configured_pool_id),
+                );
+
+                pool_ids.into_boxed_slice()
+            })
+            .unwrap_or_default()
+    }
+
+    fn parse_entry(entry: &str) -> Result<((String, String), Box<[u64]>)> {
+        let (pair, pool_ids) = entry
+            .split_once(':')
+            .context("Candidate pools are missing!")?;
+
+        let (base, quote) = pair
+            .split_once('/')
+            .map(|(base, quote)| (base.trim(), quote.trim()))
+            .filter(|(base, quote)| !base.is_empty() && !quote.is_empty())
+            .context(r#"Currency pair isn't in the "BASE/QUOTE" format!"#)?;
+
+        let pool_ids = pool_ids
+            .split('|')
+            .map(|pool_id| {
+                pool_id.trim().parse().with_context(|| {
+                    format!(r#"Invalid pool identifier, "{pool_id}"!"#)
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(((base.into(), quote.into()), pool_ids))
+    }
+}
+
+/// Pools selected for each currency pair, kept for the refresh interval so
+/// the candidates' liquidity isn't queried on every tick.
+#[derive(Debug, Clone)]
+#[must_use]
+pub(super) struct SelectedPools {
+    refresh_interval: Duration,
+    selected: Arc<Mutex<BTreeMap<CurrencyPair, (u64, Instant)>>>,
+}
+
+impl SelectedPools {
+    pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+    pub fn new(refresh_interval: Duration) -> Self {
+        Self {
+            refresh_interval,
+            selected: Arc::default(),
+        }
+    }
+
+    /// Returns the pool selected for the pair, selecting it anew through
+    /// `select_deepest` once the previous selection is older than the
+    /// refresh interval.
+    ///
+    /// When the selection fails, the pool configured in the oracle is used
+    /// and the selection is retried on the next query.
+    pub async fn select<F, Fut>(
+        &self,
+        pair: &CurrencyPair,
+        configured_pool_id: u64,
+        select_deepest: F,
+    ) -> u64
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<u64>>,
+    {
+        if let Some(&(pool_id, _)) =
+            self.lock().get(pair).filter(|(_, selected_at)| {
+                selected_at.elapsed() < self.refresh_interval
+            })
+        {
+            return pool_id;
+        }
+
+        match select_deepest().await {
+            Ok(pool_id) => {
+                self.lock().insert(pair.clone(), (pool_id, Instant::now()));
+
+                pool_id
+            },
+            Err(error) => {
+                log!(warn!(
+                    base = %pair.base,
+                    quote = %pair.quote,
+                    configured_pool_id,
+                    ?error,
+                    "Failed to select deepest candidate pool! Falling back to \
+                    the configured pool.",
+                ));
+
+                configured_pool_id
+            },
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<CurrencyPair, (u64, Instant)>> {
+        self.selected.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the amount of the quote denomination held by the pool, or none
+/// when the pool doesn't hold both the base and the quote denominations, as
+/// it can't price the pair then.
+pub(super) fn depth(
+    liquidity: &TotalPoolLiquidityResponse,
+    base_denom: &str,
+    quote_denom: &str,
+) -> Result<Option<u128>> {
+    if !liquidity
+        .liquidity
+        .iter()
+        .any(|coin| coin.denom == base_denom)
+    {
+        return Ok(None);
+    }
+
+    liquidity
+        .liquidity
+        .iter()
+        .filter(|coin| coin.denom == quote_denom)
+        .try_fold(None, |depth: Option<u128>, coin| {
+            coin.amount
+                .parse::<u128>()
+                .context("Failed to parse pool's liquidity amount!")
+                .and_then(|amount| {
+                    depth
+                        .unwrap_or(0)
+                        .checked_add(amount)
+                        .context("Pool's liquidity overflowed!")
+                })
+                .map(Some)
+        })
+}
+
+/// Selects the pool with the greatest depth, preferring the earlier one on a
+/// tie, so the configured pool is kept unless another one is deeper.
+pub(super) fn deepest<I>(depths: I) -> Option<u64>
+where
+    I: IntoIterator<Item = (u64, u128)>,
+{
+    depths
+        .into_iter()
+        .fold(None, |deepest, (pool_id, depth)| match deepest {
+            Some((_, deepest_depth)) if deepest_depth >= depth => deepest,
+            _ => Some((pool_id, depth)),
+        })
+        .map(|(pool_id, _)| pool_id)
+}
+
+#[test]
+fn test_deeper_pool_selected() {
+    use super::PoolCoin;
+
+    fn liquidity(quote_amount: &str) -> TotalPoolLiquidityResponse {
+        TotalPoolLiquidityResponse {
+            liquidity: vec![
+                PoolCoin {
+                    denom: "uosmo".into(),
+                    amount: "900000000000".into(),
+                },
+                PoolCoin {
+                    denom: "uusdc".into(),
+                    amount: quote_amount.into(),
+                },
+            ],
+        }
+    }
+
+    let candidates = PoolCandidates::parse("OSMO/USDC:1464|1263").unwrap();
+
+    let pool_ids = candidates.for_pair("OSMO", "USDC", 678);
+
+    assert_eq!(*pool_ids, [678, 1464, 1263]);
+
+    let depths = [
+        (678, liquidity("2500000000")),
+        (1464, liquidity("97000000000")),
+        (1263, liquidity("3100000000")),
+    ]
+    .map(|(pool_id, liquidity)| {
+        (
+            pool_id,
+            depth(&liquidity, "uosmo", "uusdc").unwrap().unwrap(),
+        )
+    });
+
+    assert_eq!(deepest(depths), Some(1464));
+
+    assert_eq!(
+        depth(
+            &TotalPoolLiquidityResponse {
+                liquidity: vec![PoolCoin {
+                    denom: "uusdc".into(),
+                    amount: "97000000000".into(),
+                }],
+            },
+            "uosmo",
+            "uusdc",
+        )
+        .unwrap(),
+        None,
+    );
+
+    assert_eq!(
+        depth(&liquidity("97000000000"), "uosmo", "uatom").unwrap(),
+        None
+    );
+
+    assert_eq!(deepest([(678, 5_000_000), (1464, 5_000_000)]), Some(678));
+
+    assert!(candidates.for_pair("ATOM", "USDC", 1).is_empty());
+
+    assert!(PoolCandidates::parse("OSMO/USDC:1464|pool").is_err());
+
+    assert!(PoolCandidates::parse("OSMO/USDC").is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_pool_selection_cached_and_falls_back() {
+    use anyhow::anyhow;
+    use tokio::time::sleep;
+
+    let selected_pools = SelectedPools::new(Duration::from_secs(60));
+
+    let pair = CurrencyPair {
+        base: "OSMO".into(),
+        quote: "USDC".into(),
+    };
+
+    assert_eq!(
+        selected_pools
+            .select(&pair, 678, || async { Err(anyhow!("Query failed!")) })
+            .await,
+        678,
+    );
+
+    assert_eq!(
+        selected_pools
+            .select(&pair, 678, || async { Ok(1464) })
+            .await,
+        1464,
+    );
+
+    assert_eq!(
+        selected_pools
+            .select(&pair, 678, || async { unreachable!() })
+            .await,
+        1464,
+    );
+
+    sleep(Duration::from_secs(60)).await;
+
+    assert_eq!(
+        selected_pools
+            .select(&pair, 678, || async { Ok(1263) })
+            .await,
+        1263,
+    );
+}
@@ -1,16 +1,21 @@
 use std::{cmp, collections::BTreeMap, future::Future};
 
 use anyhow::{bail, Context as _, Result};
-use tracing::debug;
 
 use chain_ops::node;
+use tonic::codegen::http::uri::PathAndQuery;
 
 use crate::{
     oracle::Oracle,
-    provider::{Amount, Base, CurrencyPair, Decimal, Provider, Quote},
+    provider::{
+        Amount, Base, CurrencyPair, Decimal, PriceQueryResult, Provider, Quote,
+    },
 };
 
-use super::{Osmosis, SpotPriceRequest, SpotPriceResponse};
+use super::{
+    pool_selection, Osmosis, SpotPriceRequest, SpotPriceResponse,
+    TotalPoolLiquidityRequest, TotalPoolLiquidityResponse,
+};
 
 impl Osmosis {
     pub(super) fn normalized_price(
@@ -101,6 +106,62 @@ impl Osmosis {
                 )
             })
     }
+
+    /// Queries each candidate pool's liquidity and returns the one holding
+    /// the most of the quote currency, out of the ones holding both the base
+    /// and the quote currencies.
+    async fn deepest_pool(
+        query_raw: &mut node::QueryRaw,
+        path_and_query: &PathAndQuery,
+        candidate_pool_ids: &[u64],
+        base_denom: &str,
+        quote_denom: &str,
+    ) -> Result<u64> {
+        let mut depths = Vec::with_capacity(candidate_pool_ids.len());
+
+        for &pool_id in candidate_pool_ids {
+            let liquidity = query_raw
+                .raw::<_, TotalPoolLiquidityResponse>(
+                    TotalPoolLiquidityRequest { pool_id },
+                    path_and_query.clone(),
+                )
+                .await
+                .with_context(|| {
+                    format!("Failed to query pool's liquidity! Pool={pool_id}")
+                })?;
+
+            if let Some(depth) =
+                pool_selection::depth(&liquidity, base_denom, quote_denom)
+                    .with_context(|| {
+                        format!("Failed to read pool's depth! Pool={pool_id}")
+                    })?
+            {
+                depths.push((pool_id, depth));
+            }
+        }
+
+        pool_selection::deepest(depths)
+            .context("No candidate pool holds both currencies of the pair!")
+    }
+
+    fn price_query_result(
+        pair: CurrencyPair,
+        spot_price: String,
+        base_decimal_digits: u8,
+        quote_decimal_digits: u8,
+    ) -> Result<PriceQueryResult> {
+        Self::normalized_price(
+            &spot_price,
+            base_decimal_digits,
+            quote_decimal_digits,
+        )
+        .map(|(base, quote)| PriceQueryResult {
+            pair,
+            base,
+            quote,
+            raw_dex_value: spot_price,
+        })
+    }
 }
 
 impl Provider for Osmosis {
@@ -133,6 +194,13 @@ impl Provider for Osmosis {
                                 },
                                 base_decimal_digits: base.decimal_digits,
                                 quote_decimal_digits: quote.decimal_digits,
+                                candidate_pool_ids: self
+                                    .pool_candidates
+                                    .for_pair(
+                                        base_ticker,
+                                        quote_ticker,
+                                        pool_id,
+                                    ),
                             },
                         )
                     })
@@ -144,26 +212,44 @@ impl Provider for Osmosis {
     fn price_query(
         &self,
         dex_node_client: &node::Client,
+        currency_pair: &CurrencyPair,
         query_message: &Self::PriceQueryMessage,
-    ) -> impl Future<Output = Result<(Amount<Base>, Amount<Quote>)>> + Send + 'static
-    {
+    ) -> impl Future<Output = Result<PriceQueryResult>> + Send + 'static {
         let mut query_raw = dex_node_client.clone().query_raw();
 
+        let pair = currency_pair.clone();
+
         let &Self::PriceQueryMessage {
             ref request,
             base_decimal_digits,
             quote_decimal_digits,
+            ref candidate_pool_ids,
         } = query_message;
 
-        let request = request.clone();
+        let mut request = request.clone();
+
+        let candidate_pool_ids = candidate_pool_ids.clone();
 
         let path_and_query = self.path_and_query.clone();
 
+        let liquidity_path_and_query = self.liquidity_path_and_query;
+
+        let selected_pools = self.selected_pools.clone();
+
         async move {
-            let (base, quote) = (
-                request.base_asset_denom.clone(),
-                request.quote_asset_denom.clone(),
-            );
+            if !candidate_pool_ids.is_empty() {
+                request.pool_id = selected_pools
+                    .select(&pair, request.pool_id, || {
+                        Self::deepest_pool(
+                            &mut query_raw,
+                            liquidity_path_and_query,
+                            &candidate_pool_ids,
+                            &request.base_asset_denom,
+                            &request.quote_asset_denom,
+                        )
+                    })
+                    .await;
+            }
 
             let spot_price = query_raw
                 .raw::<_, SpotPriceResponse>(request, path_and_query)
@@ -173,10 +259,9 @@ impl Provider for Osmosis {
                 )?
                 .spot_price;
 
-            debug!(base, quote, "Unprocessed price: {spot_price}");
-
-            Self::normalized_price(
-                &spot_price,
+            Self::price_query_result(
+                pair,
+                spot_price,
                 base_decimal_digits,
                 quote_decimal_digits,
             )
@@ -192,6 +277,7 @@ pub struct QueryMessage {
     request: SpotPriceRequest,
     base_decimal_digits: u8,
     quote_decimal_digits: u8,
+    candidate_pool_ids: Box<[u64]>,
 }
 
 pub(crate) const MAX_QUOTE_VALUE: &str = {
@@ -311,3 +397,27 @@ fn max_quote_value_assertions() {
         cmp::Ordering::Greater
     );
 }
+
+#[test]
+fn test_price_query_result_fields() {
+    let pair = CurrencyPair {
+        base: "NLS".into(),
+        quote: "USDC".into(),
+    };
+
+    let result = Osmosis::price_query_result(
+        pair.clone(),
+        "12345000000000000000000000000000000".into(),
+        6,
+        6,
+    )
+    .unwrap();
+
+    assert_eq!(result.pair, pair);
+
+    assert_eq!(result.raw_dex_value, "12345000000000000000000000000000000");
+
+    assert_eq!(result.base.into_inner(), Decimal::new("1000000".into(), 6));
+
+    assert_eq!(result.quote.into_inner(), Decimal::new("12345".into(), 6));
+}
@@ -1,24 +1,76 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    num::{NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize},
+    time::Duration,
+};
 
-use anyhow::{Context as _, Result};
+use anyhow::{bail, Context as _, Result};
 use cosmrs::Gas;
 
-use chain_ops::{env::ReadFromVar, node};
+use chain_ops::{enforcement::Enforcement, env::ReadFromVar};
+
+use crate::oracle::CurrencyPairsCache;
+
+use super::{
+    dex_node_clients::DexNodeClients, feed_dedup::FeedDedup,
+    feed_verification::FeedVerification, feeder_rotation::FeederRotation,
+    poll_limit::PollLimit, price_cache::PriceCache,
+};
 
 pub struct ApplicationDefined {
-    pub(super) dex_node_clients: BTreeMap<String, node::Client>,
+    pub(super) dex_node_clients: DexNodeClients,
     pub(super) duration_before_start: Duration,
     pub(super) gas_limit: Gas,
     pub(super) update_currencies_interval: Duration,
+    pub(super) currency_pairs_cache: CurrencyPairsCache,
+    pub(super) price_cache: Option<PriceCache>,
+    pub(super) feed_sequence_memo: bool,
+    pub(super) unhealthy_grace_period: Option<Duration>,
+    pub(super) feeder_registration: Enforcement,
+    pub(super) min_confirmations: u64,
+    pub(super) version_recheck_interval: Option<Duration>,
+    pub(super) version_query_attempts: NonZeroU8,
+    pub(super) max_pairs_per_feed: Option<NonZeroUsize>,
+    pub(super) restart_on_decimal_digits_change: bool,
+    pub(super) feed_dedup: Option<FeedDedup>,
+    pub(super) prime_on_startup: bool,
+    pub(super) feeder_rotation: Option<FeederRotation>,
+    pub(super) skip_tick_while_unconfirmed: bool,
+    pub(super) poll_limit: Option<PollLimit>,
+    pub(super) max_consecutive_empty_ticks: Option<NonZeroU64>,
+    pub(super) restart_on_empty_streak: bool,
+    pub(super) pair_rejections_before_disabling: NonZeroU32,
+    pub(super) feed_verification: Option<FeedVerification>,
 }
 
 impl ApplicationDefined {
     pub fn new() -> Result<Self> {
         Ok(ApplicationDefined {
-            dex_node_clients: BTreeMap::new(),
+            dex_node_clients: DexNodeClients::new(read_dex_client_max_age()?),
             duration_before_start: read_duration_before_start()?,
             gas_limit: read_gas_limit()?,
             update_currencies_interval: read_update_currencies_interval()?,
+            currency_pairs_cache: CurrencyPairsCache::new(),
+            price_cache: read_price_warm_up_cache()?,
+            feed_sequence_memo: read_feed_sequence_memo()?,
+            unhealthy_grace_period: read_unhealthy_grace_period()?,
+            feeder_registration: read_feeder_registration_enforcement()?,
+            min_confirmations: read_min_confirmations()?,
+            version_recheck_interval: read_version_recheck_interval()?,
+            version_query_attempts: read_version_query_attempts()?,
+            max_pairs_per_feed: read_max_pairs_per_feed()?,
+            restart_on_decimal_digits_change:
+                read_restart_on_decimal_digits_change()?,
+            feed_dedup: read_feed_dedup_window()?.map(FeedDedup::new),
+            prime_on_startup: read_prime_on_startup()?,
+            feeder_rotation: read_feeder_rotation()?,
+            skip_tick_while_unconfirmed: read_skip_tick_while_unconfirmed()?,
+            poll_limit: read_max_concurrent_delivery_polls()?
+                .map(PollLimit::new),
+            max_consecutive_empty_ticks: read_max_consecutive_empty_ticks()?,
+            restart_on_empty_streak: read_restart_on_empty_streak()?,
+            pair_rejections_before_disabling:
+                read_pair_rejections_before_disabling()?,
+            feed_verification: read_feed_verification()?,
         })
     }
 }
@@ -29,6 +81,12 @@ fn read_duration_before_start() -> Result<Duration> {
         .context("Failed to read duration before feeding starts!")
 }
 
+fn read_dex_client_max_age() -> Result<Option<Duration>> {
+    Option::<u64>::read_from_var("DEX_CLIENT_MAX_AGE_SECONDS")
+        .map(|max_age| max_age.map(Duration::from_secs))
+        .context("Failed to read maximum age of cached DEX node clients!")
+}
+
 fn read_gas_limit() -> Result<Gas> {
     Gas::read_from_var("GAS_LIMIT").context("Failed to read gas limit!")
 }
@@ -38,3 +96,170 @@ fn read_update_currencies_interval() -> Result<Duration> {
         .map(Duration::from_secs)
         .context("Failed to read update currencies interval!")
 }
+
+fn read_price_warm_up_cache() -> Result<Option<PriceCache>> {
+    const DEFAULT_MAX_AGE: Duration = Duration::from_secs(60);
+
+    let enabled = Option::<bool>::read_from_var("PRICE_WARM_UP_CACHE")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context("Failed to read whether price warm-up cache is enabled!")?;
+
+    if !enabled {
+        return Ok(None);
+    }
+
+    Option::<u64>::read_from_var("PRICE_WARM_UP_CACHE_MAX_AGE_SECONDS")
+        .map(|max_age| {
+            Some(PriceCache::new(
+                max_age.map_or(DEFAULT_MAX_AGE, Duration::from_secs),
+            ))
+        })
+        .context("Failed to read maximum age of warm-up cached prices!")
+}
+
+fn read_feed_sequence_memo() -> Result<bool> {
+    Option::<bool>::read_from_var("FEED_SEQUENCE_MEMO")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context("Failed to read whether feed sequence memo is enabled!")
+}
+
+fn read_unhealthy_grace_period() -> Result<Option<Duration>> {
+    Option::<u64>::read_from_var("UNHEALTHY_GRACE_PERIOD_SECONDS")
+        .map(|grace_period| grace_period.map(Duration::from_secs))
+        .context("Failed to read unhealthy provider grace period!")
+}
+
+fn read_feeder_registration_enforcement() -> Result<Enforcement> {
+    Enforcement::read_relaxed_from_var("RELAXED_FEEDER_REGISTRATION_CHECK")
+        .context("Failed to read whether feeder registration check is relaxed!")
+}
+
+fn read_min_confirmations() -> Result<u64> {
+    Option::<u64>::read_from_var("MIN_CONFIRMATIONS")
+        .map(|confirmations| confirmations.unwrap_or(0))
+        .context("Failed to read minimum confirmation depth!")
+}
+
+fn read_version_recheck_interval() -> Result<Option<Duration>> {
+    Option::<u64>::read_from_var("VERSION_RECHECK_SECONDS")
+        .map(|interval| interval.map(Duration::from_secs))
+        .context("Failed to read contract version recheck interval!")
+}
+
+pub(super) fn read_version_query_attempts() -> Result<NonZeroU8> {
+    const DEFAULT: NonZeroU8 = if let Some(attempts) = NonZeroU8::new(3) {
+        attempts
+    } else {
+        unreachable!()
+    };
+
+    Option::<NonZeroU8>::read_from_var("VERSION_QUERY_ATTEMPTS")
+        .map(|attempts| attempts.unwrap_or(DEFAULT))
+        .context("Failed to read maximum count of contract version queries!")
+}
+
+fn read_max_pairs_per_feed() -> Result<Option<NonZeroUsize>> {
+    Option::<NonZeroUsize>::read_from_var("MAX_PAIRS_PER_FEED")
+        .context("Failed to read maximum count of price pairs per feed!")
+}
+
+fn read_restart_on_decimal_digits_change() -> Result<bool> {
+    Option::<bool>::read_from_var("RESTART_ON_DECIMAL_DIGITS_CHANGE")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context("Failed to read whether to restart on decimal digits change!")
+}
+
+fn read_feed_dedup_window() -> Result<Option<Duration>> {
+    Option::<u64>::read_from_var("FEED_DEDUP_WINDOW_SECONDS")
+        .map(|window| window.map(Duration::from_secs))
+        .context("Failed to read feed deduplication window!")
+}
+
+fn read_prime_on_startup() -> Result<bool> {
+    Option::<bool>::read_from_var("PRIME_ON_STARTUP")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context("Failed to read whether to prime oracle on startup!")
+}
+
+fn read_feeder_rotation() -> Result<Option<FeederRotation>> {
+    let instance_id = Option::<u64>::read_from_var("FEEDER_INSTANCE_ID")
+        .context("Failed to read feeder instance ID!")?;
+
+    let instance_count =
+        Option::<NonZeroU64>::read_from_var("FEEDER_INSTANCE_COUNT")
+            .context("Failed to read count of feeder instances!")?;
+
+    match (instance_id, instance_count) {
+        (Some(instance_id), Some(instance_count)) => {
+            FeederRotation::new(instance_id, instance_count).map(Some)
+        },
+        (None, None) => Ok(None),
+        _ => bail!(
+            "Feeder instance ID and count of feeder instances have to be \
+            either both set or both unset!"
+        ),
+    }
+}
+
+fn read_skip_tick_while_unconfirmed() -> Result<bool> {
+    Option::<bool>::read_from_var("SKIP_TICK_WHILE_FEED_UNCONFIRMED")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context(
+            "Failed to read whether to skip ticks while a feed is unconfirmed!",
+        )
+}
+
+fn read_max_concurrent_delivery_polls() -> Result<Option<NonZeroUsize>> {
+    Option::<NonZeroUsize>::read_from_var("MAX_CONCURRENT_DELIVERY_POLLS")
+        .context(
+            "Failed to read maximum count of concurrent delivered \
+            transaction polls!",
+        )
+}
+
+fn read_max_consecutive_empty_ticks() -> Result<Option<NonZeroU64>> {
+    Option::<NonZeroU64>::read_from_var("MAX_CONSECUTIVE_EMPTY_TICKS")
+        .context("Failed to read maximum count of consecutive empty ticks!")
+}
+
+fn read_restart_on_empty_streak() -> Result<bool> {
+    Option::<bool>::read_from_var("RESTART_ON_EMPTY_TICKS_STREAK")
+        .map(|enabled| enabled.unwrap_or(false))
+        .context(
+            "Failed to read whether to restart on a streak of empty ticks!",
+        )
+}
+
+fn read_pair_rejections_before_disabling() -> Result<NonZeroU32> {
+    Option::<NonZeroU32>::read_from_var("PAIR_REJECTIONS_BEFORE_DISABLING")
+        .map(|rejections| rejections.unwrap_or(NonZeroU32::MIN))
+        .context("Failed to read count of rejections before disabling a pair!")
+}
+
+fn read_feed_verification() -> Result<Option<FeedVerification>> {
+    let Some(delay) =
+        Option::<u64>::read_from_var("FEED_VERIFICATION_DELAY_SECONDS")
+            .context(
+                "Failed to read delay before verifying delivered feeds!",
+            )?
+    else {
+        return Ok(None);
+    };
+
+    let tolerance_basis_points = Option::<u32>::read_from_var(
+        "FEED_VERIFICATION_TOLERANCE_BASIS_POINTS",
+    )
+    .map(|tolerance| tolerance.unwrap_or(0))
+    .context("Failed to read tolerance of verified prices' deviation!")?;
+
+    let max_price_age =
+        Option::<u64>::read_from_var("FEED_VERIFICATION_MAX_PRICE_AGE_SECONDS")
+            .map(|max_age| max_age.map(Duration::from_secs))
+            .context("Failed to read maximum age of verified prices!")?;
+
+    Ok(Some(FeedVerification::new(
+        Duration::from_secs(delay),
+        tolerance_basis_points,
+        max_price_age,
+    )))
+}
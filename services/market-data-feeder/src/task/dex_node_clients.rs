@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use tokio::time::Instant;
+
+use chain_ops::node;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "dex-node-clients",
+            $($body)+
+        )
+    };
+}
+
+pub(crate) trait ConnectionHealth {
+    fn connection_failed(&self) -> bool;
+}
+
+impl ConnectionHealth for node::Client {
+    #[inline]
+    fn connection_failed(&self) -> bool {
+        node::Client::connection_failed(self)
+    }
+}
+
+/// Caches DEX nodes' clients per network, evicting the ones which exceeded
+/// the configured maximum age or which reported connection errors.
+#[must_use]
+pub(crate) struct DexNodeClients<Client = node::Client> {
+    max_age: Option<Duration>,
+    clients: BTreeMap<String, CachedClient<Client>>,
+}
+
+impl<Client> DexNodeClients<Client>
+where
+    Client: ConnectionHealth + Clone,
+{
+    #[inline]
+    pub const fn new(max_age: Option<Duration>) -> Self {
+        Self {
+            max_age,
+            clients: BTreeMap::new(),
+        }
+    }
+
+    pub fn get(&mut self, network: &str) -> Option<Client> {
+        let cached = self.clients.get(network)?;
+
+        let expired = self
+            .max_age
+            .is_some_and(|max_age| cached.established_at.elapsed() >= max_age);
+
+        if expired {
+            log!(info!(%network, "Evicting expired client."));
+        } else if cached.client.connection_failed() {
+            log!(warn!(
+                %network,
+                "Evicting client which reported connection errors.",
+            ));
+        } else {
+            return Some(cached.client.clone());
+        }
+
+        _ = self.clients.remove(network);
+
+        None
+    }
+
+    pub fn insert(&mut self, network: String, client: Client) {
+        _ = self.clients.insert(
+            network,
+            CachedClient {
+                established_at: Instant::now(),
+                client,
+            },
+        );
+    }
+}
+
+struct CachedClient<Client> {
+    established_at: Instant,
+    client: Client,
+}
+
+#[cfg(test)]
+#[derive(Clone, Default)]
+struct TestingClient(std::sync::Arc<std::sync::atomic::AtomicBool>);
+
+#[cfg(test)]
+impl ConnectionHealth for TestingClient {
+    fn connection_failed(&self) -> bool {
+        self.0.load(std::sync::atomic::Ordering::Acquire)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_max_age_expiry() {
+    use tokio::time::advance;
+
+    let mut clients = DexNodeClients::new(Some(Duration::from_secs(60)));
+
+    clients.insert("osmosis".into(), TestingClient::default());
+
+    assert!(clients.get("osmosis").is_some());
+
+    advance(Duration::from_secs(59)).await;
+
+    assert!(clients.get("osmosis").is_some());
+
+    advance(Duration::from_secs(1)).await;
+
+    assert!(clients.get("osmosis").is_none());
+
+    clients.insert("osmosis".into(), TestingClient::default());
+
+    assert!(clients.get("osmosis").is_some());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_connection_error_eviction() {
+    use std::sync::atomic::Ordering;
+
+    let mut clients = DexNodeClients::new(None);
+
+    let client = TestingClient::default();
+
+    clients.insert("neutron".into(), client.clone());
+
+    assert!(clients.get("neutron").is_some());
+
+    client.0.store(true, Ordering::Release);
+
+    assert!(clients.get("neutron").is_none());
+
+    client.0.store(false, Ordering::Release);
+
+    assert!(clients.get("neutron").is_none());
+}
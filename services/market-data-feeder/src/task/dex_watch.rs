@@ -0,0 +1,101 @@
+use std::future::Future;
+
+use anyhow::{bail, Result};
+
+use chain_ops::contract::admin::Dex;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "dex-watch",
+            $($body)+
+        )
+    };
+}
+
+/// Keeps the protocol's DEX configuration the provider was created with, so
+/// a change in the admin contract, e.g. an upgrade moving Astroport's router
+/// to a new address, isn't missed by an already running provider.
+#[must_use]
+pub(crate) struct DexWatch {
+    dex: Dex,
+}
+
+impl DexWatch {
+    #[inline]
+    pub const fn new(dex: Dex) -> Self {
+        Self { dex }
+    }
+
+    /// Compares the configuration currently reported by the admin contract
+    /// with the one the provider was created with.
+    ///
+    /// Fails when it changed, so the task is restarted and the provider is
+    /// recreated from the updated configuration. A failed query only skips
+    /// the check, as it doesn't indicate a change and is retried on the next
+    /// update.
+    pub async fn check<F>(&self, query_dex: F) -> Result<()>
+    where
+        F: Future<Output = Result<Dex>>,
+    {
+        let dex = match query_dex.await {
+            Ok(dex) => dex,
+            Err(error) => {
+                log!(warn!(
+                    ?error,
+                    "Failed to query protocol's DEX configuration! Skipping \
+                    check.",
+                ));
+
+                return Ok(());
+            },
+        };
+
+        if dex != self.dex {
+            bail!(
+                "Protocol's DEX configuration changed! Restarting task. \
+                Previous={previous:?}; Current={dex:?}",
+                previous = self.dex,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_router_address_change_detected() {
+    let dex_watch = DexWatch::new(Dex::Astroport {
+        router_address: "neutron1router".into(),
+    });
+
+    dex_watch
+        .check(async {
+            Ok(Dex::Astroport {
+                router_address: "neutron1router".into(),
+            })
+        })
+        .await
+        .unwrap();
+
+    let error = dex_watch
+        .check(async {
+            Ok(Dex::Astroport {
+                router_address: "neutron1upgraded".into(),
+            })
+        })
+        .await
+        .unwrap_err()
+        .to_string();
+
+    assert!(error.contains("neutron1router"));
+
+    assert!(error.contains("neutron1upgraded"));
+
+    assert!(dex_watch.check(async { Ok(Dex::Osmosis) }).await.is_err());
+
+    dex_watch
+        .check(async { Err(anyhow::anyhow!("Query timed out!")) })
+        .await
+        .unwrap();
+}
@@ -0,0 +1,68 @@
+use std::num::NonZeroU64;
+
+/// Tracks consecutive ticks which collected no prices at all, so a provider
+/// whose upstream keeps silently returning nothing, while still passing
+/// health checks, is told apart from a single transient empty tick.
+#[must_use]
+pub(crate) struct EmptyStreak {
+    threshold: Option<NonZeroU64>,
+    consecutive: u64,
+}
+
+impl EmptyStreak {
+    #[inline]
+    pub const fn new(threshold: Option<NonZeroU64>) -> Self {
+        Self {
+            threshold,
+            consecutive: 0,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn consecutive(&self) -> u64 {
+        self.consecutive
+    }
+
+    #[inline]
+    pub fn reset(&mut self) {
+        self.consecutive = 0;
+    }
+
+    /// Records an empty tick and returns whether the streak just reached the
+    /// threshold, so it's reported once per streak.
+    #[must_use]
+    pub fn record(&mut self) -> bool {
+        self.consecutive += 1;
+
+        self.threshold
+            .is_some_and(|threshold| self.consecutive == threshold.get())
+    }
+}
+
+#[test]
+fn test_alert_fires_at_threshold() {
+    let mut empty_streak = EmptyStreak::new(NonZeroU64::new(3));
+
+    let alerts: Vec<bool> = (0..5).map(|_| empty_streak.record()).collect();
+
+    assert_eq!(alerts, [false, false, true, false, false]);
+
+    assert_eq!(empty_streak.consecutive(), 5);
+
+    empty_streak.reset();
+
+    assert!(!empty_streak.record());
+
+    empty_streak.reset();
+
+    assert!(!empty_streak.record());
+
+    assert!(!empty_streak.record());
+
+    assert!(empty_streak.record());
+
+    let mut unbounded = EmptyStreak::new(None);
+
+    assert!((0..100).all(|_| !unbounded.record()));
+}
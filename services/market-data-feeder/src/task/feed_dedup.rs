@@ -0,0 +1,155 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use super::{provider::Price, Id};
+
+type Key = (Id, Arc<str>, Arc<str>);
+
+/// Remembers the prices delivered by each provider for a short window, for
+/// the lifetime of the process, so a quickly restarted provider doesn't
+/// re-submit, on its first tick, a feed identical to one delivered right
+/// before restarting.
+#[derive(Clone)]
+#[must_use]
+pub(crate) struct FeedDedup {
+    window: Duration,
+    fed: Arc<Mutex<BTreeMap<Key, FedPrice>>>,
+}
+
+impl FeedDedup {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            fed: Arc::default(),
+        }
+    }
+
+    /// Filters out the prices identical to ones delivered by the provider
+    /// within the window.
+    pub fn retain_unfed(&self, id: &Id, prices: &[Price]) -> Vec<Price> {
+        let now = Instant::now();
+
+        let fed = self.fed.lock().unwrap_or_else(PoisonError::into_inner);
+
+        prices
+            .iter()
+            .filter(|&price| {
+                !fed.get(&Self::key(id, price)).is_some_and(|fed_price| {
+                    now.saturating_duration_since(fed_price.fed_at)
+                        < self.window
+                        && fed_price.is(price)
+                })
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Records the prices as delivered by the provider.
+    pub fn record_delivered(&self, id: &Id, prices: &[Price]) {
+        let now = Instant::now();
+
+        let mut fed = self.fed.lock().unwrap_or_else(PoisonError::into_inner);
+
+        fed.retain(|_, fed_price| {
+            now.saturating_duration_since(fed_price.fed_at) < self.window
+        });
+
+        for price in prices {
+            _ = fed.insert(
+                Self::key(id, price),
+                FedPrice {
+                    amount: price.amount.amount.clone(),
+                    amount_quote: price.amount_quote.amount.clone(),
+                    fed_at: now,
+                },
+            );
+        }
+    }
+
+    fn key(id: &Id, price: &Price) -> Key {
+        (
+            id.clone(),
+            price.amount.ticker.clone(),
+            price.amount_quote.ticker.clone(),
+        )
+    }
+}
+
+struct FedPrice {
+    amount: String,
+    amount_quote: String,
+    fed_at: Instant,
+}
+
+impl FedPrice {
+    fn is(&self, price: &Price) -> bool {
+        self.amount == price.amount.amount
+            && self.amount_quote == price.amount_quote.amount
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restarted_provider_feed_deduplication() {
+    use tokio::time::advance;
+
+    use super::provider::Coin;
+
+    fn price(amount_quote: &str) -> Price {
+        Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: "NLS".into(),
+            },
+            amount_quote: Coin {
+                amount: amount_quote.into(),
+                ticker: "USDC".into(),
+            },
+        }
+    }
+
+    let id = Id::new("OSMOSIS".into());
+
+    let dedup = FeedDedup::new(Duration::from_secs(30));
+
+    assert_eq!(dedup.retain_unfed(&id, &[price("18110")]).len(), 1);
+
+    assert_eq!(dedup.retain_unfed(&id, &[price("18110")]).len(), 1);
+
+    dedup.record_delivered(&id, &[price("18110")]);
+
+    let restarted_task_dedup = dedup.clone();
+
+    advance(Duration::from_secs(5)).await;
+
+    assert!(restarted_task_dedup
+        .retain_unfed(&id, &[price("18110")])
+        .is_empty());
+
+    assert_eq!(
+        restarted_task_dedup
+            .retain_unfed(&Id::new("NEUTRON".into()), &[price("18110")])
+            .len(),
+        1,
+    );
+
+    let changed = restarted_task_dedup
+        .retain_unfed(&id, &[price("18110"), price("18200")]);
+
+    assert_eq!(changed.len(), 1);
+
+    assert_eq!(changed[0].amount_quote.amount, "18200");
+
+    advance(Duration::from_secs(26)).await;
+
+    assert_eq!(
+        restarted_task_dedup
+            .retain_unfed(&id, &[price("18110")])
+            .len(),
+        1,
+    );
+}
@@ -0,0 +1,73 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Monotonically increasing sequence of a provider's feeds which is embedded
+/// into each feed transaction's memo, allowing duplicate deliveries of the
+/// same feed to be told apart.
+///
+/// The sequence starts at the provider's start time, in milliseconds since
+/// the UNIX epoch, so it keeps increasing across restarts as long as feeds
+/// aren't sent more often than once per millisecond.
+#[must_use]
+pub(crate) struct FeedSequence {
+    protocol: Arc<str>,
+    next: u64,
+}
+
+impl FeedSequence {
+    #[inline]
+    pub fn new(protocol: Arc<str>, started_at: SystemTime) -> Self {
+        let next = started_at.duration_since(UNIX_EPOCH).map_or(0, |elapsed| {
+            elapsed.as_millis().try_into().unwrap_or(u64::MAX)
+        });
+
+        Self { protocol, next }
+    }
+
+    #[must_use]
+    pub fn next_memo(&mut self) -> String {
+        let memo = format!(
+            "Oracle feed; Protocol={}; Sequence={}",
+            self.protocol, self.next,
+        );
+
+        self.next += 1;
+
+        memo
+    }
+}
+
+#[test]
+fn test_memo_increments_per_feed() {
+    use std::time::Duration;
+
+    let started_at = UNIX_EPOCH + Duration::from_millis(1_700_000_000_000);
+
+    let mut sequence =
+        FeedSequence::new("OSMOSIS-OSMOSIS-USDC_NOBLE".into(), started_at);
+
+    assert_eq!(
+        sequence.next_memo(),
+        "Oracle feed; Protocol=OSMOSIS-OSMOSIS-USDC_NOBLE; \
+        Sequence=1700000000000",
+    );
+
+    assert_eq!(
+        sequence.next_memo(),
+        "Oracle feed; Protocol=OSMOSIS-OSMOSIS-USDC_NOBLE; \
+        Sequence=1700000000001",
+    );
+
+    let mut restarted = FeedSequence::new(
+        "OSMOSIS-OSMOSIS-USDC_NOBLE".into(),
+        started_at + Duration::from_secs(1),
+    );
+
+    assert_eq!(
+        restarted.next_memo(),
+        "Oracle feed; Protocol=OSMOSIS-OSMOSIS-USDC_NOBLE; \
+        Sequence=1700000001000",
+    );
+}
@@ -0,0 +1,94 @@
+use cosmrs::Gas;
+
+/// Tally of the currency pairs fed and skipped during a single feed tick,
+/// logged as one line once the tick completes.
+///
+/// The gas is the fallback gas the feeds were sent with, as the simulated
+/// gas only becomes known once the broadcaster handles them, after the tick
+/// is logged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct FeedSummary {
+    pub fed: usize,
+    pub failed: usize,
+    pub disabled: usize,
+    pub fallback_gas: Gas,
+}
+
+impl FeedSummary {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            fed: 0,
+            failed: 0,
+            disabled: 0,
+            fallback_gas: 0,
+        }
+    }
+
+    #[inline]
+    pub fn record_fed(&mut self, count: usize, fallback_gas: Gas) {
+        self.fed += count;
+
+        self.fallback_gas += fallback_gas;
+    }
+
+    #[inline]
+    pub fn record_failed(&mut self) {
+        self.failed += 1;
+    }
+
+    #[inline]
+    pub fn record_disabled(&mut self, count: usize) {
+        self.disabled += count;
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn skipped(&self) -> usize {
+        self.failed + self.disabled
+    }
+}
+
+#[test]
+fn test_mixed_batch_summary() {
+    enum Outcome {
+        Fetched,
+        Failed,
+    }
+
+    let mut summary = FeedSummary::new();
+
+    summary.record_disabled(2);
+
+    let outcomes = [
+        Outcome::Fetched,
+        Outcome::Failed,
+        Outcome::Fetched,
+        Outcome::Fetched,
+        Outcome::Failed,
+    ];
+
+    let mut fetched = 0;
+
+    for outcome in outcomes {
+        match outcome {
+            Outcome::Fetched => fetched += 1,
+            Outcome::Failed => summary.record_failed(),
+        }
+    }
+
+    summary.record_fed(fetched, 250_000);
+
+    assert_eq!(
+        summary,
+        FeedSummary {
+            fed: 3,
+            failed: 2,
+            disabled: 2,
+            fallback_gas: 250_000,
+        },
+    );
+
+    assert_eq!(summary.skipped(), 4);
+}
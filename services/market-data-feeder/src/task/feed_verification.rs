@@ -0,0 +1,429 @@
+use std::{future::Future, time::Duration};
+
+use anyhow::{anyhow, Context as _, Result};
+use tokio::time::{sleep, Instant};
+
+use crate::oracle::{StablePrices, StoredPrice};
+
+use super::provider::Price;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "feed-verification",
+            $($body)+
+        )
+    };
+}
+
+/// Re-queries the oracle some time after a feed got delivered, confirming
+/// the fed prices were actually taken in, as a feed can be silently ignored
+/// by the contract while its transaction still succeeds.
+///
+/// The oracle only holds prices aggregated from all feeders and quoted in
+/// its stable currency, thus only prices of pairs quoted in it are compared,
+/// within the configured tolerance. The rest are skipped, which is logged
+/// along with their count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct FeedVerification {
+    delay: Duration,
+    tolerance_basis_points: u32,
+    max_price_age: Option<Duration>,
+}
+
+impl FeedVerification {
+    const BASIS_POINTS: u32 = 10_000;
+
+    #[inline]
+    pub const fn new(
+        delay: Duration,
+        tolerance_basis_points: u32,
+        max_price_age: Option<Duration>,
+    ) -> Self {
+        Self {
+            delay,
+            tolerance_basis_points,
+            max_price_age,
+        }
+    }
+
+    /// Logs the fed prices as stale when they were delivered later than the
+    /// maximum price age after being fed, otherwise waits out the delay,
+    /// queries the oracle's prices and logs each fed price which is either
+    /// missing or differs from the stored one.
+    ///
+    /// Returns the number of discrepancies found.
+    pub async fn verify<F>(
+        self,
+        source: &str,
+        fed_prices: &[Price],
+        fed_at: Instant,
+        query_prices: F,
+    ) -> usize
+    where
+        F: Future<Output = Result<StablePrices>>,
+    {
+        let age = fed_at.elapsed();
+
+        if let Some(max_price_age) =
+            self.max_price_age.filter(|&max_age| age > max_age)
+        {
+            log!(warn!(
+                %source,
+                ?age,
+                ?max_price_age,
+                count = fed_prices.len(),
+                "Fed prices were delivered past their maximum age!",
+            ));
+
+            return fed_prices.len();
+        }
+
+        sleep(self.delay).await;
+
+        let StablePrices {
+            stable_currency,
+            prices: stored_prices,
+        } = match query_prices.await {
+            Ok(stable_prices) => stable_prices,
+            Err(error) => {
+                log!(error!(
+                    %source,
+                    ?error,
+                    "Failed to query oracle's prices for verifying feed!",
+                ));
+
+                return 0;
+            },
+        };
+
+        let (comparable, skipped): (Vec<_>, Vec<_>) =
+            fed_prices.iter().partition(|fed_price| {
+                *fed_price.amount_quote.ticker == stable_currency
+            });
+
+        if !skipped.is_empty() {
+            log!(info!(
+                %source,
+                %stable_currency,
+                count = skipped.len(),
+                "Fed prices not quoted in the oracle's stable currency can't \
+                be verified! Skipping them.",
+            ));
+        }
+
+        comparable
+            .into_iter()
+            .filter(|fed_price| {
+                !self.verify_price(source, fed_price, &stored_prices)
+            })
+            .count()
+    }
+
+    fn verify_price(
+        self,
+        source: &str,
+        fed_price: &Price,
+        stored_prices: &[StoredPrice],
+    ) -> bool {
+        let base = &*fed_price.amount.ticker;
+
+        let quote = &*fed_price.amount_quote.ticker;
+
+        let Some(stored_price) = stored_prices.iter().find(|stored_price| {
+            stored_price.amount.ticker == base
+                && stored_price.amount_quote.ticker == quote
+        }) else {
+            log!(warn!(
+                %source,
+                %base,
+                %quote,
+                "Fed price isn't held by the oracle! Feed might have been \
+                silently rejected.",
+            ));
+
+            return false;
+        };
+
+        match self.within_tolerance(fed_price, stored_price) {
+            Ok(true) => true,
+            Ok(false) => {
+                log!(warn!(
+                    %source,
+                    %base,
+                    %quote,
+                    fed = %format_args!(
+                        "{} ~ {}",
+                        fed_price.amount.amount,
+                        fed_price.amount_quote.amount,
+                    ),
+                    on_chain = %format_args!(
+                        "{} ~ {}",
+                        stored_price.amount.amount,
+                        stored_price.amount_quote.amount,
+                    ),
+                    tolerance_basis_points = self.tolerance_basis_points,
+                    "On-chain price differs from the fed one!",
+                ));
+
+                false
+            },
+            Err(error) => {
+                log!(error!(
+                    %source,
+                    %base,
+                    %quote,
+                    ?error,
+                    "Failed to compare fed price with the on-chain one!",
+                ));
+
+                false
+            },
+        }
+    }
+
+    /// Compares both prices' ratios, reduced to their lowest terms, so
+    /// amounts of differing magnitudes still match, falling back to their
+    /// relative deviation when they aren't equal.
+    ///
+    /// The deviation is compared through cross-multiplication, so amounts
+    /// beyond a float's precision aren't rounded.
+    fn within_tolerance(
+        self,
+        fed_price: &Price,
+        stored_price: &StoredPrice,
+    ) -> Result<bool> {
+        fn parse(amount: &str) -> Result<u128> {
+            amount
+                .parse()
+                .with_context(|| format!(r#"Invalid amount, "{amount}"!"#))
+        }
+
+        let fed = reduced(
+            parse(&fed_price.amount.amount)?,
+            parse(&fed_price.amount_quote.amount)?,
+        );
+
+        let stored = reduced(
+            parse(&stored_price.amount.amount)?,
+            parse(&stored_price.amount_quote.amount)?,
+        );
+
+        if fed == stored {
+            return Ok(true);
+        }
+
+        if self.tolerance_basis_points == 0 {
+            return Ok(false);
+        }
+
+        const OVERFLOW_ERROR: &str = "Prices are too large to be compared!";
+
+        let ((fed_base, fed_quote), (stored_base, stored_quote)) =
+            (fed, stored);
+
+        if fed_base == 0 || stored_base == 0 || stored_quote == 0 {
+            return Ok(false);
+        }
+
+        // |fed - stored| / stored <= tolerance
+        //   <=> |fed_quote * stored_base - stored_quote * fed_base|
+        //       * BASIS_POINTS
+        //     <= tolerance * stored_quote * fed_base
+        let fed_cross =
+            fed_quote.checked_mul(stored_base).context(OVERFLOW_ERROR)?;
+
+        let stored_cross =
+            stored_quote.checked_mul(fed_base).context(OVERFLOW_ERROR)?;
+
+        let deviation = fed_cross
+            .abs_diff(stored_cross)
+            .checked_mul(Self::BASIS_POINTS.into());
+
+        let allowed_deviation =
+            stored_cross.checked_mul(self.tolerance_basis_points.into());
+
+        match (deviation, allowed_deviation) {
+            (Some(deviation), Some(allowed_deviation)) => {
+                Ok(deviation <= allowed_deviation)
+            },
+            (Some(_), None) => Ok(true),
+            (None, Some(_)) => Ok(false),
+            (None, None) => Err(anyhow!(OVERFLOW_ERROR)),
+        }
+    }
+}
+
+fn reduced(base: u128, quote: u128) -> (u128, u128) {
+    let (mut a, mut b) = (base, quote);
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    if a == 0 {
+        (base, quote)
+    } else {
+        (base / a, quote / a)
+    }
+}
+
+#[cfg(test)]
+fn fed(
+    base: &str,
+    quote: &str,
+    base_amount: &str,
+    quote_amount: &str,
+) -> Price {
+    use std::sync::Arc;
+
+    use super::provider::Coin;
+
+    Price {
+        amount: Coin {
+            amount: base_amount.into(),
+            ticker: Arc::from(base),
+        },
+        amount_quote: Coin {
+            amount: quote_amount.into(),
+            ticker: Arc::from(quote),
+        },
+    }
+}
+
+#[cfg(test)]
+fn stored(base: &str, base_amount: &str, quote_amount: &str) -> StoredPrice {
+    use crate::oracle::StoredAmount;
+
+    StoredPrice {
+        amount: StoredAmount {
+            amount: base_amount.into(),
+            ticker: base.into(),
+        },
+        amount_quote: StoredAmount {
+            amount: quote_amount.into(),
+            ticker: "USDC".into(),
+        },
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_discrepancy_logged() {
+    use chain_ops::testing::LogCapture;
+    use tracing::subscriber;
+
+    const DELAY: Duration = Duration::from_secs(6);
+
+    let capture = LogCapture::leak();
+
+    let _guard = subscriber::set_default(capture);
+
+    let started_at = Instant::now();
+
+    let discrepancies = FeedVerification::new(DELAY, 100, None)
+        .verify(
+            "Test",
+            &[
+                fed("NLS", "USDC", "1000000", "18110"),
+                fed("ATOM", "USDC", "1000000", "6120000"),
+                fed("OSMO", "USDC", "1000000", "410000"),
+                fed("JUNO", "USDC", "1000000", "500000"),
+                fed("NTRN", "OSMO", "1000000", "1250000"),
+            ],
+            started_at,
+            async {
+                assert!(started_at.elapsed() >= DELAY);
+
+                Ok(StablePrices {
+                    stable_currency: "USDC".into(),
+                    prices: vec![
+                        stored("NLS", "100000000", "1811000"),
+                        stored("ATOM", "1000000", "5990000"),
+                        stored("JUNO", "1000000", "502000"),
+                    ],
+                })
+            },
+        )
+        .await;
+
+    assert_eq!(discrepancies, 2);
+
+    let events = capture.events();
+
+    let [skipped, differs, missing] = events.as_slice() else {
+        panic!("Expected exactly three events!");
+    };
+
+    assert_eq!(skipped.field("count"), Some("1"));
+
+    assert!(skipped.message().contains("can't be verified"));
+
+    assert_eq!(differs.field("base"), Some("ATOM"));
+
+    assert_eq!(differs.field("fed"), Some("1000000 ~ 6120000"));
+
+    assert_eq!(differs.field("on_chain"), Some("1000000 ~ 5990000"));
+
+    assert_eq!(missing.field("base"), Some("OSMO"));
+
+    assert!(missing.message().contains("silently rejected"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_stale_feed_logged() {
+    use chain_ops::testing::LogCapture;
+    use tracing::subscriber;
+
+    const MAX_PRICE_AGE: Duration = Duration::from_secs(30);
+
+    let capture = LogCapture::leak();
+
+    let _guard = subscriber::set_default(capture);
+
+    let fed_at = Instant::now();
+
+    sleep(MAX_PRICE_AGE + Duration::from_secs(1)).await;
+
+    let discrepancies =
+        FeedVerification::new(Duration::from_secs(6), 0, Some(MAX_PRICE_AGE))
+            .verify(
+                "Test",
+                &[fed("NLS", "USDC", "1000000", "18110")],
+                fed_at,
+                async { unreachable!("Stale prices aren't compared!") },
+            )
+            .await;
+
+    assert_eq!(discrepancies, 1);
+
+    let events = capture.events();
+
+    let [stale] = events.as_slice() else {
+        panic!("Expected exactly one event!");
+    };
+
+    assert!(stale.message().contains("past their maximum age"));
+}
+
+#[test]
+fn test_tolerance_compared_exactly() {
+    let verification = FeedVerification::new(Duration::ZERO, 1, None);
+
+    let within_tolerance = |fed_quote_amount| {
+        verification
+            .within_tolerance(
+                &fed("NLS", "USDC", "1", fed_quote_amount),
+                &stored("NLS", "1", "100000000000000000000000"),
+            )
+            .unwrap()
+    };
+
+    assert!(within_tolerance("100010000000000000000000"));
+
+    assert!(!within_tolerance("100010000000000000000001"));
+
+    assert!(within_tolerance("99990000000000000000000"));
+
+    assert!(!within_tolerance("99989999999999999999999"));
+}
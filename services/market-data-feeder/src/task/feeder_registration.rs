@@ -0,0 +1,75 @@
+use anyhow::{bail, Result};
+
+use chain_ops::enforcement::Enforcement;
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "feeder-registration",
+            $($body)+
+        )
+    };
+}
+
+/// Checks whether the signer's address is among the feeders registered in
+/// the oracle.
+///
+/// When strict, an unregistered signer fails the task's creation, so no gas
+/// is wasted on feeds which the oracle would reject.
+pub(crate) fn check<'r, I>(
+    feeders: I,
+    signer_address: &str,
+    oracle_address: &str,
+    enforcement: Enforcement,
+) -> Result<()>
+where
+    I: IntoIterator<Item = &'r str>,
+{
+    if feeders.into_iter().any(|feeder| feeder == signer_address) {
+        return Ok(());
+    }
+
+    match enforcement {
+        Enforcement::Strict => bail!(
+            "Signer isn't registered as a feeder of the oracle! \
+            Signer={signer_address}; Oracle={oracle_address}",
+        ),
+        Enforcement::Relaxed => {
+            log!(warn!(
+                signer = signer_address,
+                oracle = oracle_address,
+                "Signer isn't registered as a feeder of the oracle! Feeds \
+                will be rejected until it gets registered.",
+            ));
+
+            Ok(())
+        },
+    }
+}
+
+#[test]
+fn test_registered_and_unregistered_signer() {
+    const ORACLE: &str = "nolus1oracle";
+
+    let feeders = ["nolus1first", "nolus1signer", "nolus1third"];
+
+    let registered =
+        |enforcement| check(feeders, "nolus1signer", ORACLE, enforcement);
+
+    let unregistered =
+        |enforcement| check(feeders, "nolus1other", ORACLE, enforcement);
+
+    assert!(registered(Enforcement::Strict).is_ok());
+
+    assert!(registered(Enforcement::Relaxed).is_ok());
+
+    let error = unregistered(Enforcement::Strict).unwrap_err().to_string();
+
+    assert!(error.contains("nolus1other"));
+
+    assert!(error.contains(ORACLE));
+
+    assert!(unregistered(Enforcement::Relaxed).is_ok());
+
+    assert!(check([], "nolus1signer", ORACLE, Enforcement::Strict).is_err());
+}
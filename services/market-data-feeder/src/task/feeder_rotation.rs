@@ -0,0 +1,152 @@
+use std::{
+    future::Future,
+    num::NonZeroU64,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Result};
+
+/// Deterministic rotation between feeder instances feeding the same oracle,
+/// so only one of them submits prices on each tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct FeederRotation {
+    instance_id: u64,
+    instance_count: NonZeroU64,
+}
+
+impl FeederRotation {
+    pub fn new(instance_id: u64, instance_count: NonZeroU64) -> Result<Self> {
+        if instance_id >= instance_count.get() {
+            bail!(
+                "Feeder instance ID has to be less than the count of \
+                instances! ID={instance_id}; Count={instance_count}",
+            );
+        }
+
+        Ok(Self {
+            instance_id,
+            instance_count,
+        })
+    }
+
+    /// Derives the index of the tick from the wall clock, so it's the same
+    /// across all instances regardless of when each of them started.
+    pub fn tick_index(now: SystemTime, tick_duration: Duration) -> u64 {
+        now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+            / tick_duration.as_secs().max(1)
+    }
+
+    #[must_use]
+    pub const fn is_turn(&self, tick_index: u64) -> bool {
+        tick_index % self.instance_count.get() == self.instance_id
+    }
+
+    /// Decides whether to feed on the given tick, falling back to feeding
+    /// outside of the instance's turn when the oracle's prices are stale.
+    ///
+    /// The staleness query is only awaited when it isn't the instance's turn.
+    pub async fn decide<F>(
+        &self,
+        tick_index: u64,
+        query_stale: F,
+    ) -> Result<Decision>
+    where
+        F: Future<Output = Result<bool>>,
+    {
+        if self.is_turn(tick_index) {
+            Ok(Decision::Turn)
+        } else if query_stale.await? {
+            Ok(Decision::StaleFallback)
+        } else {
+            Ok(Decision::Skip)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Decision {
+    Turn,
+    StaleFallback,
+    Skip,
+}
+
+#[test]
+fn test_rotation_selection() {
+    let count = NonZeroU64::new(3).unwrap();
+
+    let rotations: Vec<_> = (0..3)
+        .map(|instance_id| FeederRotation::new(instance_id, count).unwrap())
+        .collect();
+
+    for tick_index in 0..30 {
+        let feeding: Vec<_> = rotations
+            .iter()
+            .filter(|rotation| rotation.is_turn(tick_index))
+            .collect();
+
+        assert_eq!(
+            feeding,
+            [&rotations[usize::try_from(tick_index % 3).unwrap()]],
+        );
+    }
+
+    assert!(FeederRotation::new(3, count).is_err());
+
+    let tick_duration = Duration::from_secs(15);
+
+    let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+
+    assert_eq!(FeederRotation::tick_index(at(0), tick_duration), 0);
+
+    assert_eq!(FeederRotation::tick_index(at(14), tick_duration), 0);
+
+    assert_eq!(FeederRotation::tick_index(at(15), tick_duration), 1);
+
+    assert_eq!(FeederRotation::tick_index(at(3_000), tick_duration), 200);
+}
+
+#[tokio::test]
+async fn test_stale_prices_fallback() {
+    use std::cell::Cell;
+
+    use anyhow::anyhow;
+
+    let rotation = FeederRotation::new(1, NonZeroU64::new(2).unwrap()).unwrap();
+
+    let queried = Cell::new(0);
+
+    let query = |stale| {
+        let queried = &queried;
+
+        async move {
+            queried.set(queried.get() + 1);
+
+            Ok(stale)
+        }
+    };
+
+    assert_eq!(
+        rotation.decide(1, query(false)).await.unwrap(),
+        Decision::Turn,
+    );
+
+    assert_eq!(queried.get(), 0);
+
+    assert_eq!(
+        rotation.decide(2, query(false)).await.unwrap(),
+        Decision::Skip,
+    );
+
+    assert_eq!(
+        rotation.decide(2, query(true)).await.unwrap(),
+        Decision::StaleFallback,
+    );
+
+    assert_eq!(queried.get(), 2);
+
+    assert!(rotation
+        .decide(2, async { Err(anyhow!("Node unavailable!")) })
+        .await
+        .is_err());
+}
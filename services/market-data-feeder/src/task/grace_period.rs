@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+/// Tracks for how long a provider's health checks have been failing
+/// continuously, so it can give up once the configured grace period is
+/// exceeded instead of retrying indefinitely.
+#[must_use]
+pub(crate) struct GracePeriod {
+    duration: Option<Duration>,
+    failing_since: Option<Instant>,
+}
+
+impl GracePeriod {
+    #[inline]
+    pub const fn new(duration: Option<Duration>) -> Self {
+        Self {
+            duration,
+            failing_since: None,
+        }
+    }
+
+    #[inline]
+    pub fn succeeded(&mut self) {
+        self.failing_since = None;
+    }
+
+    /// Records a failed health check and returns whether checks have been
+    /// failing for longer than the grace period.
+    #[must_use]
+    pub fn failed(&mut self) -> bool {
+        let failing_since =
+            *self.failing_since.get_or_insert_with(Instant::now);
+
+        self.duration
+            .is_some_and(|duration| failing_since.elapsed() >= duration)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_perpetual_failure_exceeds_grace_period() {
+    use tokio::time::sleep;
+
+    let mut grace_period = GracePeriod::new(Some(Duration::from_secs(30)));
+
+    let started_at = Instant::now();
+
+    while !grace_period.failed() {
+        sleep(Duration::from_secs(1)).await;
+    }
+
+    assert_eq!(started_at.elapsed(), Duration::from_secs(30));
+
+    grace_period.succeeded();
+
+    assert!(!grace_period.failed());
+
+    let mut unbounded = GracePeriod::new(None);
+
+    for _ in 0..3600 {
+        assert!(!unbounded.failed());
+
+        sleep(Duration::from_secs(1)).await;
+    }
+}
@@ -0,0 +1,423 @@
+use std::{borrow::Borrow, future::Future, time::Duration};
+
+use anyhow::{anyhow, bail, Context as _, Error, Result};
+use tokio::time::timeout;
+
+use chain_ops::{env::ReadFromVar, node};
+
+macro_rules! log {
+    ($macro:ident!($($body:tt)+)) => {
+        ::tracing::$macro!(
+            target: "healthcheck",
+            $($body)+
+        )
+    };
+}
+
+/// Gauges whether a DEX node is ready to serve price queries.
+pub(crate) trait HealthcheckStrategy {
+    fn check(&mut self) -> impl Future<Output = Result<Health>> + Send + '_;
+}
+
+#[derive(Debug)]
+pub(crate) enum Health {
+    Healthy,
+    Unhealthy(Error),
+}
+
+/// Configured healthcheck strategy, built into a [`Healthcheck`] per DEX
+/// node client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Strategy {
+    /// Requires the node to not be syncing and its latest block height to
+    /// increment between checks.
+    Tendermint,
+    /// Issues a cheap query and treats a successful response within the
+    /// timeout as healthy, for nodes behind gateways which don't expose
+    /// tendermint's status.
+    Query { timeout: Duration },
+    /// Always reports healthy without issuing any requests, for trusted,
+    /// co-located nodes.
+    None,
+}
+
+impl Strategy {
+    pub fn build(self, client: node::Client) -> Healthcheck {
+        match self {
+            Self::Tendermint => Healthcheck::Tendermint(Tendermint::new(
+                client.query_tendermint(),
+            )),
+            Self::Query { timeout } => Healthcheck::Query(Query::new(
+                client.query_reflection(),
+                timeout,
+            )),
+            Self::None => Healthcheck::None(NoHealthcheck),
+        }
+    }
+}
+
+impl ReadFromVar for Strategy {
+    fn read_from_var<S>(variable: S) -> Result<Self>
+    where
+        S: Borrow<str> + Into<String>,
+    {
+        let mut variable = variable.into();
+
+        match &*String::read_from_var(variable.as_str())
+            .context("Failed to read healthcheck strategy!")?
+        {
+            "tendermint" => Ok(Self::Tendermint),
+            "none" => Ok(Self::None),
+            "query" => {
+                variable.push_str("__TIMEOUT_SECONDS");
+
+                u64::read_from_var(variable)
+                    .map(|timeout| Self::Query {
+                        timeout: Duration::from_secs(timeout),
+                    })
+                    .context("Failed to read healthcheck query timeout!")
+            },
+            strategy => bail!(
+                "Unknown healthcheck strategy \"{strategy}\"! Expected either \
+                \"tendermint\", \"query\" or \"none\"."
+            ),
+        }
+    }
+}
+
+pub(crate) enum Healthcheck {
+    Tendermint(Tendermint<node::QueryTendermint>),
+    Query(Query<node::QueryReflection>),
+    None(NoHealthcheck),
+}
+
+impl HealthcheckStrategy for Healthcheck {
+    async fn check(&mut self) -> Result<Health> {
+        match self {
+            Self::Tendermint(tendermint) => tendermint.check().await,
+            Self::Query(query) => query.check().await,
+            Self::None(no_healthcheck) => no_healthcheck.check().await,
+        }
+    }
+}
+
+pub(crate) trait NodeStatus: Send {
+    fn syncing(&mut self) -> impl Future<Output = Result<bool>> + Send + '_;
+
+    fn latest_block_height(
+        &mut self,
+    ) -> impl Future<Output = Result<u64>> + Send + '_;
+
+    fn reconnect(&self) -> impl Future<Output = Result<()>> + Send + '_;
+}
+
+impl NodeStatus for node::QueryTendermint {
+    fn syncing(&mut self) -> impl Future<Output = Result<bool>> + Send + '_ {
+        self.syncing()
+    }
+
+    fn latest_block_height(
+        &mut self,
+    ) -> impl Future<Output = Result<u64>> + Send + '_ {
+        self.get_latest_block()
+    }
+
+    fn reconnect(&self) -> impl Future<Output = Result<()>> + Send + '_ {
+        node::Reconnect::reconnect(self)
+    }
+}
+
+pub(crate) struct Tendermint<C> {
+    client: C,
+    last_block_height: Option<u64>,
+}
+
+impl<C> Tendermint<C>
+where
+    C: NodeStatus,
+{
+    pub const fn new(client: C) -> Self {
+        Self {
+            client,
+            last_block_height: None,
+        }
+    }
+
+    async fn query_status(&mut self) -> Result<(bool, u64)> {
+        let syncing = self.client.syncing().await?;
+
+        self.client
+            .latest_block_height()
+            .await
+            .map(|latest_block_height| (syncing, latest_block_height))
+    }
+}
+
+impl<C> HealthcheckStrategy for Tendermint<C>
+where
+    C: NodeStatus,
+{
+    async fn check(&mut self) -> Result<Health> {
+        let (syncing, latest_block_height) = match self.query_status().await {
+            Ok(status) => status,
+            Err(error) => {
+                log!(warn!(
+                    ?error,
+                    "Dex node's status query failed! Reconnecting before \
+                    retrying.",
+                ));
+
+                self.client
+                    .reconnect()
+                    .await
+                    .context("Failed to reconnect to dex node!")?;
+
+                self.query_status().await.context(
+                    "Dex node's status query failed after reconnecting!",
+                )?
+            },
+        };
+
+        if syncing {
+            bail!("Dex node reported in with syncing status!");
+        }
+
+        match self.last_block_height {
+            Some(last_block_height)
+                if last_block_height >= latest_block_height =>
+            {
+                Ok(Health::Unhealthy(anyhow!(
+                    "Dex node's latest block height didn't increment! Last \
+                    recorded: {last_block_height}; Latest reported: \
+                    {latest_block_height}."
+                )))
+            },
+            _ => {
+                self.last_block_height = Some(latest_block_height);
+
+                Ok(Health::Healthy)
+            },
+        }
+    }
+}
+
+pub(crate) trait Probe: Send {
+    fn probe(&mut self) -> impl Future<Output = Result<()>> + Send + '_;
+}
+
+impl Probe for node::QueryReflection {
+    async fn probe(&mut self) -> Result<()> {
+        self.account_prefix().await.map(drop)
+    }
+}
+
+pub(crate) struct Query<C> {
+    client: C,
+    timeout: Duration,
+}
+
+impl<C> Query<C>
+where
+    C: Probe,
+{
+    pub const fn new(client: C, timeout: Duration) -> Self {
+        Self { client, timeout }
+    }
+}
+
+impl<C> HealthcheckStrategy for Query<C>
+where
+    C: Probe,
+{
+    async fn check(&mut self) -> Result<Health> {
+        Ok(match timeout(self.timeout, self.client.probe()).await {
+            Ok(Ok(())) => Health::Healthy,
+            Ok(Err(error)) => Health::Unhealthy(
+                error.context("Dex node's probe query failed!"),
+            ),
+            Err(_) => Health::Unhealthy(anyhow!(
+                "Dex node's probe query didn't complete within {} seconds!",
+                self.timeout.as_secs(),
+            )),
+        })
+    }
+}
+
+pub(crate) struct NoHealthcheck;
+
+impl HealthcheckStrategy for NoHealthcheck {
+    async fn check(&mut self) -> Result<Health> {
+        Ok(Health::Healthy)
+    }
+}
+
+#[tokio::test]
+async fn test_tendermint_strategy() {
+    use std::collections::VecDeque;
+
+    struct Stub {
+        syncing: bool,
+        block_heights: VecDeque<u64>,
+    }
+
+    impl NodeStatus for Stub {
+        async fn syncing(&mut self) -> Result<bool> {
+            Ok(self.syncing)
+        }
+
+        async fn latest_block_height(&mut self) -> Result<u64> {
+            self.block_heights
+                .pop_front()
+                .context("No more block heights!")
+        }
+
+        async fn reconnect(&self) -> Result<()> {
+            bail!("Reconnecting is not supported!")
+        }
+    }
+
+    let mut tendermint = Tendermint::new(Stub {
+        syncing: false,
+        block_heights: [10, 11, 11, 10, 12].into(),
+    });
+
+    let mut results = vec![];
+
+    for _ in 0..5 {
+        results
+            .push(matches!(tendermint.check().await.unwrap(), Health::Healthy));
+    }
+
+    assert_eq!(results, [true, true, false, false, true]);
+
+    tendermint.client.syncing = true;
+
+    tendermint.client.block_heights.push_back(13);
+
+    assert!(tendermint.check().await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_query_strategy() {
+    struct Stub {
+        delay: Duration,
+        succeed: bool,
+    }
+
+    impl Probe for Stub {
+        async fn probe(&mut self) -> Result<()> {
+            tokio::time::sleep(self.delay).await;
+
+            if self.succeed {
+                Ok(())
+            } else {
+                bail!("Gateway rejected query!")
+            }
+        }
+    }
+
+    let mut query = Query::new(
+        Stub {
+            delay: Duration::from_secs(1),
+            succeed: true,
+        },
+        Duration::from_secs(5),
+    );
+
+    assert!(matches!(query.check().await.unwrap(), Health::Healthy));
+
+    query.client.delay = Duration::from_secs(10);
+
+    assert!(matches!(query.check().await.unwrap(), Health::Unhealthy(_)));
+
+    query.client.delay = Duration::ZERO;
+
+    query.client.succeed = false;
+
+    assert!(matches!(query.check().await.unwrap(), Health::Unhealthy(_)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_no_healthcheck_strategy() {
+    use tokio::time::Instant;
+
+    let started_at = Instant::now();
+
+    for _ in 0..3 {
+        // A zero timeout only succeeds when the check completes on its first
+        // poll, i.e. without waiting on any network round trip.
+        assert!(matches!(
+            timeout(Duration::ZERO, NoHealthcheck.check()).await,
+            Ok(Ok(Health::Healthy))
+        ));
+    }
+
+    assert_eq!(started_at.elapsed(), Duration::ZERO);
+}
+
+#[tokio::test]
+async fn test_tendermint_strategy_reconnects_dropped_connection() {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    struct Stub {
+        connected: AtomicBool,
+        reconnectable: bool,
+        reconnects: AtomicUsize,
+        block_height: u64,
+    }
+
+    impl NodeStatus for Stub {
+        async fn syncing(&mut self) -> Result<bool> {
+            if self.connected.load(Ordering::Acquire) {
+                Ok(false)
+            } else {
+                bail!("Connection dropped!")
+            }
+        }
+
+        async fn latest_block_height(&mut self) -> Result<u64> {
+            self.block_height += 1;
+
+            Ok(self.block_height)
+        }
+
+        async fn reconnect(&self) -> Result<()> {
+            _ = self.reconnects.fetch_add(1, Ordering::AcqRel);
+
+            if self.reconnectable {
+                self.connected.store(true, Ordering::Release);
+
+                Ok(())
+            } else {
+                bail!("Node is unreachable!")
+            }
+        }
+    }
+
+    let mut tendermint = Tendermint::new(Stub {
+        connected: AtomicBool::new(true),
+        reconnectable: true,
+        reconnects: AtomicUsize::new(0),
+        block_height: 0,
+    });
+
+    assert!(matches!(tendermint.check().await.unwrap(), Health::Healthy));
+
+    tendermint.client.connected.store(false, Ordering::Release);
+
+    assert!(matches!(tendermint.check().await.unwrap(), Health::Healthy));
+
+    assert_eq!(tendermint.client.reconnects.load(Ordering::Acquire), 1);
+
+    assert!(matches!(tendermint.check().await.unwrap(), Health::Healthy));
+
+    assert_eq!(tendermint.client.reconnects.load(Ordering::Acquire), 1);
+
+    tendermint.client.connected.store(false, Ordering::Release);
+
+    tendermint.client.reconnectable = false;
+
+    assert!(tendermint.check().await.is_err());
+
+    assert_eq!(tendermint.client.reconnects.load(Ordering::Acquire), 2);
+}
@@ -1,8 +1,12 @@
 use std::{
-    borrow::Cow, collections::btree_map::Entry as BTreeMapEntry, sync::Arc,
+    borrow::Cow,
+    env,
+    num::NonZeroU64,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
-use anyhow::{bail, Context as _, Result};
+use anyhow::{bail, Context as _, Error, Result};
 
 use chain_ops::{
     channel,
@@ -15,11 +19,19 @@ use chain_ops::{
 };
 
 use crate::{
-    oracle::Oracle,
-    providers::{astroport::Astroport, osmosis::Osmosis, Provider},
+    oracle::{Oracle, PairFilter},
+    providers::{
+        astroport::Astroport,
+        osmosis::{Osmosis, PoolCandidates},
+        Provider,
+    },
 };
 
-use super::{context, Base, Task};
+use super::{
+    context, dex_watch::DexWatch, feed_sequence::FeedSequence,
+    feeder_registration, healthcheck, price_floor::PriceFloors,
+    price_transform::PriceTransforms, Base, Task,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Id {
@@ -31,9 +43,20 @@ impl Id {
         Self { protocol }
     }
 
-    fn dex_node_grpc_var(mut network: String) -> Result<String> {
-        const VAR_SUFFIX: &str = "__NODE_GRPC";
+    fn dex_node_grpc_var(network: String) -> Result<String> {
+        Self::network_var(network, "__NODE_GRPC")
+    }
+
+    fn dex_node_grpc_headers_var(network: String) -> Result<String> {
+        Self::network_var(network, "_HEADER_")
+            .map(|variable| format!("DEX_{variable}"))
+    }
 
+    fn dex_node_healthcheck_var(network: String) -> Result<String> {
+        Self::network_var(network, "__NODE_HEALTHCHECK")
+    }
+
+    fn network_var(mut network: String, suffix: &str) -> Result<String> {
         if network.is_empty() {
             bail!("Protocol's network identifier is zero-length!");
         }
@@ -49,15 +72,178 @@ impl Id {
             search_index = index + 2;
         }
 
-        network = network.to_ascii_uppercase().replace('-', "_");
+        network = Self::env_var_name(&network)?;
 
-        network.reserve_exact(VAR_SUFFIX.len());
+        network.reserve_exact(suffix.len());
 
-        network.push_str(VAR_SUFFIX);
+        network.push_str(suffix);
 
         Ok(network)
     }
 
+    /// Maps a name reported by the contracts onto an environment variable
+    /// identifier, replacing hyphens with underscores.
+    ///
+    /// Names which still contain characters not allowed in identifiers are
+    /// rejected, instead of producing a lookup which would always fail.
+    fn env_var_name(name: &str) -> Result<String> {
+        let variable = name.to_ascii_uppercase().replace('-', "_");
+
+        if variable.starts_with(|ch: char| ch.is_ascii_digit())
+            || !variable
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        {
+            bail!(
+                "Name \"{name}\" can't be mapped to a valid environment \
+                variable identifier!"
+            );
+        }
+
+        Ok(variable)
+    }
+
+    /// Connects to the DEX node of the given network, configured through the
+    /// `<NETWORK>__NODE_GRPC` and `DEX_<NETWORK>_HEADER_*` variables, e.g.
+    /// `DEX_NEUTRON_MAINNET_HEADER_X_API_KEY` for the `x-api-key` header.
+    pub(super) async fn connect_dex_node_client(
+        network: String,
+    ) -> Result<node::Client> {
+        node::Client::connect_pool_with_options(
+            &[Self::dex_node_grpc_var(network.clone())
+                .and_then(String::read_from_var)?],
+            node::ConnectOptions {
+                headers: Self::dex_node_grpc_headers_var(network)
+                    .and_then(|prefix| {
+                        node::RequestHeaders::read_from_env_prefix(&prefix)
+                    })
+                    .context("Failed to read DEX node's request headers!")?,
+                ..node::ConnectOptions::default()
+            },
+        )
+        .await
+    }
+
+    /// Reads the protocol's tick duration override, falling back to the
+    /// globally configured one.
+    fn read_tick_duration(&self, default: Duration) -> Result<Duration> {
+        self.tick_duration(default, |variable| env::var(variable).ok())
+    }
+
+    /// Resolves the protocol's tick duration override through the given
+    /// lookup of environment variables.
+    ///
+    /// A tick of zero seconds is refused, as the provider's feed interval
+    /// can't have a zero period.
+    fn tick_duration<F>(&self, default: Duration, lookup: F) -> Result<Duration>
+    where
+        F: FnOnce(&str) -> Option<String>,
+    {
+        let mut variable = Self::env_var_name(&self.protocol)?;
+
+        variable.push_str("__TICK_SECONDS");
+
+        lookup(&variable)
+            .map(|tick| tick.parse::<NonZeroU64>())
+            .transpose()
+            .map(|tick| {
+                tick.map_or(default, |tick| Duration::from_secs(tick.get()))
+            })
+            .with_context(|| {
+                format!(
+                    "Failed to read protocol's tick duration! Expected a \
+                    positive amount of seconds. Protocol={}",
+                    self.protocol
+                )
+            })
+    }
+
+    /// Marks failures caused by an oracle contract which isn't instantiated
+    /// yet, e.g. while the protocol is being deployed, so the task is
+    /// deferred instead of being treated as failed.
+    fn defer_if_oracle_not_found(error: Error) -> Error {
+        if node::is_contract_not_found(&error) {
+            error.context(application_defined::NotReady)
+        } else {
+            error
+        }
+    }
+
+    /// Reads the provider's currency pairs restrictions, configured through
+    /// the `<PROVIDER>__PAIRS_ALLOW` and `<PROVIDER>__PAIRS_DENY` variables.
+    pub(super) fn read_pair_filter(dex: &Dex) -> Result<PairFilter> {
+        let provider = Self::env_var_name(Self::dex_name(dex))?;
+
+        let allow =
+            Option::<String>::read_from_var(format!("{provider}__PAIRS_ALLOW"))
+                .context("Failed to read provider's allowed currency pairs!")?;
+
+        let deny =
+            Option::<String>::read_from_var(format!("{provider}__PAIRS_DENY"))
+                .context("Failed to read provider's denied currency pairs!")?;
+
+        PairFilter::new(allow.as_deref(), deny.as_deref())
+            .context("Failed to parse provider's currency pairs filter!")
+    }
+
+    /// Reads the provider's price transforms, configured through the
+    /// `<PROVIDER>__PRICE_TRANSFORMS` variable.
+    fn read_price_transforms(dex: &Dex) -> Result<PriceTransforms> {
+        let provider = Self::env_var_name(Self::dex_name(dex))?;
+
+        Option::<String>::read_from_var(format!("{provider}__PRICE_TRANSFORMS"))
+            .context("Failed to read provider's price transforms!")?
+            .map_or_else(
+                || Ok(PriceTransforms::default()),
+                |transforms| PriceTransforms::parse(&transforms),
+            )
+            .context("Failed to parse provider's price transforms!")
+    }
+
+    /// Reads the provider's minimum prices, configured through the
+    /// `<PROVIDER>__PRICE_FLOORS` variable.
+    fn read_price_floors(dex: &Dex) -> Result<PriceFloors> {
+        let provider = Self::env_var_name(Self::dex_name(dex))?;
+
+        Option::<String>::read_from_var(format!("{provider}__PRICE_FLOORS"))
+            .context("Failed to read provider's price floors!")?
+            .map_or_else(
+                || Ok(PriceFloors::default()),
+                |floors| PriceFloors::parse(&floors),
+            )
+            .context("Failed to parse provider's price floors!")
+    }
+
+    /// Reads the provider's candidate pools, configured through the
+    /// `<PROVIDER>__POOL_CANDIDATES` variable.
+    fn read_pool_candidates(dex: &Dex) -> Result<PoolCandidates> {
+        let provider = Self::env_var_name(Self::dex_name(dex))?;
+
+        Option::<String>::read_from_var(format!("{provider}__POOL_CANDIDATES"))
+            .context("Failed to read provider's pool candidates!")?
+            .map_or_else(
+                || Ok(PoolCandidates::default()),
+                |candidates| PoolCandidates::parse(&candidates),
+            )
+            .context("Failed to parse provider's pool candidates!")
+    }
+
+    /// Reads for how long the provider keeps a selected candidate pool,
+    /// configured through the `<PROVIDER>__POOL_SELECTION_REFRESH_SECONDS`
+    /// variable.
+    fn read_pool_selection_refresh_interval(
+        dex: &Dex,
+        default: Duration,
+    ) -> Result<Duration> {
+        let provider = Self::env_var_name(Self::dex_name(dex))?;
+
+        Option::<u64>::read_from_var(format!(
+            "{provider}__POOL_SELECTION_REFRESH_SECONDS"
+        ))
+        .map(|interval| interval.map_or(default, Duration::from_secs))
+        .context("Failed to read provider's pool selection refresh interval!")
+    }
+
     const fn dex_name(dex: &Dex) -> &'static str {
         match dex {
             Dex::Astroport { .. } => "Astroport",
@@ -65,13 +251,19 @@ impl Id {
         }
     }
 
-    fn construct_provider(dex: Dex) -> Provider {
-        match dex {
+    pub(super) fn construct_provider(dex: Dex) -> Result<Provider> {
+        Ok(match dex {
             Dex::Astroport { router_address } => {
                 Provider::Astroport(Astroport::new(router_address))
             },
-            Dex::Osmosis => Provider::Osmosis(Osmosis::new()),
-        }
+            Dex::Osmosis => Provider::Osmosis(Osmosis::new(
+                Self::read_pool_candidates(&Dex::Osmosis)?,
+                Self::read_pool_selection_refresh_interval(
+                    &Dex::Osmosis,
+                    Osmosis::DEFAULT_POOL_SELECTION_REFRESH_INTERVAL,
+                )?,
+            )),
+        })
     }
 }
 
@@ -121,39 +313,59 @@ impl application_defined::Id for Id {
 
         let node_client = service_configuration.node_client().clone();
 
-        let dex_node_client = {
-            let entry = task_creation_context
+        let idle_duration =
+            self.read_tick_duration(service_configuration.idle_duration())?;
+
+        let healthcheck_strategy =
+            Self::dex_node_healthcheck_var(network.clone())
+                .and_then(Option::<healthcheck::Strategy>::read_from_var)
+                .context("Failed to read DEX node's healthcheck strategy!")?
+                .unwrap_or(healthcheck::Strategy::Tendermint);
+
+        let dex_node_client = if let Some(dex_node_client) =
+            task_creation_context.dex_node_clients.get(&network)
+        {
+            dex_node_client
+        } else {
+            let dex_node_client =
+                Self::connect_dex_node_client(network.clone()).await?;
+
+            task_creation_context
                 .dex_node_clients
-                .entry(network.clone());
-
-            match entry {
-                BTreeMapEntry::Vacant(entry) => entry.insert(
-                    node::Client::connect(
-                        &Self::dex_node_grpc_var(network.clone())
-                            .and_then(String::read_from_var)?,
-                    )
-                    .await?,
-                ),
-                BTreeMapEntry::Occupied(entry) => entry.into_mut(),
-            }
-            .clone()
+                .insert(network, dex_node_client.clone());
+
+            dex_node_client
         };
 
-        task_creation_context
-            .dex_node_clients
-            .insert(network, dex_node_client.clone());
+        let healthcheck = healthcheck_strategy.build(dex_node_client.clone());
 
-        Oracle::new(
+        let mut oracle = Oracle::new(
             node_client.clone().query_wasm(),
             oracle_address.clone(),
             task_creation_context.update_currencies_interval,
+            task_creation_context
+                .currency_pairs_cache
+                .for_oracle(&oracle_address),
+            Self::read_pair_filter(&dex)?,
+            task_creation_context.version_recheck_interval,
+            task_creation_context.version_query_attempts,
         )
         .await
-        .map(|oracle| Base {
+        .map_err(Self::defer_if_oracle_not_found)?;
+
+        feeder_registration::check(
+            oracle.query_feeders().await?.iter().map(String::as_str),
+            service_configuration.signer().address(),
+            &oracle_address,
+            task_creation_context.feeder_registration,
+        )?;
+
+        let base = Base {
             protocol: self.protocol.clone(),
             node_client,
             oracle,
             dex_node_client,
+            healthcheck,
             source: format!(
                 "{}; Protocol={}",
                 Self::dex_name(&dex),
@@ -165,14 +377,166 @@ impl application_defined::Id for Id {
                 service_configuration.signer().address().into(),
                 oracle_address,
             ),
-            idle_duration: service_configuration.idle_duration(),
+            idle_duration,
             timeout_duration: service_configuration.timeout_duration(),
             hard_gas_limit: task_creation_context.gas_limit,
             transaction_tx: transaction_tx.clone(),
-        })
-        .map(|base| Task {
+            price_cache: task_creation_context.price_cache.clone(),
+            feed_sequence: task_creation_context.feed_sequence_memo.then(
+                || FeedSequence::new(self.protocol.clone(), SystemTime::now()),
+            ),
+            unhealthy_grace_period: task_creation_context
+                .unhealthy_grace_period,
+            min_confirmations: task_creation_context.min_confirmations,
+            max_pairs_per_feed: task_creation_context.max_pairs_per_feed,
+            restart_on_decimal_digits_change: task_creation_context
+                .restart_on_decimal_digits_change,
+            feed_dedup: task_creation_context.feed_dedup.clone(),
+            prime_on_startup: task_creation_context.prime_on_startup,
+            feeder_rotation: task_creation_context.feeder_rotation,
+            price_transforms: Self::read_price_transforms(&dex)?,
+            price_floors: Self::read_price_floors(&dex)?,
+            skip_tick_while_unconfirmed: task_creation_context
+                .skip_tick_while_unconfirmed,
+            poll_limit: task_creation_context.poll_limit.clone(),
+            max_consecutive_empty_ticks: task_creation_context
+                .max_consecutive_empty_ticks,
+            restart_on_empty_streak: task_creation_context
+                .restart_on_empty_streak,
+            pair_rejections_before_disabling: task_creation_context
+                .pair_rejections_before_disabling,
+            admin_contract: service_configuration.admin_contract().clone(),
+            dex_watch: DexWatch::new(dex.clone()),
+            feed_verification: task_creation_context.feed_verification,
+            protocol_readiness: service_configuration
+                .protocol_readiness()
+                .clone(),
+        };
+
+        Ok(Task {
             base,
-            provider: Self::construct_provider(dex),
+            provider: Self::construct_provider(dex)?,
         })
     }
 }
+
+#[tokio::test(start_paused = true)]
+async fn test_per_protocol_tick_durations() {
+    use std::collections::BTreeMap;
+
+    use chain_ops::{
+        channel::{self, Channel as _},
+        task::RunnableState,
+    };
+    use tokio::{spawn, time::sleep};
+
+    use super::provider::{self, OneToOne};
+
+    async fn feeds_within(tick_duration: Duration, window: Duration) -> usize {
+        let (transaction_tx, mut transaction_rx) =
+            channel::unbounded::Channel::new();
+
+        let mut base = Base::stub(&[("NLS", "USDC")], transaction_tx);
+
+        base.idle_duration = tick_duration;
+
+        let feeder = spawn(
+            provider::Provider::new(base, OneToOne).run(RunnableState::New),
+        );
+
+        sleep(window).await;
+
+        feeder.abort();
+
+        _ = feeder.await;
+
+        let mut feeds = 0;
+
+        while transaction_rx.try_recv().is_ok() {
+            feeds += 1;
+        }
+
+        feeds
+    }
+
+    let environment = BTreeMap::from([
+        ("TEST_VOLATILE__TICK_SECONDS", "5"),
+        ("TEST_ZERO__TICK_SECONDS", "0"),
+        ("TEST_INVALID__TICK_SECONDS", "fast"),
+    ]);
+
+    let lookup = |variable: &str| {
+        environment.get(variable).map(|&value| value.to_owned())
+    };
+
+    let global = Duration::from_secs(60);
+
+    let volatile = Id::new("test-volatile".into())
+        .tick_duration(global, lookup)
+        .unwrap();
+
+    let stable = Id::new("TEST-STABLE".into())
+        .tick_duration(global, lookup)
+        .unwrap();
+
+    assert_eq!(volatile, Duration::from_secs(5));
+
+    assert_eq!(stable, global);
+
+    for protocol in ["TEST-ZERO", "TEST-INVALID"] {
+        assert!(Id::new(protocol.into())
+            .tick_duration(global, lookup)
+            .is_err());
+    }
+
+    let window = Duration::from_secs(118);
+
+    assert_eq!(feeds_within(volatile, window).await, 24);
+
+    assert_eq!(feeds_within(stable, window).await, 2);
+}
+
+#[test]
+fn test_env_var_name_mapping() {
+    assert_eq!(Id::env_var_name("OSMOSIS").unwrap(), "OSMOSIS");
+
+    assert_eq!(
+        Id::env_var_name("osmosis-osmosis-usdc_noble").unwrap(),
+        "OSMOSIS_OSMOSIS_USDC_NOBLE",
+    );
+
+    assert!(Id::env_var_name("OSMOSIS USDC").is_err());
+
+    assert!(Id::env_var_name("1OSMOSIS").is_err());
+
+    assert!(Id::network_var("Neutron Mainnet".into(), "__NODE_GRPC").is_err());
+
+    assert_eq!(
+        Id::network_var("NeutronMainnet".into(), "__NODE_GRPC").unwrap(),
+        "NEUTRON_MAINNET__NODE_GRPC",
+    );
+
+    assert_eq!(
+        Id::dex_node_grpc_headers_var("NeutronMainnet".into()).unwrap(),
+        "DEX_NEUTRON_MAINNET_HEADER_",
+    );
+}
+
+#[test]
+fn test_uninitialized_oracle_deferred() {
+    use tonic::Status;
+
+    let uninitialized = Error::new(Status::unknown(
+        "query wasm contract failed: no such contract: nolus1oracle",
+    ))
+    .context("Failed to run query against contract!");
+
+    assert!(Id::defer_if_oracle_not_found(uninitialized)
+        .is::<application_defined::NotReady>());
+
+    let unavailable = Error::new(Status::unavailable("connection refused"))
+        .context("Failed to run query against contract!");
+
+    assert!(!Id::defer_if_oracle_not_found(unavailable)
+        .is::<application_defined::NotReady>());
+}
@@ -1,29 +1,59 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Result;
 use cosmrs::Gas;
 
 use chain_ops::{
     channel::unbounded,
+    contract::Admin as AdminContract,
     node,
     task::{
-        application_defined, Runnable, RunnableState, TimeBasedExpiration,
-        TxPackage,
+        application_defined, protocol_watcher, Runnable, RunnableState,
+        TimeBasedExpiration, TxPackage,
     },
     tx::ExecuteTemplate,
 };
 
 use crate::{oracle::Oracle, providers};
 
-use self::provider::Provider;
+use self::{
+    dex_watch::DexWatch, feed_dedup::FeedDedup, feed_sequence::FeedSequence,
+    feed_verification::FeedVerification, feeder_rotation::FeederRotation,
+    healthcheck::Healthcheck, poll_limit::PollLimit, price_cache::PriceCache,
+    price_floor::PriceFloors, price_transform::PriceTransforms,
+    provider::Provider,
+};
 
 pub use self::{
     context::ApplicationDefined as ApplicationDefinedContext, id::Id,
+    pair_routing::print_pair_routing,
 };
 
 mod context;
+mod dex_node_clients;
+mod dex_watch;
+mod empty_streak;
+mod feed_dedup;
+mod feed_sequence;
+mod feed_summary;
+mod feed_verification;
+mod feeder_registration;
+mod feeder_rotation;
+mod grace_period;
+mod healthcheck;
 mod id;
+mod pair_circuit;
+mod pair_routing;
+mod poll_limit;
+mod price_cache;
+mod price_floor;
+mod price_transform;
 mod provider;
+mod rejection;
 
 pub struct Task {
     base: Base,
@@ -66,6 +96,7 @@ struct Base {
     node_client: node::Client,
     oracle: Oracle,
     dex_node_client: node::Client,
+    healthcheck: Healthcheck,
     source: Arc<str>,
     duration_before_start: Duration,
     execute_template: ExecuteTemplate,
@@ -73,4 +104,81 @@ struct Base {
     timeout_duration: Duration,
     hard_gas_limit: Gas,
     transaction_tx: unbounded::Sender<TxPackage<TimeBasedExpiration>>,
+    price_cache: Option<PriceCache>,
+    feed_sequence: Option<FeedSequence>,
+    unhealthy_grace_period: Option<Duration>,
+    min_confirmations: u64,
+    max_pairs_per_feed: Option<NonZeroUsize>,
+    restart_on_decimal_digits_change: bool,
+    feed_dedup: Option<FeedDedup>,
+    prime_on_startup: bool,
+    feeder_rotation: Option<FeederRotation>,
+    price_transforms: PriceTransforms,
+    price_floors: PriceFloors,
+    skip_tick_while_unconfirmed: bool,
+    poll_limit: Option<PollLimit>,
+    max_consecutive_empty_ticks: Option<NonZeroU64>,
+    restart_on_empty_streak: bool,
+    pair_rejections_before_disabling: NonZeroU32,
+    admin_contract: AdminContract,
+    dex_watch: DexWatch,
+    feed_verification: Option<FeedVerification>,
+    protocol_readiness: protocol_watcher::Readiness,
+}
+
+#[cfg(test)]
+impl Base {
+    /// Base of a provider feeding the given currency pairs through nodes
+    /// which are never reached, handing transactions over to the given
+    /// channel.
+    fn stub(
+        currency_pairs: &[(&str, &str)],
+        transaction_tx: unbounded::Sender<TxPackage<TimeBasedExpiration>>,
+    ) -> Self {
+        let node_client = node::Client::connect_lazy("http://node.invalid");
+
+        Self {
+            protocol: "TEST".into(),
+            oracle: Oracle::stub(
+                node_client.clone().query_wasm(),
+                currency_pairs,
+            ),
+            dex_node_client: node_client.clone(),
+            healthcheck: healthcheck::Strategy::None.build(node_client.clone()),
+            source: "Dummy; Protocol=TEST".into(),
+            duration_before_start: Duration::ZERO,
+            execute_template: ExecuteTemplate::new(
+                "nolus1feeder".into(),
+                "nolus1oracle".into(),
+            ),
+            idle_duration: Duration::from_secs(10),
+            timeout_duration: Duration::from_secs(60),
+            hard_gas_limit: 1_000_000,
+            transaction_tx,
+            price_cache: None,
+            feed_sequence: None,
+            unhealthy_grace_period: None,
+            min_confirmations: 0,
+            max_pairs_per_feed: None,
+            restart_on_decimal_digits_change: false,
+            feed_dedup: None,
+            prime_on_startup: false,
+            feeder_rotation: None,
+            price_transforms: PriceTransforms::default(),
+            price_floors: PriceFloors::default(),
+            skip_tick_while_unconfirmed: false,
+            poll_limit: None,
+            max_consecutive_empty_ticks: None,
+            restart_on_empty_streak: false,
+            pair_rejections_before_disabling: NonZeroU32::MIN,
+            admin_contract: AdminContract::new(
+                node_client.clone().query_wasm(),
+                "nolus1admin".into(),
+            ),
+            dex_watch: DexWatch::new(chain_ops::contract::admin::Dex::Osmosis),
+            feed_verification: None,
+            protocol_readiness: protocol_watcher::Readiness::new(),
+            node_client,
+        }
+    }
 }
@@ -0,0 +1,165 @@
+use std::{collections::BTreeMap, num::NonZeroU32, time::Duration};
+
+use tokio::time::Instant;
+
+use crate::provider::CurrencyPair;
+
+/// Tracks consecutive oracle rejections per currency pair, temporarily
+/// disabling pairs which keep getting rejected so the remaining ones keep
+/// being fed.
+///
+/// Pairs are disabled after the configured count of consecutive rejections,
+/// which by default is the first one. Disabled pairs are re-enabled after an
+/// exponentially growing backoff. A re-enabled pair is disabled again on its
+/// first rejection, until it gets delivered successfully.
+#[must_use]
+pub(crate) struct PairCircuits {
+    rejections_before_disabling: NonZeroU32,
+    circuits: BTreeMap<CurrencyPair, Circuit>,
+}
+
+impl PairCircuits {
+    const INITIAL_BACKOFF: Duration = Duration::from_secs(60);
+
+    const MAX_BACKOFF: Duration = Duration::from_secs(3600);
+
+    #[inline]
+    pub const fn new(rejections_before_disabling: NonZeroU32) -> Self {
+        Self {
+            rejections_before_disabling,
+            circuits: BTreeMap::new(),
+        }
+    }
+
+    /// Records a rejection of the currency pair and returns the duration for
+    /// which it got disabled, if it did.
+    pub fn rejected(
+        &mut self,
+        currency_pair: CurrencyPair,
+    ) -> Option<Duration> {
+        let circuit = self.circuits.entry(currency_pair).or_default();
+
+        circuit.consecutive_rejections += 1;
+
+        if circuit.trips == 0
+            && circuit.consecutive_rejections
+                < self.rejections_before_disabling.get()
+        {
+            return None;
+        }
+
+        let backoff = Self::INITIAL_BACKOFF
+            .saturating_mul(1 << circuit.trips.min(16))
+            .min(Self::MAX_BACKOFF);
+
+        circuit.trips += 1;
+
+        circuit.consecutive_rejections = 0;
+
+        circuit.disabled_until = Some(Instant::now() + backoff);
+
+        Some(backoff)
+    }
+
+    /// Clears the rejection history of currency pairs which were part of a
+    /// successfully delivered feed.
+    pub fn delivered(&mut self, currency_pairs: &[CurrencyPair]) {
+        for currency_pair in currency_pairs {
+            _ = self.circuits.remove(currency_pair);
+        }
+    }
+
+    #[must_use]
+    pub fn is_disabled(&self, currency_pair: &CurrencyPair) -> bool {
+        self.circuits
+            .get(currency_pair)
+            .and_then(|circuit| circuit.disabled_until)
+            .is_some_and(|disabled_until| Instant::now() < disabled_until)
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&CurrencyPair) -> bool,
+    {
+        self.circuits.retain(|currency_pair, _| f(currency_pair));
+    }
+}
+
+#[derive(Default)]
+struct Circuit {
+    consecutive_rejections: u32,
+    trips: u32,
+    disabled_until: Option<Instant>,
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_repeatedly_rejected_pair_is_disabled_and_retried() {
+    use tokio::time::advance;
+
+    let rejected = CurrencyPair {
+        base: "NLS".into(),
+        quote: "USDC_NOBLE".into(),
+    };
+
+    let accepted = CurrencyPair {
+        base: "ATOM".into(),
+        quote: "USDC_NOBLE".into(),
+    };
+
+    let mut circuits = PairCircuits::new(NonZeroU32::new(3).unwrap());
+
+    assert_eq!(circuits.rejected(rejected.clone()), None);
+
+    assert_eq!(circuits.rejected(rejected.clone()), None);
+
+    assert!(!circuits.is_disabled(&rejected));
+
+    assert_eq!(
+        circuits.rejected(rejected.clone()),
+        Some(Duration::from_secs(60)),
+    );
+
+    assert!(circuits.is_disabled(&rejected));
+
+    assert!(!circuits.is_disabled(&accepted));
+
+    advance(Duration::from_secs(60)).await;
+
+    assert!(!circuits.is_disabled(&rejected));
+
+    assert_eq!(
+        circuits.rejected(rejected.clone()),
+        Some(Duration::from_secs(120)),
+    );
+
+    advance(Duration::from_secs(119)).await;
+
+    assert!(circuits.is_disabled(&rejected));
+
+    advance(Duration::from_secs(1)).await;
+
+    assert!(!circuits.is_disabled(&rejected));
+
+    circuits.delivered(&[rejected.clone(), accepted]);
+
+    assert_eq!(circuits.rejected(rejected.clone()), None);
+
+    assert!(!circuits.is_disabled(&rejected));
+}
+
+#[test]
+fn test_rejected_pair_disabled_immediately_by_default() {
+    let rejected = CurrencyPair {
+        base: "NLS".into(),
+        quote: "USDC_NOBLE".into(),
+    };
+
+    let mut circuits = PairCircuits::new(NonZeroU32::MIN);
+
+    assert_eq!(
+        circuits.rejected(rejected.clone()),
+        Some(Duration::from_secs(60)),
+    );
+
+    assert!(circuits.is_disabled(&rejected));
+}
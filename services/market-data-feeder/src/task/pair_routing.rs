@@ -0,0 +1,320 @@
+use std::{
+    collections::BTreeMap,
+    io::{stdout, Write},
+    time::Duration,
+};
+
+use anyhow::{Context as _, Result};
+
+use chain_ops::{
+    contract::admin::{Protocol, ProtocolContracts},
+    node,
+    supervisor::configuration,
+};
+
+use crate::{
+    oracle::{CurrencyPairsCache, Oracle},
+    provider::{self, CurrencyPair, PriceQueryResult},
+    providers,
+};
+
+use super::{context, id::Id, provider::Provider};
+
+/// Resolves the provider of the given protocol and prices a single pair
+/// through it, printing both the value returned by the DEX and the normalized
+/// price, without running the feeder.
+///
+/// Only the node clients, the oracle and the provider are constructed, so no
+/// signing key or feeder configuration is required.
+///
+/// The pair is written in the `BASE/QUOTE` format.
+pub async fn print_pair_routing(protocol: &str, pair: &str) -> Result<()> {
+    let pair = parse_pair(pair)?;
+
+    let node_client = configuration::Service::connect_node_client().await?;
+
+    let Protocol {
+        network,
+        dex,
+        contracts:
+            ProtocolContracts {
+                oracle: oracle_address,
+            },
+    } = configuration::Service::read_admin_contract(&node_client)?
+        .protocol(protocol)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to query protocol's information! Protocol={protocol}"
+            )
+        })?;
+
+    let dex_node_client = Id::connect_dex_node_client(network)
+        .await
+        .context("Failed to connect to DEX node!")?;
+
+    let oracle = Oracle::new(
+        node_client.query_wasm(),
+        oracle_address.clone(),
+        // Currencies are never updated, as only a single pair is priced.
+        Duration::MAX,
+        CurrencyPairsCache::new().for_oracle(&oracle_address),
+        Id::read_pair_filter(&dex)?,
+        None,
+        context::read_version_query_attempts()?,
+    )
+    .await
+    .context("Failed to construct oracle!")?;
+
+    let mut output = stdout();
+
+    match Id::construct_provider(dex)? {
+        providers::Provider::Astroport(provider) => {
+            resolve_and_print(
+                &Bound::new(&provider, &oracle, &dex_node_client),
+                &pair,
+                &mut output,
+                protocol,
+            )
+            .await
+        },
+        providers::Provider::Osmosis(provider) => {
+            resolve_and_print(
+                &Bound::new(&provider, &oracle, &dex_node_client),
+                &pair,
+                &mut output,
+                protocol,
+            )
+            .await
+        },
+    }
+}
+
+/// Pricing of a single pair, the same way the provider does it on each tick.
+trait PairRouting {
+    type Provider: provider::Provider;
+
+    type Message;
+
+    fn query_messages(&self) -> Result<BTreeMap<CurrencyPair, Self::Message>>;
+
+    async fn query_price(
+        &self,
+        pair: &CurrencyPair,
+        message: &Self::Message,
+    ) -> Result<PriceQueryResult>;
+}
+
+/// Provider bound to the protocol's oracle and DEX node.
+struct Bound<'r, P> {
+    provider: &'r P,
+    oracle: &'r Oracle,
+    dex_node_client: &'r node::Client,
+}
+
+impl<'r, P> Bound<'r, P> {
+    const fn new(
+        provider: &'r P,
+        oracle: &'r Oracle,
+        dex_node_client: &'r node::Client,
+    ) -> Self {
+        Self {
+            provider,
+            oracle,
+            dex_node_client,
+        }
+    }
+}
+
+impl<P> PairRouting for Bound<'_, P>
+where
+    P: provider::Provider,
+{
+    type Provider = P;
+
+    type Message = P::PriceQueryMessage;
+
+    fn query_messages(&self) -> Result<BTreeMap<CurrencyPair, Self::Message>> {
+        self.provider.price_query_messages(self.oracle)
+    }
+
+    async fn query_price(
+        &self,
+        pair: &CurrencyPair,
+        message: &Self::Message,
+    ) -> Result<PriceQueryResult> {
+        self.provider
+            .price_query(self.dex_node_client, pair, message)
+            .await
+    }
+}
+
+fn parse_pair(pair: &str) -> Result<CurrencyPair> {
+    pair.split_once('/')
+        .map(|(base, quote)| (base.trim(), quote.trim()))
+        .filter(|(base, quote)| !base.is_empty() && !quote.is_empty())
+        .map(|(base, quote)| CurrencyPair {
+            base: base.into(),
+            quote: quote.into(),
+        })
+        .with_context(|| {
+            format!(
+                r#"Currency pair "{pair}" isn't in the "BASE/QUOTE" format!"#
+            )
+        })
+}
+
+async fn resolve_and_print<R, W>(
+    routing: &R,
+    pair: &CurrencyPair,
+    output: &mut W,
+    protocol: &str,
+) -> Result<()>
+where
+    R: PairRouting,
+    W: Write,
+{
+    let query_message = take_query_message(
+        routing
+            .query_messages()
+            .context("Failed to construct price query messages!")?,
+        pair,
+    )?;
+
+    let price = routing
+        .query_price(pair, &query_message)
+        .await
+        .context("Failed to query price from DEX!")?;
+
+    print_price::<R::Provider, W>(output, protocol, &price)
+}
+
+fn take_query_message<T>(
+    mut query_messages: BTreeMap<CurrencyPair, T>,
+    pair: &CurrencyPair,
+) -> Result<T> {
+    query_messages.remove(pair).with_context(|| {
+        format!(
+            "Currency pair isn't supported by the protocol's oracle! \
+            Base={base}; Quote={quote}",
+            base = pair.base,
+            quote = pair.quote,
+        )
+    })
+}
+
+fn print_price<P, W>(
+    output: &mut W,
+    protocol: &str,
+    price: &PriceQueryResult,
+) -> Result<()>
+where
+    P: provider::Provider,
+    W: Write,
+{
+    let CurrencyPair { base, quote } = &price.pair;
+
+    writeln!(
+        output,
+        "Protocol: {protocol}\n\
+        Provider: {provider}\n\
+        Pair: {base}/{quote}\n\
+        Raw DEX value: {raw_dex_value}\n\
+        Normalized price: {normalized_price}",
+        provider = P::PROVIDER_NAME,
+        raw_dex_value = price.raw_dex_value,
+        normalized_price = Provider::<P>::pretty_formatted_price(
+            base,
+            &price.base,
+            quote,
+            &price.quote,
+        ),
+    )
+    .context("Failed to print resolved price!")
+}
+
+#[cfg(test)]
+struct StubRouting {
+    query_message: &'static str,
+    price: PriceQueryResult,
+}
+
+#[cfg(test)]
+impl PairRouting for StubRouting {
+    type Provider = super::provider::Dummy;
+
+    type Message = &'static str;
+
+    fn query_messages(&self) -> Result<BTreeMap<CurrencyPair, Self::Message>> {
+        Ok(BTreeMap::from([(
+            self.price.pair.clone(),
+            self.query_message,
+        )]))
+    }
+
+    async fn query_price(
+        &self,
+        pair: &CurrencyPair,
+        message: &Self::Message,
+    ) -> Result<PriceQueryResult> {
+        assert_eq!(pair, &self.price.pair);
+
+        assert_eq!(*message, self.query_message);
+
+        Ok(self.price.clone())
+    }
+}
+
+#[tokio::test]
+async fn test_resolved_price_printed() {
+    use crate::provider::{Amount, Decimal};
+
+    let pair = parse_pair("NLS/USDC_NOBLE").unwrap();
+
+    let routing = StubRouting {
+        query_message: "nls_usdc_noble",
+        price: PriceQueryResult {
+            pair: pair.clone(),
+            base: Amount::new(Decimal::new("100000000000000000".into(), 17)),
+            quote: Amount::new(Decimal::new("1811002280600015".into(), 17)),
+            raw_dex_value: "0.01811002280600015".into(),
+        },
+    };
+
+    let mut output = vec![];
+
+    resolve_and_print(&routing, &pair, &mut output, "TEST")
+        .await
+        .unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("Protocol: TEST\n"));
+
+    assert!(output.contains("Provider: Dummy\n"));
+
+    assert!(output.contains("Pair: NLS/USDC_NOBLE\n"));
+
+    assert!(output.contains("Raw DEX value: 0.01811002280600015\n"));
+
+    assert!(output.ends_with(
+        "Normalized price: 1.0 NLS ~ 0.01811002280600015 USDC_NOBLE\n",
+    ));
+
+    let mut output = vec![];
+
+    assert!(resolve_and_print(
+        &routing,
+        &parse_pair("NLS/OSMO").unwrap(),
+        &mut output,
+        "TEST",
+    )
+    .await
+    .is_err());
+
+    assert!(output.is_empty());
+
+    assert!(parse_pair("NLS").is_err());
+
+    assert!(parse_pair("NLS/").is_err());
+}
@@ -0,0 +1,79 @@
+use std::{future::Future, num::NonZeroUsize, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use tokio::sync::Semaphore;
+
+/// Caps how many delivered transactions are polled for concurrently, shared
+/// between all providers, so a burst of feeds doesn't flood the node with
+/// queries.
+///
+/// Excess polls wait for a slot to free up, in no particular order.
+#[derive(Clone)]
+#[must_use]
+pub(crate) struct PollLimit(Arc<Semaphore>);
+
+impl PollLimit {
+    pub fn new(limit: NonZeroUsize) -> Self {
+        Self(Arc::new(Semaphore::new(limit.get())))
+    }
+
+    pub async fn run<F>(&self, poll: F) -> Result<F::Output>
+    where
+        F: Future,
+    {
+        let _permit = self
+            .0
+            .acquire()
+            .await
+            .context("Delivered transactions polling limit closed!")?;
+
+        Ok(poll.await)
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_concurrent_polls_capped() {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use tokio::{task::JoinSet, time::sleep};
+
+    let poll_limit = PollLimit::new(NonZeroUsize::new(2).unwrap());
+
+    let running = Arc::new(AtomicUsize::new(0));
+
+    let max_running = Arc::new(AtomicUsize::new(0));
+
+    let mut polls = JoinSet::new();
+
+    for _ in 0..6 {
+        let poll_limit = poll_limit.clone();
+
+        let running = running.clone();
+
+        let max_running = max_running.clone();
+
+        _ = polls.spawn(async move {
+            poll_limit
+                .run(async {
+                    let now_running =
+                        running.fetch_add(1, Ordering::AcqRel) + 1;
+
+                    _ = max_running.fetch_max(now_running, Ordering::AcqRel);
+
+                    sleep(Duration::from_secs(1)).await;
+
+                    _ = running.fetch_sub(1, Ordering::AcqRel);
+                })
+                .await
+        });
+    }
+
+    while let Some(result) = polls.join_next().await {
+        result.unwrap().unwrap();
+    }
+
+    assert_eq!(max_running.load(Ordering::Acquire), 2);
+}
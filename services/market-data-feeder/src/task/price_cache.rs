@@ -0,0 +1,125 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use tokio::time::Instant;
+
+use super::provider::Price;
+
+/// Keeps the last successfully collected prices of each protocol for the
+/// lifetime of the process, so a restarted provider can feed them as stale
+/// warm-up values while its first fresh fetch is still in progress.
+///
+/// Prices older than the maximum age are never handed out, as feeding them
+/// would do more harm than skipping the warm-up.
+#[derive(Clone)]
+#[must_use]
+pub(crate) struct PriceCache {
+    max_age: Duration,
+    prices: Arc<Mutex<BTreeMap<Arc<str>, CachedPrices>>>,
+}
+
+impl PriceCache {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            prices: Arc::default(),
+        }
+    }
+
+    pub fn store(&self, protocol: Arc<str>, prices: &[Price]) {
+        _ = self
+            .prices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(
+                protocol,
+                CachedPrices {
+                    fetched_at: Instant::now(),
+                    prices: prices.into(),
+                },
+            );
+    }
+
+    #[must_use]
+    pub fn get(&self, protocol: &str) -> Option<Arc<[Price]>> {
+        self.prices
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(protocol)
+            .filter(|cached| cached.fetched_at.elapsed() <= self.max_age)
+            .map(|cached| cached.prices.clone())
+    }
+}
+
+struct CachedPrices {
+    fetched_at: Instant,
+    prices: Arc<[Price]>,
+}
+
+#[test]
+fn test_warm_start_fallback() {
+    use super::provider::Coin;
+
+    let cache = PriceCache::new(Duration::from_secs(60));
+
+    assert!(cache.get("OSMOSIS").is_none());
+
+    let prices = [Price {
+        amount: Coin {
+            amount: "1000000".into(),
+            ticker: "NLS".into(),
+        },
+        amount_quote: Coin {
+            amount: "18110".into(),
+            ticker: "USDC".into(),
+        },
+    }];
+
+    cache.store("OSMOSIS".into(), &prices);
+
+    let restarted_task_cache = cache.clone();
+
+    let cached = restarted_task_cache.get("OSMOSIS").unwrap();
+
+    assert_eq!(cached.len(), 1);
+
+    assert_eq!(cached[0].amount.amount, "1000000");
+
+    assert_eq!(&*cached[0].amount_quote.ticker, "USDC");
+
+    assert!(restarted_task_cache.get("NEUTRON").is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_expired_prices_refused() {
+    use tokio::time::advance;
+
+    use super::provider::Coin;
+
+    let cache = PriceCache::new(Duration::from_secs(60));
+
+    cache.store(
+        "OSMOSIS".into(),
+        &[Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: "NLS".into(),
+            },
+            amount_quote: Coin {
+                amount: "18110".into(),
+                ticker: "USDC".into(),
+            },
+        }],
+    );
+
+    advance(Duration::from_secs(60)).await;
+
+    assert!(cache.get("OSMOSIS").is_some());
+
+    advance(Duration::from_secs(1)).await;
+
+    assert!(cache.get("OSMOSIS").is_none());
+}
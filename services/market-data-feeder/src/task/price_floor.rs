@@ -0,0 +1,210 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{bail, Context as _, Result};
+
+use crate::provider::{CurrencyPair, PriceQueryResult};
+
+use super::price_transform::Fixed;
+
+/// Minimum prices of specific pairs, below which a collected price is deemed
+/// to have lost its precision, e.g. a tiny spot price truncated during
+/// normalization, so the pair is skipped instead of feeding a near-zero
+/// value.
+///
+/// Entries are written as `BASE/QUOTE:MIN_PRICE` and separated by commas.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct PriceFloors(BTreeMap<(Arc<str>, Arc<str>), Floor>);
+
+impl PriceFloors {
+    pub fn parse(floors: &str) -> Result<Self> {
+        floors
+            .split(',')
+            .map(str::trim)
+            .filter(|floor| !floor.is_empty())
+            .map(|floor| {
+                Self::parse_entry(floor).with_context(|| {
+                    format!(r#"Invalid price floor, "{floor}"!"#)
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the pair's floor, as configured, when the price falls below
+    /// it.
+    pub fn violated(&self, price: &PriceQueryResult) -> Result<Option<&str>> {
+        let CurrencyPair { base, quote } = &price.pair;
+
+        let Some(floor) = self.0.get(&(base.clone(), quote.clone())) else {
+            return Ok(None);
+        };
+
+        let base_amount: u128 = price
+            .base
+            .as_inner()
+            .amount()
+            .parse()
+            .context("Failed to parse price's base amount!")?;
+
+        let quote_amount: u128 = price
+            .quote
+            .as_inner()
+            .amount()
+            .parse()
+            .context("Failed to parse price's quote amount!")?;
+
+        let below = floor.exceeds(
+            base_amount,
+            price.base.as_inner().decimal_places().into(),
+            quote_amount,
+            price.quote.as_inner().decimal_places().into(),
+        );
+
+        Ok(below.then_some(&*floor.configured))
+    }
+
+    fn parse_entry(floor: &str) -> Result<((Arc<str>, Arc<str>), Floor)> {
+        let (pair, min_price) =
+            floor.split_once(':').context("Minimum price is missing!")?;
+
+        let (base, quote) = pair
+            .split_once('/')
+            .map(|(base, quote)| (base.trim(), quote.trim()))
+            .filter(|(base, quote)| !base.is_empty() && !quote.is_empty())
+            .context(r#"Currency pair isn't in the "BASE/QUOTE" format!"#)?;
+
+        let min_price = min_price.trim();
+
+        let value = Fixed::parse(min_price)
+            .context("Failed to parse minimum price!")?;
+
+        if value.negative || value.mantissa == 0 {
+            bail!("Minimum price has to be positive!");
+        }
+
+        Ok((
+            (base.into(), quote.into()),
+            Floor {
+                value,
+                configured: min_price.into(),
+            },
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Floor {
+    value: Fixed,
+    configured: Box<str>,
+}
+
+impl Floor {
+    /// Checks whether the floor is greater than the price, with the price
+    /// being `quote / base`.
+    ///
+    /// Both sides are brought to a common scale, treating a side which
+    /// overflows while scaling as the greater one.
+    fn exceeds(
+        &self,
+        mut base: u128,
+        base_decimal_places: u32,
+        quote: u128,
+        quote_decimal_places: u32,
+    ) -> bool {
+        let mut base_exponent = i64::from(base_decimal_places);
+
+        while base != 0 && base % 10 == 0 {
+            base /= 10;
+
+            base_exponent -= 1;
+        }
+
+        // price < floor
+        //   <=> quote * 10^(base_dp + floor_dp) < floor * base * 10^quote_dp
+        let price_exponent =
+            base_exponent + i64::from(self.value.decimal_places);
+
+        let floor_exponent = i64::from(quote_decimal_places);
+
+        let common_exponent = price_exponent.min(floor_exponent);
+
+        let scaled = |value: Option<u128>, exponent: i64| {
+            u32::try_from(exponent - common_exponent)
+                .ok()
+                .and_then(|exponent| 10_u128.checked_pow(exponent))
+                .and_then(|factor| value?.checked_mul(factor))
+        };
+
+        let price = scaled(Some(quote), price_exponent);
+
+        let floor =
+            scaled(self.value.mantissa.checked_mul(base), floor_exponent);
+
+        match (price, floor) {
+            (Some(price), Some(floor)) => price < floor,
+            (Some(_), None) => true,
+            (None, _) => false,
+        }
+    }
+}
+
+#[test]
+fn test_price_floors() {
+    use crate::provider::{Amount, Decimal};
+
+    fn price(base: &str, quote: &str, quote_amount: &str) -> PriceQueryResult {
+        PriceQueryResult {
+            pair: CurrencyPair {
+                base: base.into(),
+                quote: quote.into(),
+            },
+            base: Amount::new(Decimal::new("1000000".into(), 6)),
+            quote: Amount::new(Decimal::new(quote_amount.into(), 12)),
+            raw_dex_value: quote_amount.into(),
+        }
+    }
+
+    let floors =
+        PriceFloors::parse("SHIB/USDC:0.00001, PEPE/USDC:0.000000001").unwrap();
+
+    assert_eq!(
+        floors.violated(&price("SHIB", "USDC", "9999999")).unwrap(),
+        Some("0.00001"),
+    );
+
+    assert_eq!(
+        floors.violated(&price("SHIB", "USDC", "10000000")).unwrap(),
+        None,
+    );
+
+    assert_eq!(
+        floors.violated(&price("SHIB", "USDC", "10000001")).unwrap(),
+        None,
+    );
+
+    assert_eq!(
+        floors.violated(&price("PEPE", "USDC", "999")).unwrap(),
+        Some("0.000000001"),
+    );
+
+    assert_eq!(
+        floors.violated(&price("PEPE", "USDC", "1001")).unwrap(),
+        None,
+    );
+
+    assert_eq!(floors.violated(&price("NLS", "USDC", "1")).unwrap(), None);
+
+    assert!(PriceFloors::parse("SHIB/USDC:0").is_err());
+
+    assert!(PriceFloors::parse("SHIB/USDC:-0.1").is_err());
+
+    assert!(PriceFloors::parse("SHIB/USDC").is_err());
+
+    assert!(PriceFloors::parse("").unwrap().is_empty());
+}
@@ -0,0 +1,332 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::{bail, Context as _, Result};
+
+use super::provider::Price;
+
+/// Linear transforms applied to the prices of specific pairs before they are
+/// fed, e.g. for wrapped tokens pegged to another asset or basket weights.
+///
+/// Entries are written as `BASE/QUOTE:SCALE[:OFFSET]` and separated by
+/// commas, with the transformed price being `SCALE * price + OFFSET`. The
+/// offset is given in whole quote units per whole base unit, the same way
+/// price floors are.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub(crate) struct PriceTransforms(BTreeMap<(Arc<str>, Arc<str>), Transform>);
+
+impl PriceTransforms {
+    pub fn parse(transforms: &str) -> Result<Self> {
+        transforms
+            .split(',')
+            .map(str::trim)
+            .filter(|transform| !transform.is_empty())
+            .map(|transform| {
+                Self::parse_entry(transform).with_context(|| {
+                    format!(r#"Invalid price transform, "{transform}"!"#)
+                })
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Applies the pair's transform, if any, to the price's quote amount.
+    ///
+    /// The currencies' decimal places are resolved through the given lookup,
+    /// as the offset has to be brought to the amounts' minimal denominations.
+    pub fn apply<F>(&self, price: &mut Price, decimal_places: F) -> Result<()>
+    where
+        F: Fn(&str) -> Result<u8>,
+    {
+        let Some(transform) = self.0.get(&(
+            price.amount.ticker.clone(),
+            price.amount_quote.ticker.clone(),
+        )) else {
+            return Ok(());
+        };
+
+        let base: u128 = price
+            .amount
+            .amount
+            .parse()
+            .context("Failed to parse price's base amount!")?;
+
+        let quote: u128 = price
+            .amount_quote
+            .amount
+            .parse()
+            .context("Failed to parse price's quote amount!")?;
+
+        let base_decimal_places = decimal_places(&price.amount.ticker)?;
+
+        let quote_decimal_places = decimal_places(&price.amount_quote.ticker)?;
+
+        price.amount_quote.amount = transform
+            .apply(
+                base,
+                base_decimal_places.into(),
+                quote,
+                quote_decimal_places.into(),
+            )
+            .with_context(|| {
+                format!(
+                    "Failed to transform price! Pair={}/{}",
+                    price.amount.ticker, price.amount_quote.ticker,
+                )
+            })?
+            .to_string();
+
+        Ok(())
+    }
+
+    fn parse_entry(
+        transform: &str,
+    ) -> Result<((Arc<str>, Arc<str>), Transform)> {
+        let mut parts = transform.split(':').map(str::trim);
+
+        let (base, quote) = parts
+            .next()
+            .and_then(|pair| pair.split_once('/'))
+            .filter(|(base, quote)| !base.is_empty() && !quote.is_empty())
+            .context(r#"Currency pair isn't in the "BASE/QUOTE" format!"#)?;
+
+        let scale = parts
+            .next()
+            .context("Scale is missing!")
+            .and_then(Fixed::parse)
+            .context("Failed to parse scale!")?;
+
+        if scale.negative || scale.mantissa == 0 {
+            bail!("Scale has to be positive!");
+        }
+
+        let offset = parts
+            .next()
+            .map(Fixed::parse)
+            .transpose()
+            .context("Failed to parse offset!")?
+            .unwrap_or(Fixed::ZERO);
+
+        if parts.next().is_some() {
+            bail!("Unexpected trailing components!");
+        }
+
+        Ok(((base.into(), quote.into()), Transform { scale, offset }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Transform {
+    scale: Fixed,
+    offset: Fixed,
+}
+
+impl Transform {
+    /// Returns the quote amount of the transformed price, keeping the base
+    /// amount unchanged.
+    ///
+    /// With the price being `quote / base`, the result is
+    /// `scale * quote + offset * base * 10^(quote_dp - base_dp)`.
+    fn apply(
+        self,
+        base: u128,
+        base_decimal_places: u32,
+        quote: u128,
+        quote_decimal_places: u32,
+    ) -> Result<u128> {
+        const OVERFLOW_ERROR: &str = "Transformed price overflows!";
+
+        let scale_exponent = -i64::from(self.scale.decimal_places);
+
+        let offset_exponent = if self.offset.mantissa == 0 {
+            scale_exponent
+        } else {
+            i64::from(quote_decimal_places)
+                - i64::from(base_decimal_places)
+                - i64::from(self.offset.decimal_places)
+        };
+
+        let common_exponent = scale_exponent.min(offset_exponent);
+
+        let scaled = |value: Option<u128>, exponent: i64| {
+            u32::try_from(exponent - common_exponent)
+                .ok()
+                .and_then(|exponent| 10_u128.checked_pow(exponent))
+                .and_then(|factor| value?.checked_mul(factor))
+                .context(OVERFLOW_ERROR)
+        };
+
+        let denominator = scaled(Some(1), 0)?;
+
+        let scaled_quote =
+            scaled(quote.checked_mul(self.scale.mantissa), scale_exponent)?;
+
+        let offset =
+            scaled(base.checked_mul(self.offset.mantissa), offset_exponent)?;
+
+        let numerator = if self.offset.negative {
+            scaled_quote
+                .checked_sub(offset)
+                .filter(|&numerator| numerator != 0)
+        } else {
+            scaled_quote.checked_add(offset)
+        }
+        .context("Transformed price has to be positive!")?;
+
+        Some(numerator / denominator)
+            .filter(|&quote| quote != 0)
+            .context("Transformed price rounds down to zero!")
+    }
+}
+
+/// Signed decimal number with a fixed count of fractional digits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Fixed {
+    pub negative: bool,
+    pub mantissa: u128,
+    pub decimal_places: u32,
+}
+
+impl Fixed {
+    const ZERO: Self = Self {
+        negative: false,
+        mantissa: 0,
+        decimal_places: 0,
+    };
+
+    /// Greatest count of decimal places whose denominator fits in the
+    /// mantissa's type.
+    const MAX_DECIMAL_PLACES: u32 = u128::MAX.ilog10();
+
+    pub fn parse(number: &str) -> Result<Self> {
+        let (negative, number) = number
+            .strip_prefix('-')
+            .map_or((false, number), |number| (true, number));
+
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+
+        if whole.is_empty()
+            || !whole
+                .chars()
+                .chain(fraction.chars())
+                .all(|ch| ch.is_ascii_digit())
+        {
+            bail!(r#"Invalid decimal number, "{number}"!"#);
+        }
+
+        Ok(Self {
+            negative,
+            mantissa: format!("{whole}{fraction}")
+                .parse()
+                .context("Decimal number is out of range!")?,
+            decimal_places: fraction
+                .len()
+                .try_into()
+                .ok()
+                .filter(|&decimal_places| {
+                    decimal_places <= Self::MAX_DECIMAL_PLACES
+                })
+                .context("Decimal number has too many decimal places!")?,
+        })
+    }
+}
+
+#[test]
+fn test_price_transforms() {
+    use super::provider::Coin;
+
+    fn price(base: &str, quote: &str, quote_amount: &str) -> Price {
+        Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: base.into(),
+            },
+            amount_quote: Coin {
+                amount: quote_amount.into(),
+                ticker: quote.into(),
+            },
+        }
+    }
+
+    let transforms = PriceTransforms::parse(
+        "WBTC/USDC:1.5, ST_ATOM/ATOM:1:0.25, NLS/USDC:2:-0.5",
+    )
+    .unwrap();
+
+    let decimal_places = |ticker: &str| match ticker {
+        "WBTC" => Ok(8),
+        "NLS" | "USDC" | "ST_ATOM" | "ATOM" => Ok(6),
+        _ => bail!("Unknown currency!"),
+    };
+
+    let transformed = |mut price| {
+        transforms
+            .apply(&mut price, decimal_places)
+            .map(|()| price.amount_quote.amount)
+    };
+
+    assert_eq!(
+        transformed(price("WBTC", "USDC", "2000000")).unwrap(),
+        "3000000",
+    );
+
+    assert_eq!(
+        transformed(price("ST_ATOM", "ATOM", "2000000")).unwrap(),
+        "2250000",
+    );
+
+    assert_eq!(
+        transformed(price("NLS", "USDC", "2000000")).unwrap(),
+        "3500000",
+    );
+
+    assert!(transformed(price("NLS", "USDC", "250000")).is_err());
+
+    assert!(PriceTransforms::parse("NLS/USDC:0.000001")
+        .unwrap()
+        .apply(&mut price("NLS", "USDC", "100000"), decimal_places)
+        .is_err());
+
+    let transforms =
+        PriceTransforms::parse("WBTC/USDC:1:100, NLS/WBTC:1:0.00000001")
+            .unwrap();
+
+    let transformed = |mut price| {
+        transforms
+            .apply(&mut price, decimal_places)
+            .map(|()| price.amount_quote.amount)
+    };
+
+    // 0.01 WBTC at 60000 USDC, offset by 100 USDC per WBTC.
+    assert_eq!(
+        transformed(price("WBTC", "USDC", "600000000")).unwrap(),
+        "601000000",
+    );
+
+    // 1 NLS at 0.0000005 WBTC, offset by 0.00000001 WBTC per NLS.
+    assert_eq!(transformed(price("NLS", "WBTC", "50")).unwrap(), "51");
+
+    assert!(transformed(price("WBTC", "OSMO", "2000000")).is_ok());
+
+    assert!(Fixed::parse("0.000000000000000000000000000000000000001").is_err());
+
+    assert_eq!(
+        transformed(price("OSMO", "USDC", "2000000")).unwrap(),
+        "2000000",
+    );
+
+    assert!(PriceTransforms::parse("WBTC/USDC:-1.5").is_err());
+
+    assert!(PriceTransforms::parse("WBTC/USDC:0").is_err());
+
+    assert!(PriceTransforms::parse("WBTC/USDC").is_err());
+
+    assert!(PriceTransforms::parse("WBTC:1.5").is_err());
+
+    assert!(PriceTransforms::parse("").unwrap().is_empty());
+}
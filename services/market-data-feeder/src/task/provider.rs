@@ -1,30 +1,50 @@
 use std::{
-    collections::BTreeMap, convert::identity, future::Future, sync::Arc,
+    collections::{BTreeMap, BTreeSet},
+    convert::identity,
+    future::Future,
+    mem,
+    num::NonZeroUsize,
+    slice::Chunks,
+    sync::Arc,
+    time::{Duration, SystemTime},
 };
 
 use anyhow::{bail, Context as _, Result};
-use cosmrs::{
-    proto::cosmos::base::abci::v1beta1::TxResponse,
-    tendermint::abci::Code as TxCode, Gas,
-};
+use cosmrs::{tendermint::abci::Code as TxCode, Gas};
 use serde::Serialize;
 use tokio::{
     select, spawn,
     sync::oneshot,
-    task::{AbortHandle, JoinSet},
+    task::{AbortHandle, JoinHandle, JoinSet},
     time::{interval, sleep, timeout, Instant, MissedTickBehavior},
 };
+use tracing::Instrument as _;
 
 use chain_ops::{
     defer::Defer,
-    task::{RunnableState, TimeBasedExpiration, TxPackage},
+    task::{
+        CorrelationId, RunnableState, TimeBasedExpiration, TxFeedback,
+        TxPackage,
+    },
     task_set::TaskSet,
     tx,
 };
 
 use crate::{
-    provider::{self, Amount, Base, CurrencyPair, Decimal, Quote},
-    task,
+    oracle::{CurrencyPairs, DecimalDigitsChange},
+    provider::{
+        self, Amount, Base, CurrencyPair, Decimal, PriceQueryResult, Quote,
+    },
+    task::{
+        self,
+        empty_streak::EmptyStreak,
+        feed_summary::FeedSummary,
+        feeder_rotation::{Decision, FeederRotation},
+        grace_period::GracePeriod,
+        healthcheck::{Health, HealthcheckStrategy as _},
+        pair_circuit::PairCircuits,
+        rejection::Rejection,
+    },
 };
 
 macro_rules! log {
@@ -52,6 +72,12 @@ where
 {
     base: task::Base,
     provider: P,
+    pair_circuits: PairCircuits,
+    feed_summary: FeedSummary,
+    empty_ticks: u64,
+    empty_streak: EmptyStreak,
+    gas_accuracy: tx::GasAccuracy,
+    deduplicate_next_feed: bool,
 }
 
 impl<P> Provider<P>
@@ -59,7 +85,20 @@ where
     P: provider::Provider,
 {
     pub const fn new(base: task::Base, provider: P) -> Self {
-        Self { base, provider }
+        let empty_streak = EmptyStreak::new(base.max_consecutive_empty_ticks);
+
+        Self {
+            base,
+            provider,
+            pair_circuits: PairCircuits::new(
+                base.pair_rejections_before_disabling,
+            ),
+            feed_summary: FeedSummary::new(),
+            empty_ticks: 0,
+            empty_streak,
+            gas_accuracy: tx::GasAccuracy::new(),
+            deduplicate_next_feed: false,
+        }
     }
 
     pub async fn run(mut self, state: RunnableState) -> Result<()> {
@@ -71,9 +110,29 @@ where
         let mut price_collection_buffer =
             Vec::with_capacity(query_messages.len());
 
-        let mut dex_block_height = self.get_dex_block_height().await?;
+        if let Health::Unhealthy(error) = self.base.healthcheck.check().await? {
+            return Err(error.context("Dex node is unhealthy!"));
+        }
+
+        let _ready = self
+            .base
+            .protocol_readiness
+            .mark_ready(self.base.protocol.clone());
+
+        let mut fetch_delivered_set =
+            Defer::new(JoinSet::new(), JoinSet::abort_all);
 
         if matches!(state, RunnableState::New) {
+            log_with_context!(info![self.base.protocol, P](
+                pairs = ?query_messages
+                    .keys()
+                    .map(|CurrencyPair { base, quote }| {
+                        format!("{base}/{quote}")
+                    })
+                    .collect::<Vec<_>>(),
+                "Effective currency pairs.",
+            ));
+
             self.spawn_query_tasks(
                 &mut query_messages,
                 &mut queries_task_set,
@@ -82,11 +141,24 @@ where
             .await
             .context("Failed to spawn price querying tasks!")?;
 
-            self.initial_fetch_and_print(&mut queries_task_set).await?;
-        }
+            let prices =
+                self.initial_fetch_and_print(&mut queries_task_set).await?;
 
-        let mut fetch_delivered_set =
-            Defer::new(JoinSet::new(), JoinSet::abort_all);
+            let duration_before_start = self.base.duration_before_start;
+
+            prime_and_wait(
+                self.base.prime_on_startup.then_some(|| {
+                    log_with_context!(info![self.base.protocol, P](
+                        count = prices.len(),
+                        "Priming oracle with prices collected on startup.",
+                    ));
+
+                    self.feed_prices(&prices, 0, &mut fetch_delivered_set)
+                }),
+                duration_before_start,
+            )
+            .await?;
+        }
 
         let mut next_feed_interval = interval(self.base.idle_duration);
 
@@ -94,59 +166,120 @@ where
 
         let mut fallback_gas = 0;
 
+        let mut grace_period =
+            GracePeriod::new(self.base.unhealthy_grace_period);
+
+        let mut started_feeding = false;
+
+        if matches!(state, RunnableState::Restart) {
+            if let Some(prices) = self.cached_prices() {
+                log_with_context!(warn![self.base.protocol, P](
+                    count = prices.len(),
+                    "Feeding stale prices cached before restart while fresh \
+                    ones are being fetched.",
+                ));
+
+                self.feed_prices(
+                    &prices,
+                    fallback_gas,
+                    &mut fetch_delivered_set,
+                )?;
+            }
+
+            self.deduplicate_next_feed = true;
+        }
+
         loop {
             select! {
                 biased;
-                Some((currency_pair, result)) = queries_task_set.join_next(),
+                Some((_, result)) = queries_task_set.join_next(),
                 if !queries_task_set.is_empty() => {
                     self.handle_price_query_result(
                         &mut price_collection_buffer,
-                        currency_pair,
                         result
                             .context("Failed to join back price query task!")?,
                     );
 
-                    if queries_task_set.is_empty()
-                        && !price_collection_buffer.is_empty() {
-                        let _: AbortHandle = self.send_for_broadcast(
+                    if queries_task_set.is_empty() {
+                        if !price_collection_buffer.is_empty() {
+                            self.cache_prices(&price_collection_buffer);
+                        }
+
+                        self.feed_prices(
                             &price_collection_buffer,
                             fallback_gas,
-                        )
-                        .map(|feedback_response_rx| {
-                            self.fetch_delivered(feedback_response_rx)
-                        })
-                        .map(|future| fetch_delivered_set.spawn(future))?;
+                            &mut fetch_delivered_set,
+                        )?;
 
                         price_collection_buffer.clear();
+
+                        self.log_feed_summary();
                     }
                 },
                 Some(result) = fetch_delivered_set.join_next(),
                 if !fetch_delivered_set.is_empty() => {
-                    let result = result.context(
+                    let (fed_pairs, result) = result.context(
                         "Failed to join back delivered transaction fetching \
                         task!",
                     )?;
 
                     fallback_gas = self.handle_fetch_delivered_result(
                         fallback_gas,
+                        &fed_pairs,
                         result,
                     )?;
                 },
                 _ = next_feed_interval.tick(),
                 if queries_task_set.is_empty() => {
-                    let new_block_height = self.get_dex_block_height().await?;
-
-                    if dex_block_height >= new_block_height {
+                    if let Health::Unhealthy(error) =
+                        self.base.healthcheck.check().await? {
                         log_with_context!(error![self.base.protocol, P](
-                            last_recorded = dex_block_height,
-                            latest_reported = new_block_height,
-                            "Dex node's latest block height didn't increment!",
+                            ?error,
+                            "Dex node's healthcheck failed!",
                         ));
 
+                        if grace_period.failed() {
+                            bail!(
+                                "Dex node didn't recover within the grace \
+                                period!"
+                            );
+                        }
+
                         continue;
                     }
 
-                    dex_block_height = new_block_height;
+                    grace_period.succeeded();
+
+                    self.base
+                        .oracle
+                        .recheck_version()
+                        .await
+                        .context(
+                            "Failed to recheck oracle contract's version!",
+                        )?;
+
+                    if !self.rotation_allows_feed().await {
+                        continue;
+                    }
+
+                    if skip_tick_while_unconfirmed(
+                        self.base.skip_tick_while_unconfirmed,
+                        &fetch_delivered_set,
+                    ) {
+                        log_with_context!(info![self.base.protocol, P](
+                            unconfirmed = fetch_delivered_set.len(),
+                            "Previous feed isn't confirmed yet. Skipping \
+                            tick.",
+                        ));
+
+                        continue;
+                    }
+
+                    Self::log_started_feeding(
+                        &mut started_feeding,
+                        &self.base.protocol,
+                        query_messages.len(),
+                    );
 
                     self.spawn_query_tasks(
                         &mut query_messages,
@@ -160,21 +293,89 @@ where
         }
     }
 
-    async fn get_dex_block_height(&self) -> Result<u64> {
-        let mut query_tendermint =
-            self.base.dex_node_client.clone().query_tendermint();
+    /// Marks the transition from startup to actively feeding, once per start
+    /// of the provider, on its first regular tick.
+    fn log_started_feeding(
+        started_feeding: &mut bool,
+        protocol: &str,
+        pairs: usize,
+    ) {
+        if !*started_feeding {
+            *started_feeding = true;
+
+            log_with_context!(info![protocol, P](
+                pairs,
+                "Provider started feeding.",
+            ));
+        }
+    }
+
+    /// Checks whether this instance feeds on the current tick, when feeding
+    /// is rotated between multiple instances.
+    async fn rotation_allows_feed(&mut self) -> bool {
+        let Some(feeder_rotation) = self.base.feeder_rotation else {
+            return true;
+        };
+
+        let tick_index = FeederRotation::tick_index(
+            SystemTime::now(),
+            self.base.idle_duration,
+        );
+
+        match feeder_rotation
+            .decide(tick_index, self.base.oracle.has_stale_prices())
+            .await
+        {
+            Ok(Decision::Turn) => true,
+            Ok(Decision::StaleFallback) => {
+                log_with_context!(warn![self.base.protocol, P](
+                    tick_index,
+                    "Oracle has stale prices. Feeding outside of this \
+                    instance's turn.",
+                ));
 
-        if query_tendermint.syncing().await? {
-            bail!("Dex node reported in with syncing status!");
+                true
+            },
+            Ok(Decision::Skip) => {
+                log_with_context!(debug![self.base.protocol, P](
+                    tick_index,
+                    "Not this instance's turn. Skipping feed.",
+                ));
+
+                false
+            },
+            Err(error) => {
+                log_with_context!(error![self.base.protocol, P](
+                    ?error,
+                    tick_index,
+                    "Failed to check oracle's prices for staleness! Feeding \
+                    outside of this instance's turn.",
+                ));
+
+                true
+            },
         }
+    }
 
-        query_tendermint.get_latest_block().await
+    fn cached_prices(&self) -> Option<Arc<[Price]>> {
+        self.base
+            .price_cache
+            .as_ref()
+            .and_then(|price_cache| price_cache.get(&self.base.protocol))
+    }
+
+    fn cache_prices(&self, prices: &[Price]) {
+        if let Some(price_cache) = &self.base.price_cache {
+            price_cache.store(self.base.protocol.clone(), prices);
+        }
     }
 
+    /// Collects and prints the prices fetched by the initial batch of
+    /// queries, returning them so they can be used for priming.
     async fn initial_fetch_and_print(
         &mut self,
         queries_task_set: &mut QueryTasksSet,
-    ) -> Result<()> {
+    ) -> Result<Vec<Price>> {
         let mut prices = vec![];
 
         let mut fetch_errors = vec![];
@@ -187,7 +388,9 @@ where
 
             match result {
                 Ok(price) => {
-                    prices.push((currency_pair, price));
+                    if self.above_price_floor(&price) {
+                        prices.push(price);
+                    }
                 },
                 Err(error) => {
                     fetch_errors.push((currency_pair, error));
@@ -195,16 +398,28 @@ where
             }
         }
 
-        self.log_prices_and_errors(prices, fetch_errors);
+        let collected = prices
+            .iter()
+            .map(|price| Price {
+                amount: Coin {
+                    amount: price.base.as_inner().amount().into(),
+                    ticker: price.pair.base.clone(),
+                },
+                amount_quote: Coin {
+                    amount: price.quote.as_inner().amount().into(),
+                    ticker: price.pair.quote.clone(),
+                },
+            })
+            .collect();
 
-        sleep(self.base.duration_before_start).await;
+        self.log_prices_and_errors(prices, fetch_errors);
 
-        Ok(())
+        Ok(collected)
     }
 
     fn log_prices_and_errors(
         &self,
-        prices: Vec<QueryTaskResponse>,
+        prices: Vec<PriceQueryResult>,
         fetch_errors: Vec<(CurrencyPair, anyhow::Error)>,
     ) {
         log!(info_span!("pre-feeding-check")).in_scope(|| {
@@ -218,13 +433,17 @@ where
         });
     }
 
-    fn log_prices(&self, prices: Vec<QueryTaskResponse>) {
+    fn log_prices(&self, prices: Vec<PriceQueryResult>) {
         log_with_context!(info![self.base.protocol, P]("Collected prices:"));
 
-        for (CurrencyPair { base, quote }, (base_amount, quote_amount)) in
-            prices
+        for PriceQueryResult {
+            pair: CurrencyPair { base, quote },
+            base: base_amount,
+            quote: quote_amount,
+            raw_dex_value,
+        } in prices
         {
-            log!(debug!("{base_amount:?} / {quote_amount:?}"));
+            log!(debug!(raw_dex_value, "{base_amount:?} / {quote_amount:?}"));
 
             log!(info!(
                 "{}",
@@ -263,7 +482,7 @@ where
         log!(error!(""));
     }
 
-    fn pretty_formatted_price(
+    pub(super) fn pretty_formatted_price(
         base_ticker: &str,
         base_amount: &Amount<Base>,
         quote_ticker: &str,
@@ -314,14 +533,62 @@ where
         )
     }
 
+    fn log_pair_price(
+        protocol: &str,
+        &PriceQueryResult {
+            pair: CurrencyPair {
+                ref base,
+                ref quote,
+            },
+            base: ref base_amount,
+            quote: ref quote_amount,
+            ref raw_dex_value,
+        }: &PriceQueryResult,
+    ) {
+        if !log!(enabled!(::tracing::Level::DEBUG)) {
+            return;
+        }
+
+        log!(debug_span!("pair", %base, %quote)).in_scope(|| {
+            log_with_context!(debug![protocol, P](
+                raw_dex_value,
+                base_amount = base_amount.as_inner().amount(),
+                base_decimal_places = base_amount.as_inner().decimal_places(),
+                quote_amount = quote_amount.as_inner().amount(),
+                quote_decimal_places = quote_amount.as_inner().decimal_places(),
+                price = %Self::pretty_formatted_price(
+                    base,
+                    base_amount,
+                    quote,
+                    quote_amount,
+                ),
+                "Collected price.",
+            ));
+        });
+    }
+
     fn handle_price_query_result(
         &mut self,
         price_collection_buffer: &mut Vec<Price>,
-        CurrencyPair { base, quote }: CurrencyPair,
-        result: Result<(Amount<Base>, Amount<Quote>)>,
+        result: Result<PriceQueryResult>,
     ) {
         match result {
-            Ok((base_amount, quote_amount)) => {
+            Ok(price) => {
+                Self::log_pair_price(&self.base.protocol, &price);
+
+                if !self.above_price_floor(&price) {
+                    self.feed_summary.record_failed();
+
+                    return;
+                }
+
+                let PriceQueryResult {
+                    pair: CurrencyPair { base, quote },
+                    base: base_amount,
+                    quote: quote_amount,
+                    raw_dex_value: _,
+                } = price;
+
                 price_collection_buffer.push(Price {
                     amount: Coin {
                         amount: base_amount.into_inner().into_amount(),
@@ -338,57 +605,377 @@ where
                     ?error,
                     "Price fetching failed!",
                 ));
+
+                self.feed_summary.record_failed();
             },
         }
     }
 
+    /// Checks the price against the pair's configured floor, logging the
+    /// pairs which are skipped because of it.
+    fn above_price_floor(&self, price: &PriceQueryResult) -> bool {
+        if self.base.price_floors.is_empty() {
+            return true;
+        }
+
+        let CurrencyPair { base, quote } = &price.pair;
+
+        match self.base.price_floors.violated(price) {
+            Ok(None) => true,
+            Ok(Some(floor)) => {
+                log_with_context!(warn![self.base.protocol, P](
+                    %base,
+                    %quote,
+                    quote_amount = price.quote.as_inner().amount(),
+                    quote_decimal_places =
+                        price.quote.as_inner().decimal_places(),
+                    floor,
+                    "Price is below the pair's floor! Skipping pair.",
+                ));
+
+                false
+            },
+            Err(error) => {
+                log_with_context!(error![self.base.protocol, P](
+                    %base,
+                    %quote,
+                    ?error,
+                    "Failed to check price against the pair's floor! \
+                    Skipping pair.",
+                ));
+
+                false
+            },
+        }
+    }
+
+    fn feed_prices(
+        &mut self,
+        prices: &[Price],
+        fallback_gas: Gas,
+        fetch_delivered_set: &mut JoinSet<DeliveredFeed>,
+    ) -> Result<()> {
+        let deduplicate = mem::take(&mut self.deduplicate_next_feed);
+
+        if prices.is_empty() {
+            self.empty_ticks += 1;
+
+            let streak_reached = self.empty_streak.record();
+
+            log_with_context!(warn![self.base.protocol, P](
+                empty_ticks = self.empty_ticks,
+                consecutive_empty_ticks = self.empty_streak.consecutive(),
+                "No prices were collected during this tick. Skipping feed.",
+            ));
+
+            if streak_reached {
+                log_with_context!(error![self.base.protocol, P](
+                    consecutive_empty_ticks = self.empty_streak.consecutive(),
+                    "No prices were collected for too many consecutive \
+                    ticks! Upstream might be silently failing.",
+                ));
+
+                if self.base.restart_on_empty_streak {
+                    bail!(
+                        "No prices were collected for too many consecutive \
+                        ticks! Restarting task."
+                    );
+                }
+            }
+
+            return Ok(());
+        }
+
+        self.empty_streak.reset();
+
+        let mut supported_prices = Self::retain_supported_pairs(
+            &self.base.protocol,
+            prices,
+            self.base.oracle.currency_pairs(),
+        );
+
+        if !self.base.price_transforms.is_empty() {
+            supported_prices.retain_mut(|price| {
+                self.base
+                    .price_transforms
+                    .apply(price, |ticker| {
+                        self.base
+                            .oracle
+                            .currencies()
+                            .get(ticker)
+                            .map(|currency| currency.decimal_digits)
+                    })
+                    .inspect_err(|error| {
+                        log_with_context!(error![self.base.protocol, P](
+                            ?error,
+                            "Dropping price which couldn't be transformed!",
+                        ));
+                    })
+                    .is_ok()
+            });
+        }
+
+        let prices = &*supported_prices;
+
+        if prices.is_empty() {
+            return Ok(());
+        }
+
+        let unfed_prices;
+
+        let prices = if let Some(feed_dedup) =
+            self.base.feed_dedup.as_ref().filter(|_| deduplicate)
+        {
+            unfed_prices = feed_dedup.retain_unfed(
+                &task::Id::new(self.base.protocol.clone()),
+                prices,
+            );
+
+            if unfed_prices.len() != prices.len() {
+                log_with_context!(info![self.base.protocol, P](
+                    suppressed = prices.len() - unfed_prices.len(),
+                    "Suppressed prices identical to recently fed ones.",
+                ));
+            }
+
+            if unfed_prices.is_empty() {
+                return Ok(());
+            }
+
+            &unfed_prices
+        } else {
+            prices
+        };
+
+        let correlation_id = CorrelationId::next();
+
+        let _span = correlation_id.span().entered();
+
+        feed_batches(prices, self.base.max_pairs_per_feed).try_for_each(
+            |prices| {
+                self.feed_batch(
+                    prices,
+                    fallback_gas,
+                    fetch_delivered_set,
+                    correlation_id,
+                )
+            },
+        )
+    }
+
+    /// Drops prices of pairs which the oracle doesn't support, e.g. after the
+    /// DEX's pools were reconfigured, as the oracle would reject the whole
+    /// feed otherwise.
+    fn retain_supported_pairs(
+        protocol: &str,
+        prices: &[Price],
+        currency_pairs: &CurrencyPairs,
+    ) -> Vec<Price> {
+        prices
+            .iter()
+            .filter(|&price| {
+                let base = &price.amount.ticker;
+
+                let quote = &price.amount_quote.ticker;
+
+                let supported = currency_pairs
+                    .get(&(base.to_string(), quote.to_string()))
+                    .is_some();
+
+                if !supported {
+                    log_with_context!(warn![protocol, P](
+                        %base,
+                        %quote,
+                        "Dropping price of a pair which the oracle doesn't \
+                        support!",
+                    ));
+                }
+
+                supported
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn feed_batch(
+        &mut self,
+        prices: &[Price],
+        fallback_gas: Gas,
+        fetch_delivered_set: &mut JoinSet<DeliveredFeed>,
+        correlation_id: CorrelationId,
+    ) -> Result<()> {
+        let fed_pairs: Arc<[CurrencyPair]> = prices
+            .iter()
+            .map(|price| CurrencyPair {
+                base: price.amount.ticker.clone(),
+                quote: price.amount_quote.ticker.clone(),
+            })
+            .collect();
+
+        let feedback_response_rx =
+            self.send_for_broadcast(prices, fallback_gas, correlation_id)?;
+
+        self.feed_summary.record_fed(prices.len(), fallback_gas);
+
+        let _: AbortHandle = fetch_delivered_set.spawn(
+            self.fetch_delivered(
+                feedback_response_rx,
+                fed_pairs,
+                prices.into(),
+            )
+            .instrument(correlation_id.span()),
+        );
+
+        Ok(())
+    }
+
+    fn log_feed_summary(&self) {
+        let FeedSummary {
+            fed,
+            failed,
+            disabled,
+            fallback_gas,
+        } = self.feed_summary;
+
+        log_with_context!(info![self.base.protocol, P](
+            fed,
+            skipped = self.feed_summary.skipped(),
+            failed,
+            disabled,
+            fallback_gas,
+            "Feed tick summary.",
+        ));
+    }
+
     fn fetch_delivered(
         &self,
-        feedback_response_rx: oneshot::Receiver<TxResponse>,
-    ) -> impl Future<Output = Result<Option<TxResponse>>> + Send + 'static {
+        feedback_response_rx: oneshot::Receiver<TxFeedback>,
+        fed_pairs: Arc<[CurrencyPair]>,
+        prices: Arc<[Price]>,
+    ) -> impl Future<Output = DeliveredFeed> + Send + 'static {
         let mut query_tx = self.base.node_client.clone().query_tx();
 
+        let mut query_tendermint =
+            self.base.node_client.clone().query_tendermint();
+
         let source = self.base.source.clone();
 
         let timeout_duration = self.base.timeout_duration;
 
+        let min_confirmations = self.base.min_confirmations;
+
         let protocol = self.base.protocol.clone();
 
-        async move {
-            let response = feedback_response_rx.await?;
+        let poll_limit = self.base.poll_limit.clone();
 
-            if TxCode::from(response.code).is_ok() {
-                tx::fetch_delivered(
-                    &mut query_tx,
-                    &source,
+        let verification =
+            self.base.feed_verification.map(|feed_verification| {
+                (feed_verification, self.base.oracle.query_stable_prices())
+            });
+
+        let feed_dedup = self.base.feed_dedup.clone().map(|feed_dedup| {
+            (feed_dedup, task::Id::new(self.base.protocol.clone()))
+        });
+
+        let fed_at = Instant::now();
+
+        async move {
+            let result: Result<Option<TxFeedback>> = async {
+                let TxFeedback {
                     response,
-                    timeout_duration,
-                )
-                .await
-            } else {
-                log_with_context!(error![protocol, P](
-                    hash = %response.txhash,
-                    log = ?response.raw_log,
-                    "Transaction failed upon broadcast!",
-                ));
+                    simulated_gas,
+                } = feedback_response_rx.await?;
+
+                if TxCode::from(response.code).is_ok() {
+                    let poll = async {
+                        let delivered = tx::fetch_delivered(
+                            &mut query_tx,
+                            &source,
+                            response,
+                            timeout_duration,
+                        )
+                        .await?;
+
+                        if let Some(delivered) = &delivered {
+                            tx::await_confirmations(
+                                &mut query_tendermint,
+                                &source,
+                                delivered,
+                                min_confirmations,
+                                timeout_duration,
+                            )
+                            .await?;
+                        }
+
+                        anyhow::Ok(delivered.map(|delivered| {
+                            TxFeedback::new(delivered, simulated_gas)
+                        }))
+                    };
+
+                    if let Some(poll_limit) = &poll_limit {
+                        poll_limit.run(poll).await.and_then(identity)
+                    } else {
+                        poll.await
+                    }
+                } else {
+                    log_with_context!(error![protocol, P](
+                        hash = %response.txhash,
+                        log = ?response.raw_log,
+                        "Transaction failed upon broadcast!",
+                    ));
 
-                Ok(None)
+                    Ok(None)
+                }
+            }
+            .await;
+
+            if let Ok(Some(delivered)) = &result {
+                if TxCode::from(delivered.response.code).is_ok() {
+                    if let Some((feed_dedup, id)) = feed_dedup {
+                        feed_dedup.record_delivered(&id, &prices);
+                    }
+
+                    if let Some((feed_verification, query_prices)) =
+                        verification
+                    {
+                        let _: JoinHandle<usize> = spawn(
+                            async move {
+                                feed_verification
+                                    .verify(
+                                        &source,
+                                        &prices,
+                                        fed_at,
+                                        query_prices,
+                                    )
+                                    .await
+                            }
+                            .in_current_span(),
+                        );
+                    }
+                }
             }
+
+            (fed_pairs, result)
         }
     }
 
     fn send_for_broadcast(
         &mut self,
-        price_collection_buffer: &Vec<Price>,
+        price_collection_buffer: &[Price],
         fallback_gas: Gas,
-    ) -> Result<oneshot::Receiver<TxResponse>> {
+        correlation_id: CorrelationId,
+    ) -> Result<oneshot::Receiver<TxFeedback>> {
         self.base
             .execute_template
             .apply(&ExecuteMsg::FeedPrices {
                 prices: price_collection_buffer,
             })
             .context("Failed to construct transaction's body!")
-            .and_then(|tx_body| {
+            .and_then(|mut tx_body| {
+                if let Some(feed_sequence) = &mut self.base.feed_sequence {
+                    tx_body.memo = feed_sequence.next_memo();
+                }
+
                 let (feedback_sender, feedback_receiver) = oneshot::channel();
 
                 self.base
@@ -402,6 +989,7 @@ where
                         expiration: TimeBasedExpiration::new(
                             Instant::now() + self.base.timeout_duration,
                         ),
+                        correlation_id,
                     })
                     .map(|()| feedback_receiver)
                     .context("Failed to send transaction for broadcasting!")
@@ -409,32 +997,53 @@ where
     }
 
     fn handle_fetch_delivered_result(
-        &self,
+        &mut self,
         mut fallback_gas: Gas,
-        result: Result<Option<TxResponse>>,
+        fed_pairs: &[CurrencyPair],
+        result: Result<Option<TxFeedback>>,
     ) -> Result<Gas> {
         match result {
-            Ok(Some(response)) => 'transaction_result_available: {
+            Ok(Some(TxFeedback {
+                response,
+                simulated_gas,
+            })) => 'transaction_result_available: {
+                chain_ops::log_tx_response!(
+                    target: "provider",
+                    stage: "delivered",
+                    source: self.base.source,
+                    failure: info,
+                    response,
+                );
+
+                self.gas_accuracy.record_delivered(
+                    &self.base.source,
+                    simulated_gas,
+                    &response,
+                );
+
                 let code: TxCode = response.code.into();
 
                 if code.is_ok() {
-                    log_with_context!(info![self.base.protocol, P](
-                        hash = %response.txhash,
-                        height = %response.height,
-                        "Transaction included in block successfully.",
-                    ));
+                    self.pair_circuits.delivered(fed_pairs);
                 } else if code.value() == tx::OUT_OF_GAS_ERROR_CODE {
                     log_with_context!(error![self.base.protocol, P](
                         hash = %response.txhash,
-                        log = ?response.raw_log,
                         "Transaction failed, likely because it ran out of gas.",
                     ));
                 } else {
-                    log_with_context!(error![self.base.protocol, P](
-                        hash = %response.txhash,
-                        log = ?response.raw_log,
-                        "Transaction failed because of unknown reason!",
-                    ));
+                    let rejections =
+                        Rejection::parse(&response.raw_log, fed_pairs);
+
+                    if rejections.is_empty() {
+                        log_with_context!(error![self.base.protocol, P](
+                            hash = %response.txhash,
+                            "Transaction failed because of unknown reason!",
+                        ));
+                    } else {
+                        for rejection in rejections {
+                            self.handle_rejection(rejection);
+                        }
+                    }
 
                     break 'transaction_result_available;
                 }
@@ -472,31 +1081,169 @@ where
         Ok(fallback_gas)
     }
 
+    fn handle_rejection(&mut self, rejection: Rejection) {
+        match rejection {
+            Rejection::UnsupportedPair(currency_pair) => {
+                let base = currency_pair.base.clone();
+
+                let quote = currency_pair.quote.clone();
+
+                if let Some(backoff) =
+                    self.pair_circuits.rejected(currency_pair)
+                {
+                    log_with_context!(warn![self.base.protocol, P](
+                        %base,
+                        %quote,
+                        backoff = ?backoff,
+                        "Oracle repeatedly rejected currency pair as \
+                        unsupported! Disabling it temporarily.",
+                    ));
+                } else {
+                    log_with_context!(warn![self.base.protocol, P](
+                        %base,
+                        %quote,
+                        "Oracle rejected currency pair as unsupported!",
+                    ));
+                }
+            },
+        }
+    }
+
+    /// Logs which currency pairs got added or removed after the oracle's
+    /// supported pairs were refreshed at runtime.
+    fn log_pairs_diff<'r, I, J>(protocol: &str, previous: I, current: J)
+    where
+        I: IntoIterator<Item = &'r CurrencyPair>,
+        J: IntoIterator<Item = &'r CurrencyPair>,
+    {
+        let previous: BTreeSet<_> = previous.into_iter().collect();
+
+        let current: BTreeSet<_> = current.into_iter().collect();
+
+        let added: Vec<_> = current
+            .difference(&previous)
+            .map(|CurrencyPair { base, quote }| format!("{base}/{quote}"))
+            .collect();
+
+        let removed: Vec<_> = previous
+            .difference(&current)
+            .map(|CurrencyPair { base, quote }| format!("{base}/{quote}"))
+            .collect();
+
+        if !(added.is_empty() && removed.is_empty()) {
+            log_with_context!(info![protocol, P](
+                ?added,
+                ?removed,
+                "Supported currency pairs changed.",
+            ));
+        }
+    }
+
+    fn handle_decimal_digits_changes(
+        &self,
+        decimal_digits_changes: &[DecimalDigitsChange],
+    ) -> Result<()> {
+        for DecimalDigitsChange {
+            ticker,
+            previous,
+            current,
+        } in decimal_digits_changes
+        {
+            log_with_context!(warn![self.base.protocol, P](
+                %ticker,
+                previous,
+                current,
+                "Currency's decimal digits changed! Previously fed prices \
+                were scaled differently.",
+            ));
+        }
+
+        if self.base.restart_on_decimal_digits_change
+            && !decimal_digits_changes.is_empty()
+        {
+            bail!("Currencies' decimal digits changed! Restarting task.");
+        }
+
+        Ok(())
+    }
+
+    /// Fails when the protocol's DEX configuration changed in the admin
+    /// contract since the provider was created, e.g. Astroport's router
+    /// address, so the task is restarted with the updated one.
+    async fn check_dex_unchanged(&mut self) -> Result<()> {
+        let task::Base {
+            protocol,
+            admin_contract,
+            dex_watch,
+            ..
+        } = &mut self.base;
+
+        dex_watch
+            .check(async {
+                admin_contract
+                    .protocol(protocol)
+                    .await
+                    .map(|protocol| protocol.dex)
+            })
+            .await
+    }
+
     async fn spawn_query_tasks(
         &mut self,
         query_messages: &mut BTreeMap<CurrencyPair, P::PriceQueryMessage>,
         task_set: &mut QueryTasksSet,
         replacement_buffer: &mut Vec<Price>,
     ) -> Result<()> {
-        if self
+        if let Some(decimal_digits_changes) = self
             .base
             .oracle
             .update_currencies_and_pairs()
             .await
             .context("Failed to update currencies and currency pairs")?
         {
+            self.handle_decimal_digits_changes(&decimal_digits_changes)?;
+
+            self.check_dex_unchanged().await?;
+
+            let previous_pairs: Vec<CurrencyPair> =
+                query_messages.keys().cloned().collect();
+
             *query_messages =
                 self.provider.price_query_messages(&self.base.oracle)?;
 
+            Self::log_pairs_diff(
+                &self.base.protocol,
+                &previous_pairs,
+                query_messages.keys(),
+            );
+
             let additional_capacity = query_messages
                 .len()
                 .saturating_sub(replacement_buffer.len());
 
             replacement_buffer.reserve_exact(additional_capacity);
+
+            self.pair_circuits.retain(|currency_pair| {
+                query_messages.contains_key(currency_pair)
+            });
         }
 
+        self.feed_summary = FeedSummary::new();
+
+        self.feed_summary.record_disabled(
+            query_messages
+                .keys()
+                .filter(|&currency_pair| {
+                    self.pair_circuits.is_disabled(currency_pair)
+                })
+                .count(),
+        );
+
         query_messages
             .iter()
+            .filter(|&(currency_pair, _)| {
+                !self.pair_circuits.is_disabled(currency_pair)
+            })
             .for_each(self.spawn_query_task(task_set));
 
         Ok(())
@@ -509,9 +1256,11 @@ where
         let duration = self.base.idle_duration;
 
         move |(currency_pair, message)| {
-            let price_query = self
-                .provider
-                .price_query(&self.base.dex_node_client, message);
+            let price_query = self.provider.price_query(
+                &self.base.dex_node_client,
+                currency_pair,
+                message,
+            );
 
             task_set.add_handle(
                 currency_pair.clone(),
@@ -531,10 +1280,48 @@ where
     }
 }
 
-type QueryTasksSet =
-    TaskSet<CurrencyPair, Result<(Amount<Base>, Amount<Quote>)>>;
+type QueryTasksSet = TaskSet<CurrencyPair, Result<PriceQueryResult>>;
+
+type DeliveredFeed = (Arc<[CurrencyPair]>, Result<Option<TxFeedback>>);
 
-type QueryTaskResponse = (CurrencyPair, (Amount<Base>, Amount<Quote>));
+/// Performs the priming feed, when enabled, right away and then waits out the
+/// delay before the regular feeding cadence starts.
+async fn prime_and_wait<F>(
+    prime: Option<F>,
+    duration_before_start: Duration,
+) -> Result<()>
+where
+    F: FnOnce() -> Result<()>,
+{
+    if let Some(prime) = prime {
+        prime().context("Failed to prime oracle on startup!")?;
+    }
+
+    sleep(duration_before_start).await;
+
+    Ok(())
+}
+
+/// Checks whether the tick has to be skipped, when enabled, because of a
+/// previous feed which isn't confirmed yet, so there is at most one
+/// outstanding feed per provider.
+fn skip_tick_while_unconfirmed(
+    enabled: bool,
+    fetch_delivered_set: &JoinSet<DeliveredFeed>,
+) -> bool {
+    enabled && !fetch_delivered_set.is_empty()
+}
+
+/// Splits a tick's prices into separate feed transactions, each carrying at
+/// most the configured amount of pairs.
+fn feed_batches(
+    prices: &[Price],
+    max_pairs_per_feed: Option<NonZeroUsize>,
+) -> Chunks<'_, Price> {
+    prices.chunks(
+        max_pairs_per_feed.map_or(prices.len().max(1), NonZeroUsize::get),
+    )
+}
 
 #[derive(Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -542,55 +1329,100 @@ enum ExecuteMsg<'r> {
     FeedPrices { prices: &'r [Price] },
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
-struct Price {
-    amount: Coin,
-    amount_quote: Coin,
+pub(crate) struct Price {
+    pub amount: Coin,
+    pub amount_quote: Coin,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
-struct Coin {
-    amount: String,
-    ticker: Arc<str>,
+pub(crate) struct Coin {
+    pub amount: String,
+    pub ticker: Arc<str>,
 }
 
-#[test]
-fn test_pretty_price_formatting() {
-    use chain_ops::node;
+#[cfg(test)]
+pub(super) enum Never {}
 
-    use crate::oracle::Oracle;
+#[cfg(test)]
+pub(super) struct Dummy;
 
-    enum Never {}
+#[cfg(test)]
+impl provider::Provider for Dummy {
+    type PriceQueryMessage = Never;
+    const PROVIDER_NAME: &'static str = "Dummy";
 
-    struct Dummy;
-
-    impl provider::Provider for Dummy {
-        type PriceQueryMessage = Never;
-        const PROVIDER_NAME: &'static str = "Dummy";
+    fn price_query_messages(
+        &self,
+        _: &crate::oracle::Oracle,
+    ) -> Result<BTreeMap<CurrencyPair, Self::PriceQueryMessage>> {
+        Ok(BTreeMap::new())
+    }
 
-        fn price_query_messages(
-            &self,
-            _: &Oracle,
-        ) -> Result<BTreeMap<CurrencyPair, Self::PriceQueryMessage>> {
-            Ok(BTreeMap::new())
+    #[allow(clippy::manual_async_fn)]
+    fn price_query(
+        &self,
+        _: &chain_ops::node::Client,
+        _: &CurrencyPair,
+        _: &Self::PriceQueryMessage,
+    ) -> impl Future<Output = Result<PriceQueryResult>> + Send + 'static {
+        async move {
+            unreachable!();
         }
+    }
+}
 
-        #[allow(clippy::manual_async_fn)]
-        fn price_query(
-            &self,
-            _: &node::Client,
-            _: &Self::PriceQueryMessage,
-        ) -> impl Future<Output = Result<(Amount<Base>, Amount<Quote>)>>
-               + Send
-               + 'static {
-            async move {
-                unreachable!();
-            }
+/// Prices each pair supported by the oracle at one to one.
+#[cfg(test)]
+pub(super) struct OneToOne;
+
+#[cfg(test)]
+impl provider::Provider for OneToOne {
+    type PriceQueryMessage = ();
+    const PROVIDER_NAME: &'static str = "OneToOne";
+
+    fn price_query_messages(
+        &self,
+        oracle: &crate::oracle::Oracle,
+    ) -> Result<BTreeMap<CurrencyPair, Self::PriceQueryMessage>> {
+        Ok(oracle
+            .currency_pairs()
+            .keys()
+            .map(|(base, quote)| {
+                (
+                    CurrencyPair {
+                        base: base.as_str().into(),
+                        quote: quote.as_str().into(),
+                    },
+                    (),
+                )
+            })
+            .collect())
+    }
+
+    fn price_query(
+        &self,
+        _: &chain_ops::node::Client,
+        currency_pair: &CurrencyPair,
+        (): &Self::PriceQueryMessage,
+    ) -> impl Future<Output = Result<PriceQueryResult>> + Send + 'static {
+        let pair = currency_pair.clone();
+
+        async move {
+            Ok(PriceQueryResult {
+                pair,
+                base: Amount::new(Decimal::new("1000000".into(), 6)),
+                quote: Amount::new(Decimal::new("1000000".into(), 6)),
+                raw_dex_value: "1".into(),
+            })
         }
     }
+}
 
+#[test]
+fn test_pretty_price_formatting() {
     let base = Amount::new(Decimal::new("100000000000000000".into(), 17));
 
     let quote = Amount::new(Decimal::new("1811002280600015".into(), 17));
@@ -616,3 +1448,435 @@ fn test_pretty_price_formatting() {
         "1.0 WETH ~ 6724.7624153 OSMO"
     );
 }
+
+#[test]
+fn test_per_pair_price_logging() {
+    use chain_ops::testing::{CapturedSpan, LogCapture};
+    use tracing::subscriber;
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        Provider::<Dummy>::log_pair_price(
+            "OSMOSIS-OSMOSIS-USDC_NOBLE",
+            &PriceQueryResult {
+                pair: CurrencyPair {
+                    base: "NLS".into(),
+                    quote: "USDC_NOBLE".into(),
+                },
+                base: Amount::new(Decimal::new(
+                    "100000000000000000".into(),
+                    17,
+                )),
+                quote: Amount::new(Decimal::new("1811002280600015".into(), 17)),
+                raw_dex_value: "18110022806000150000000000000000000".into(),
+            },
+        );
+    });
+
+    assert_eq!(
+        capture.spans(),
+        [CapturedSpan {
+            name: "pair",
+            fields: [("base", "NLS".into()), ("quote", "USDC_NOBLE".into())]
+                .into(),
+        }],
+    );
+
+    let events = capture.events();
+
+    assert_eq!(events.len(), 1);
+
+    for (field, value) in [
+        ("provider", "Dummy"),
+        ("protocol", "OSMOSIS-OSMOSIS-USDC_NOBLE"),
+        ("raw_dex_value", "18110022806000150000000000000000000"),
+        ("base_amount", "100000000000000000"),
+        ("base_decimal_places", "17"),
+        ("quote_amount", "1811002280600015"),
+        ("quote_decimal_places", "17"),
+        ("price", "1.0 NLS ~ 0.01811002280600015 USDC_NOBLE"),
+    ] {
+        assert_eq!(events[0].field(field), Some(value), "Field: {field}");
+    }
+}
+
+#[test]
+fn test_feed_split_by_max_pairs() {
+    let prices: Vec<Price> = (0..25)
+        .map(|index| Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: format!("BASE_{index}").into(),
+            },
+            amount_quote: Coin {
+                amount: "2000000".into(),
+                ticker: "USDC".into(),
+            },
+        })
+        .collect();
+
+    let batch_sizes = |max_pairs_per_feed| {
+        feed_batches(&prices, NonZeroUsize::new(max_pairs_per_feed))
+            .map(<[Price]>::len)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(batch_sizes(10), [10, 10, 5]);
+
+    assert_eq!(batch_sizes(25), [25]);
+
+    assert_eq!(batch_sizes(0), [25]);
+
+    let batched_tickers: Vec<_> = feed_batches(&prices, NonZeroUsize::new(10))
+        .flatten()
+        .map(|price| price.amount.ticker.clone())
+        .collect();
+
+    assert!(batched_tickers
+        .iter()
+        .eq(prices.iter().map(|price| &price.amount.ticker)));
+}
+
+#[tokio::test]
+async fn test_empty_feed_sends_no_transactions() {
+    use chain_ops::channel::{self, Channel as _};
+
+    let (transaction_tx, transaction_rx) = channel::unbounded::Channel::new();
+
+    let mut provider = Provider::new(
+        task::Base::stub(&[("NLS", "USDC")], transaction_tx),
+        Dummy,
+    );
+
+    let mut fetch_delivered_set = JoinSet::new();
+
+    for max_pairs_per_feed in [None, NonZeroUsize::new(10)] {
+        provider.base.max_pairs_per_feed = max_pairs_per_feed;
+
+        provider
+            .feed_prices(&[], 0, &mut fetch_delivered_set)
+            .unwrap();
+    }
+
+    assert_eq!(provider.empty_ticks, 2);
+
+    assert!(fetch_delivered_set.is_empty());
+
+    assert!(transaction_rx.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_priming_feed_before_first_tick() {
+    use chain_ops::channel::{self, Channel as _};
+
+    async fn feeds_at(
+        prime_on_startup: bool,
+        checkpoints: &[Duration],
+    ) -> Vec<usize> {
+        let (transaction_tx, mut transaction_rx) =
+            channel::unbounded::Channel::new();
+
+        let mut base = task::Base::stub(&[("NLS", "USDC")], transaction_tx);
+
+        base.prime_on_startup = prime_on_startup;
+
+        base.duration_before_start = Duration::from_secs(30);
+
+        base.idle_duration = Duration::from_secs(60);
+
+        let start = Instant::now();
+
+        let provider =
+            spawn(Provider::new(base, OneToOne).run(RunnableState::New));
+
+        let mut packages = vec![];
+
+        let mut feeds = vec![];
+
+        for &checkpoint in checkpoints {
+            sleep(checkpoint - start.elapsed()).await;
+
+            // Packages are kept, so the feeds stay unconfirmed instead of
+            // failing.
+            while let Ok(package) = transaction_rx.try_recv() {
+                packages.push(package);
+            }
+
+            feeds.push(packages.len());
+        }
+
+        provider.abort();
+
+        _ = provider.await;
+
+        feeds
+    }
+
+    let checkpoints = [Duration::from_secs(1), Duration::from_secs(31)];
+
+    assert_eq!(feeds_at(true, &checkpoints).await, [1, 2]);
+
+    assert_eq!(feeds_at(false, &checkpoints).await, [0, 1]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_ticks_skipped_while_feed_unconfirmed() {
+    use chain_ops::channel::{self, Channel as _};
+
+    async fn feeds_within(
+        skip_tick_while_unconfirmed: bool,
+        window: Duration,
+    ) -> usize {
+        let (transaction_tx, mut transaction_rx) =
+            channel::unbounded::Channel::new();
+
+        let mut base = task::Base::stub(&[("NLS", "USDC")], transaction_tx);
+
+        base.skip_tick_while_unconfirmed = skip_tick_while_unconfirmed;
+
+        let provider =
+            spawn(Provider::new(base, OneToOne).run(RunnableState::New));
+
+        sleep(window).await;
+
+        provider.abort();
+
+        _ = provider.await;
+
+        // Transactions are never broadcast, thus no feed gets confirmed.
+        let mut feeds = 0;
+
+        while transaction_rx.try_recv().is_ok() {
+            feeds += 1;
+        }
+
+        feeds
+    }
+
+    let window = Duration::from_secs(55);
+
+    assert_eq!(feeds_within(true, window).await, 1);
+
+    assert_eq!(feeds_within(false, window).await, 6);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_restart_feeds_cached_prices_first() {
+    use chain_ops::channel::{self, Channel as _};
+
+    use task::{feed_dedup::FeedDedup, price_cache::PriceCache};
+
+    const STALE_QUOTE: &str = "18110";
+
+    fn price(quote_amount: &str) -> Price {
+        Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: "NLS".into(),
+            },
+            amount_quote: Coin {
+                amount: quote_amount.into(),
+                ticker: "USDC".into(),
+            },
+        }
+    }
+
+    async fn feeds(
+        state: RunnableState,
+        deduplicate: bool,
+    ) -> Vec<&'static str> {
+        let (transaction_tx, mut transaction_rx) =
+            channel::unbounded::Channel::new();
+
+        let mut base = task::Base::stub(&[("NLS", "USDC")], transaction_tx);
+
+        let price_cache = PriceCache::new(Duration::from_secs(60));
+
+        price_cache.store(base.protocol.clone(), &[price(STALE_QUOTE)]);
+
+        base.price_cache = Some(price_cache);
+
+        if deduplicate {
+            let feed_dedup = FeedDedup::new(Duration::from_secs(60));
+
+            // Same as the fresh price, as if delivered right before the
+            // restart.
+            feed_dedup.record_delivered(
+                &task::Id::new(base.protocol.clone()),
+                &[price("1000000")],
+            );
+
+            base.feed_dedup = Some(feed_dedup);
+        }
+
+        let provider = spawn(Provider::new(base, OneToOne).run(state));
+
+        sleep(Duration::from_secs(1)).await;
+
+        provider.abort();
+
+        _ = provider.await;
+
+        let mut feeds = vec![];
+
+        while let Ok(package) = transaction_rx.try_recv() {
+            let message =
+                String::from_utf8_lossy(&package.tx_body.messages[0].value)
+                    .into_owned();
+
+            feeds.push(
+                if message.contains(&format!(r#""amount":"{STALE_QUOTE}""#)) {
+                    "stale"
+                } else {
+                    "fresh"
+                },
+            );
+        }
+
+        feeds
+    }
+
+    assert_eq!(feeds(RunnableState::New, false).await, ["fresh"]);
+
+    assert_eq!(
+        feeds(RunnableState::Restart, false).await,
+        ["stale", "fresh"],
+    );
+
+    // The first fresh feed after a restart is deduplicated against the
+    // recently delivered prices.
+    assert_eq!(feeds(RunnableState::Restart, true).await, ["stale"]);
+
+    assert_eq!(feeds(RunnableState::New, true).await, ["fresh"]);
+}
+
+#[test]
+fn test_pairs_diff_logged() {
+    use chain_ops::testing::LogCapture;
+    use tracing::subscriber;
+
+    fn pair(base: &str, quote: &str) -> CurrencyPair {
+        CurrencyPair {
+            base: base.into(),
+            quote: quote.into(),
+        }
+    }
+
+    let previous = [pair("ATOM", "USDC"), pair("NLS", "USDC")];
+
+    let current = [pair("NLS", "USDC"), pair("OSMO", "USDC")];
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        Provider::<Dummy>::log_pairs_diff("OSMOSIS", &previous, &current);
+
+        Provider::<Dummy>::log_pairs_diff("OSMOSIS", &current, &current);
+    });
+
+    let events = capture.events();
+
+    assert_eq!(events.len(), 1);
+
+    for (field, value) in [
+        ("provider", "Dummy"),
+        ("protocol", "OSMOSIS"),
+        ("added", r#"["OSMO/USDC"]"#),
+        ("removed", r#"["ATOM/USDC"]"#),
+    ] {
+        assert_eq!(events[0].field(field), Some(value), "Field: {field}");
+    }
+}
+
+#[test]
+fn test_unexpected_pair_dropped() {
+    use chain_ops::testing::LogCapture;
+    use tracing::{subscriber, Level};
+
+    fn price(base: &str, quote: &str) -> Price {
+        Price {
+            amount: Coin {
+                amount: "1000000".into(),
+                ticker: base.into(),
+            },
+            amount_quote: Coin {
+                amount: "2000000".into(),
+                ticker: quote.into(),
+            },
+        }
+    }
+
+    let currency_pairs: CurrencyPairs = [
+        (("NLS".to_string(), "USDC".to_string()), 7),
+        (("ATOM".to_string(), "USDC".to_string()), 1),
+    ]
+    .into_iter()
+    .collect();
+
+    let capture = LogCapture::leak();
+
+    let retained = subscriber::with_default(capture, || {
+        Provider::<Dummy>::retain_supported_pairs(
+            "OSMOSIS",
+            &[
+                price("NLS", "USDC"),
+                price("OSMO", "USDC"),
+                price("ATOM", "USDC"),
+            ],
+            &currency_pairs,
+        )
+    });
+
+    assert_eq!(
+        retained
+            .iter()
+            .map(|price| &*price.amount.ticker)
+            .collect::<Vec<_>>(),
+        ["NLS", "ATOM"],
+    );
+
+    assert_eq!(capture.count(Level::WARN), 1);
+}
+
+#[test]
+fn test_started_feeding_logged_once() {
+    use chain_ops::testing::LogCapture;
+    use tracing::subscriber;
+
+    let capture = LogCapture::leak();
+
+    subscriber::with_default(capture, || {
+        let mut started_feeding = false;
+
+        for _ in 0..3 {
+            Provider::<Dummy>::log_started_feeding(
+                &mut started_feeding,
+                "OSMOSIS",
+                12,
+            );
+        }
+
+        let mut restarted_feeding = false;
+
+        Provider::<Dummy>::log_started_feeding(
+            &mut restarted_feeding,
+            "OSMOSIS",
+            12,
+        );
+    });
+
+    let events = capture.events();
+
+    assert_eq!(events.len(), 2);
+
+    for event in &events {
+        assert_eq!(event.field("provider"), Some("Dummy"));
+
+        assert_eq!(event.field("protocol"), Some("OSMOSIS"));
+
+        assert_eq!(event.field("pairs"), Some("12"));
+
+        assert_eq!(event.message(), "Provider started feeding.");
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use chain_ops::tx;
+
+use crate::provider::CurrencyPair;
+
+/// Reasons for which the oracle contract rejected a feed transaction, as
+/// reported back through the transaction's raw log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Rejection {
+    UnsupportedPair(CurrencyPair),
+}
+
+impl Rejection {
+    /// Returns the rejections which can be attributed to the fed currency
+    /// pairs. A pair is considered rejected when the raw log reports an
+    /// unsupported pair and mentions both of its tickers.
+    pub fn parse<'r, I>(raw_log: &str, fed_pairs: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = &'r CurrencyPair>,
+    {
+        if !tx::reports_unsupported_pair(raw_log) {
+            return vec![];
+        }
+
+        let tokens: BTreeSet<&str> = raw_log
+            .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '_'))
+            .filter(|token| !token.is_empty())
+            .collect();
+
+        fed_pairs
+            .into_iter()
+            .filter(|CurrencyPair { base, quote }| {
+                tokens.contains(&**base) && tokens.contains(&**quote)
+            })
+            .cloned()
+            .map(Self::UnsupportedPair)
+            .collect()
+    }
+}
+
+#[test]
+fn test_unsupported_pair_rejection() {
+    let fed_pairs = [
+        CurrencyPair {
+            base: "NLS".into(),
+            quote: "USDC_NOBLE".into(),
+        },
+        CurrencyPair {
+            base: "ATOM".into(),
+            quote: "USDC_NOBLE".into(),
+        },
+        CurrencyPair {
+            base: "OSMO".into(),
+            quote: "USDC".into(),
+        },
+    ];
+
+    assert_eq!(
+        Rejection::parse(
+            "failed to execute message; message index: 0: Unsupported \
+            currency pair: ATOM/USDC_NOBLE: execute wasm contract failed",
+            &fed_pairs,
+        ),
+        [Rejection::UnsupportedPair(fed_pairs[1].clone())],
+    );
+
+    assert_eq!(
+        Rejection::parse(
+            "failed to execute message; message index: 0: Unsupported \
+            currency pair: OSMO/USDC_NOBLE: execute wasm contract failed",
+            &fed_pairs,
+        ),
+        [],
+    );
+
+    assert_eq!(
+        Rejection::parse("out of gas in location: wasm contract", &fed_pairs),
+        [],
+    );
+}